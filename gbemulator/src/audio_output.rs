@@ -32,6 +32,8 @@ pub struct CpalAudioOutput {
     sync_sender: Option<Sender<EmulationSignal>>,
     event_loop: Arc<EventLoop>,
     current_stream_id: Option<StreamId>,
+    //Silence is played instead so the device still paces the emulation
+    muted: bool,
 }
 
 impl CpalAudioOutput {
@@ -48,36 +50,63 @@ impl CpalAudioOutput {
             sync_sender,
             event_loop: Arc::new(event_loop),
             current_stream_id: None,
+            muted: false,
         }
     }
 
-    pub fn get_default_device_name(&self) -> String {
-        self.host.default_output_device().unwrap().name().unwrap()
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
     }
 
-    pub fn start(&mut self, device_name: String) {
-        let mut device_list = self.host.devices().unwrap();
+    /// None if there is no sound device
+    pub fn get_default_device_name(&self) -> Option<String> {
+        self.host.default_output_device()?.name().ok()
+    }
 
-        let device = device_list
-            .find(|dev| dev.name().unwrap() == device_name)
-            .unwrap();
+    pub fn start(&mut self, device_name: String) -> Result<(), String> {
+        let mut device_list = match self.host.devices() {
+            Ok(device_list) => device_list,
+            Err(e) => return Err(format!("Could not list the sound devices: {}", e)),
+        };
+
+        let device = match device_list.find(|dev| dev.name().ok().as_ref() == Some(&device_name)) {
+            Some(device) => device,
+            None => return Err(format!("Sound device {} not found", device_name)),
+        };
+
+        let default_format = match device.default_output_format() {
+            Ok(format) => format,
+            Err(e) => {
+                return Err(format!(
+                    "Could not open sound device {}: {}",
+                    device_name, e
+                ))
+            }
+        };
 
-        let default_format = device.default_output_format().unwrap();
         let format = Format {
             channels: 2,
             sample_rate: default_format.sample_rate,
             data_type: default_format.data_type,
         };
 
-        self.sample_rate = Some(format.sample_rate.0);
+        let stream_id = match self.event_loop.build_output_stream(&device, &format) {
+            Ok(stream_id) => stream_id,
+            Err(e) => {
+                return Err(format!(
+                    "Could not open sound device {}: {}",
+                    device_name, e
+                ))
+            }
+        };
 
-        let stream_id = self
-            .event_loop
-            .build_output_stream(&device, &format)
-            .unwrap();
-        self.current_stream_id = Some(stream_id.clone());
+        if let Err(e) = self.event_loop.play_stream(stream_id.clone()) {
+            self.event_loop.destroy_stream(stream_id);
+            return Err(format!("Could not play sound on {}: {}", device_name, e));
+        }
 
-        self.event_loop.play_stream(stream_id).unwrap();
+        self.sample_rate = Some(format.sample_rate.0);
+        self.current_stream_id = Some(stream_id);
 
         let buffer = Arc::clone(&self.buffer);
         let sender = self.sync_sender.clone();
@@ -88,6 +117,8 @@ impl CpalAudioOutput {
             .name("audio".to_string())
             .spawn(move || event_loop_runner(event_loop, buffer, sender))
             .unwrap();
+
+        Ok(())
     }
 
     pub fn stop(&self) {
@@ -157,6 +188,7 @@ fn fill_buffer<T: Sample>(
 
 impl AudioOutput for CpalAudioOutput {
     fn output(&mut self, sample: (i16, i16)) {
+        let sample = if self.muted { (0, 0) } else { sample };
         let mut buffer = self.buffer.lock().unwrap();
         if buffer.data.len() < buffer.buffer_size {
             buffer.data.push(sample.0);
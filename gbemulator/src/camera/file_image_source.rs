@@ -0,0 +1,114 @@
+use lib_gbemulation::cartridge::camera::{ImageSource, SENSOR_HEIGHT, SENSOR_WIDTH};
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Feeds PNG files to the camera sensor. Either a single still image or a directory
+/// of numbered frames which are used one after another for each capture
+pub struct FileImageSource {
+    frames: Vec<PathBuf>,
+    next_frame: usize,
+}
+
+impl FileImageSource {
+    pub fn new(path: &str) -> Result<Self, String> {
+        let path = Path::new(path);
+
+        let frames = if path.is_dir() {
+            let entries = match fs::read_dir(path) {
+                Ok(entries) => entries,
+                Err(e) => return Err(format!("Could not read directory {:?}: {}", path, e)),
+            };
+
+            //Frames are ordered by their file name so they need to be numbered with leading zeros
+            let mut frames: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| is_png(path))
+                .collect();
+            frames.sort();
+            frames
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        if frames.is_empty() {
+            return Err(format!("No PNG files found in {:?}", path));
+        }
+
+        Ok(FileImageSource {
+            frames,
+            next_frame: 0,
+        })
+    }
+}
+
+impl ImageSource for FileImageSource {
+    fn capture(&mut self) -> Vec<u8> {
+        let frame = &self.frames[self.next_frame];
+        self.next_frame = (self.next_frame + 1) % self.frames.len();
+
+        match load_grayscale_image(frame) {
+            Ok(image) => image,
+            Err(e) => {
+                eprintln!("{}", e);
+                vec![0x80; SENSOR_WIDTH * SENSOR_HEIGHT]
+            }
+        }
+    }
+}
+
+fn is_png(path: &Path) -> bool {
+    match path.extension() {
+        Some(extension) => extension.eq_ignore_ascii_case("png"),
+        None => false,
+    }
+}
+
+/// Loads a PNG and scales it to the sensor size
+fn load_grayscale_image(path: &Path) -> Result<Vec<u8>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("Could not open image {:?}: {}", path, e)),
+    };
+
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+    let mut reader = match decoder.read_info() {
+        Ok(reader) => reader,
+        Err(e) => return Err(format!("Could not decode image {:?}: {}", path, e)),
+    };
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = match reader.next_frame(&mut buffer) {
+        Ok(info) => info,
+        Err(e) => return Err(format!("Could not decode image {:?}: {}", path, e)),
+    };
+
+    let channels = info.color_type.samples();
+    let width = info.width as usize;
+    let height = info.height as usize;
+
+    let mut image = Vec::with_capacity(SENSOR_WIDTH * SENSOR_HEIGHT);
+
+    for y in 0..SENSOR_HEIGHT {
+        for x in 0..SENSOR_WIDTH {
+            let source_x = x * width / SENSOR_WIDTH;
+            let source_y = y * height / SENSOR_HEIGHT;
+            let index = (source_y * info.line_size) + source_x * channels;
+
+            image.push(get_luminance(&buffer[index..index + channels]));
+        }
+    }
+
+    Ok(image)
+}
+
+fn get_luminance(pixel: &[u8]) -> u8 {
+    if pixel.len() < 3 {
+        return pixel[0];
+    }
+
+    (pixel[0] as f32 * 0.299 + pixel[1] as f32 * 0.587 + pixel[2] as f32 * 0.114) as u8
+}
@@ -0,0 +1 @@
+pub mod file_image_source;
@@ -0,0 +1,166 @@
+use lib_gbemulation::io::joypad::{Joypad, Key};
+use std::fs;
+use std::path::Path;
+
+/// Keys which are held in each frame. Every line contains a frame number followed by the keys
+/// which are held from that frame on until the next line, e.g. "120 start" or "121" to release
+/// all keys. Text after a # is a comment
+pub struct InputScript {
+    //Frame numbers with their keys, sorted by frame
+    changes: Vec<(u32, Vec<Key>)>,
+}
+
+impl InputScript {
+    pub fn empty() -> Self {
+        InputScript {
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(e) => Err(format!("Could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut changes: Vec<(u32, Vec<Key>)> = Vec::new();
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = line.split_whitespace();
+
+            let frame = match words.next() {
+                Some(frame) => match frame.parse() {
+                    Ok(frame) => frame,
+                    Err(_) => {
+                        return Err(format!(
+                            "Invalid frame {} in line {}",
+                            frame,
+                            line_number + 1
+                        ))
+                    }
+                },
+                None => continue,
+            };
+
+            if let Some((last_frame, _)) = changes.last() {
+                if frame <= *last_frame {
+                    return Err(format!(
+                        "Frame {} in line {} is not after the previous line",
+                        frame,
+                        line_number + 1
+                    ));
+                }
+            }
+
+            let mut keys = Vec::new();
+            for name in words {
+                match parse_key(name) {
+                    Some(key) => keys.push(key),
+                    None => {
+                        return Err(format!("Unknown key {} in line {}", name, line_number + 1))
+                    }
+                }
+            }
+
+            changes.push((frame, keys));
+        }
+
+        Ok(InputScript { changes })
+    }
+
+    /// Joypad with the keys which are held in the given frame
+    pub fn joypad(&self, frame: u32) -> Joypad {
+        let mut joypad = Joypad::new();
+
+        let keys = self
+            .changes
+            .iter()
+            .take_while(|(change_frame, _)| *change_frame <= frame)
+            .last();
+
+        if let Some((_, keys)) = keys {
+            for key in keys {
+                joypad.push_key(*key);
+            }
+        }
+
+        joypad
+    }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    match name.to_lowercase().as_str() {
+        "a" => Some(Key::A),
+        "b" => Some(Key::B),
+        "start" => Some(Key::Start),
+        "select" => Some(Key::Select),
+        "up" => Some(Key::Up),
+        "down" => Some(Key::Down),
+        "left" => Some(Key::Left),
+        "right" => Some(Key::Right),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Low nibble of the joypad register, a 0 bit is a pressed key
+    fn buttons(joypad: &Joypad) -> u8 {
+        joypad.read_input(0x10) & 0x0F
+    }
+
+    fn directions(joypad: &Joypad) -> u8 {
+        joypad.read_input(0x20) & 0x0F
+    }
+
+    #[test]
+    fn holds_keys_until_next_line() {
+        let script = InputScript::parse("10 start\n20 A right\n30\n").unwrap();
+
+        assert_eq!(buttons(&script.joypad(9)), 0x0F);
+        assert_eq!(buttons(&script.joypad(10)), 0x07);
+        assert_eq!(buttons(&script.joypad(19)), 0x07);
+        assert_eq!(buttons(&script.joypad(20)), 0x0E);
+        assert_eq!(directions(&script.joypad(20)), 0x0E);
+        assert_eq!(buttons(&script.joypad(30)), 0x0F);
+        assert_eq!(directions(&script.joypad(1000)), 0x0F);
+    }
+
+    #[test]
+    fn ignores_comments_and_empty_lines() {
+        let text = "# Intro\n\n   \n5 down # Menu\n# 6 up\n";
+        let script = InputScript::parse(text).unwrap();
+
+        assert_eq!(directions(&script.joypad(6)), 0x07);
+    }
+
+    #[test]
+    fn empty_script_presses_nothing() {
+        let script = InputScript::empty();
+
+        assert_eq!(buttons(&script.joypad(1)), 0x0F);
+        assert_eq!(directions(&script.joypad(1)), 0x0F);
+    }
+
+    #[test]
+    fn rejects_frames_out_of_order() {
+        let message = InputScript::parse("10 a\n10 b").err().unwrap();
+        assert_eq!(message, "Frame 10 in line 2 is not after the previous line");
+
+        assert!(InputScript::parse("10 a\n5 b").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        let message = InputScript::parse("start 10").err().unwrap();
+        assert_eq!(message, "Invalid frame start in line 1");
+
+        let message = InputScript::parse("1 a\n2 turbo").err().unwrap();
+        assert_eq!(message, "Unknown key turbo in line 2");
+    }
+}
@@ -0,0 +1,4 @@
+pub mod input_script;
+pub mod options;
+pub mod run_command;
+pub mod save_command;
@@ -0,0 +1,97 @@
+use crate::rom::rom_file::RomLocation;
+use crate::savestate::save_state_slots::SLOT_COUNT;
+use std::path::PathBuf;
+
+const DEFAULT_SCALE: u32 = 3;
+const MAX_SCALE: u32 = 10;
+
+pub const USAGE: &str = "Usage: gbemulator [options] [rom]
+       gbemulator save <import|export> <rom> <file> [--format sav|vba|srm|hex] [--patch <patch>]
+       gbemulator run <rom> --frames <n> [--screenshot <png>] [--input <file>] [--expect <hash>]
+
+Options:
+  --config <file>     Config file to use instead of the one in the config directory
+  --scale <1-10>      Size of the window as a multiple of the Game Boy screen
+  --boot-rom <file>   DMG boot ROM which runs before the game
+  --save-dir <dir>    Directory for saves, save states and screenshots
+  --state <1-10>      Load the save state slot after the ROM has been started
+  --patch <file>      IPS, UPS or BPS patch which is applied to the ROM
+  --mute              Start without sound
+  --help              Show this help
+
+A ROM inside a zip archive is selected with <archive>#<rom>";
+
+/// Options of the command line when the emulator is started with a window
+pub struct Options {
+    pub rom_location: Option<RomLocation>,
+    pub config_path: Option<PathBuf>,
+    pub scale: u32,
+    pub boot_rom_path: Option<String>,
+    pub data_path: Option<String>,
+    //Index of the save state slot, the command line counts from 1
+    pub state_slot: Option<usize>,
+    pub muted: bool,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            rom_location: None,
+            config_path: None,
+            scale: DEFAULT_SCALE,
+            boot_rom_path: None,
+            data_path: None,
+            state_slot: None,
+            muted: false,
+            help: false,
+        };
+
+        let mut patch = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => options.config_path = Some(PathBuf::from(value(&mut args, arg)?)),
+                "--scale" => options.scale = parse_number(value(&mut args, arg)?, arg, MAX_SCALE)?,
+                "--boot-rom" => options.boot_rom_path = Some(value(&mut args, arg)?.to_string()),
+                "--save-dir" => options.data_path = Some(value(&mut args, arg)?.to_string()),
+                "--state" => {
+                    let slot = parse_number(value(&mut args, arg)?, arg, SLOT_COUNT as u32)?;
+                    options.state_slot = Some(slot as usize - 1);
+                }
+                "--patch" => patch = Some(PathBuf::from(value(&mut args, arg)?)),
+                "--mute" => options.muted = true,
+                "--help" | "-h" => options.help = true,
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+                _ if options.rom_location.is_some() => {
+                    return Err(format!("Unexpected argument {}", arg))
+                }
+                _ => options.rom_location = Some(RomLocation::from_argument(arg)),
+            }
+        }
+
+        match options.rom_location {
+            Some(ref mut rom_location) => rom_location.patch = patch,
+            None if patch.is_some() => return Err("--patch needs a ROM".to_string()),
+            None if options.state_slot.is_some() => return Err("--state needs a ROM".to_string()),
+            None => {}
+        }
+
+        Ok(options)
+    }
+}
+
+fn value<'a>(args: &mut impl Iterator<Item = &'a String>, option: &str) -> Result<&'a str, String> {
+    match args.next() {
+        Some(value) => Ok(value),
+        None => Err(format!("{} needs a value", option)),
+    }
+}
+
+fn parse_number(value: &str, option: &str, max: u32) -> Result<u32, String> {
+    match value.parse() {
+        Ok(number) if (1..=max).contains(&number) => Ok(number),
+        _ => Err(format!("{} must be a number from 1 to {}", option, max)),
+    }
+}
@@ -0,0 +1,294 @@
+use crate::cli::input_script::InputScript;
+use crate::config::color_palette::ColorPalette;
+use crate::emulation;
+use crate::rom::rom_file;
+use crate::rom::rom_file::RomLocation;
+use flate2::Crc;
+use lib_gbemulation::apu::apu::Apu;
+use lib_gbemulation::apu::NullAudioOutput;
+use lib_gbemulation::cartridge;
+use lib_gbemulation::cpu::cpu::Cpu;
+use lib_gbemulation::emulation::Emulation;
+use lib_gbemulation::gpu::gpu::Gpu;
+use lib_gbemulation::gpu::{Screen, BUFFER_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
+use lib_gbemulation::memory::mmu::Mmu;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Exit code if the hash of the last frame is not the expected one
+pub const EXIT_MISMATCH: i32 = 2;
+
+const USAGE: &str = "Usage: gbemulator run <rom> --frames <n> [--screenshot <png>] [--input <file>] [--expect <hash>] [--boot-rom <file>] [--patch <patch>]
+Runs the ROM without a window, sound or savegame and prints the CRC32 of every frame.
+--screenshot writes the last frame, --expect compares the hash of the last frame.
+Every line of the input file contains a frame followed by the keys held from then on, e.g. \"120 start\".
+Exits with 0 on success, 1 on errors and 2 if the last frame does not match --expect";
+
+struct RunOptions {
+    rom_location: RomLocation,
+    frames: u32,
+    screenshot_path: Option<PathBuf>,
+    input_script: InputScript,
+    expected_hash: Option<u32>,
+    boot_rom_path: Option<PathBuf>,
+}
+
+/// Runs a ROM for a number of frames and returns the exit code
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let hash = run_frames(&options)?;
+
+    match options.expected_hash {
+        Some(expected_hash) if expected_hash != hash => {
+            eprintln!(
+                "The last frame has the hash {:08x} instead of {:08x}",
+                hash, expected_hash
+            );
+            Ok(EXIT_MISMATCH)
+        }
+        _ => Ok(0),
+    }
+}
+
+//Returns the hash of the last frame
+fn run_frames(options: &RunOptions) -> Result<u32, String> {
+    let rom = rom_file::read_rom(&options.rom_location)?;
+    let mut cartridge = cartridge::new_cartridge(rom, None)?;
+
+    for warning in cartridge.header().warnings.iter() {
+        eprintln!("Warning: {}", warning);
+    }
+    let sgb_enabled = cartridge.header().sgb_supported;
+
+    let screen = Arc::new(HeadlessScreen::new());
+    let mut audio_output = NullAudioOutput;
+    let mut apu = Apu::new(&mut audio_output);
+    let mut gpu = Gpu::new(screen.clone());
+
+    if sgb_enabled {
+        gpu.enable_sgb();
+    }
+
+    let mut mmu = Mmu::new(&mut *cartridge, &mut gpu, &mut apu);
+
+    let mut cpu = match options.boot_rom_path {
+        Some(ref path) => {
+            mmu.set_boot_rom(emulation::read_boot_rom(path)?);
+            Cpu::new_with_boot_rom()
+        }
+        None => Cpu::new(),
+    };
+
+    let mut emulation = Emulation::new();
+    let mut hash = 0;
+
+    for frame in 1..=options.frames {
+        let joypad = options.input_script.joypad(frame);
+        emulation.run_until_vblank(&mut cpu, &mut mmu, &joypad);
+
+        hash = screen.frame_hash();
+        println!("{} {:08x}", frame, hash);
+    }
+
+    if let Some(ref path) = options.screenshot_path {
+        write_png(path, &screen.frame())?;
+    }
+
+    Ok(hash)
+}
+
+fn parse_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut rom_argument = None;
+    let mut frames = None;
+    let mut screenshot_path = None;
+    let mut input_script = InputScript::empty();
+    let mut expected_hash = None;
+    let mut boot_rom_path = None;
+    let mut patch = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            if rom_argument.is_some() {
+                return Err(USAGE.to_string());
+            }
+            rom_argument = Some(arg.as_str());
+            continue;
+        }
+
+        let value = match args.next() {
+            Some(value) => value,
+            None => return Err(USAGE.to_string()),
+        };
+
+        match arg.as_str() {
+            "--frames" => match value.parse() {
+                Ok(value) => frames = Some(value),
+                Err(_) => return Err(format!("Invalid number of frames: {}", value)),
+            },
+            "--screenshot" => screenshot_path = Some(PathBuf::from(value)),
+            "--input" => input_script = InputScript::load(Path::new(value))?,
+            "--expect" => match u32::from_str_radix(value, 16) {
+                Ok(value) => expected_hash = Some(value),
+                Err(_) => return Err(format!("Invalid hash: {}", value)),
+            },
+            "--boot-rom" => boot_rom_path = Some(PathBuf::from(value)),
+            "--patch" => patch = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown option {}\n{}", arg, USAGE)),
+        }
+    }
+
+    let (rom_argument, frames) = match (rom_argument, frames) {
+        (Some(rom_argument), Some(frames)) => (rom_argument, frames),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let mut rom_location = RomLocation::from_argument(rom_argument);
+    let roms = rom_location.select_single_rom()?;
+    if !roms.is_empty() {
+        return Err(format!(
+            "The archive contains more than one ROM, select one with <archive>#<rom>: {}",
+            roms.join(", ")
+        ));
+    }
+
+    rom_location.patch = patch;
+    rom_location.find_patch();
+
+    Ok(RunOptions {
+        rom_location,
+        frames,
+        screenshot_path,
+        input_script,
+        expected_hash,
+        boot_rom_path,
+    })
+}
+
+fn write_png(path: &Path, frame: &[u8; BUFFER_SIZE]) -> Result<(), String> {
+    let file = match File::create(path) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("Could not create {}: {}", path.display(), e)),
+    };
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        SCREEN_WIDTH as u32,
+        SCREEN_HEIGHT as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let result = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(frame));
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Could not write {}: {}", path.display(), e)),
+    }
+}
+
+//Keeps the last frame. Uses the default palette so the hashes do not depend on the config
+struct HeadlessScreen {
+    frame: Mutex<[u8; BUFFER_SIZE]>,
+}
+
+impl HeadlessScreen {
+    fn new() -> Self {
+        HeadlessScreen {
+            frame: Mutex::new([255; BUFFER_SIZE]),
+        }
+    }
+
+    fn frame(&self) -> [u8; BUFFER_SIZE] {
+        *self.frame.lock().unwrap()
+    }
+
+    fn frame_hash(&self) -> u32 {
+        let mut crc = Crc::new();
+        crc.update(&*self.frame.lock().unwrap());
+        crc.sum()
+    }
+}
+
+impl Screen for HeadlessScreen {
+    fn draw(&self, screen_buffer: &[u8; BUFFER_SIZE]) {
+        *self.frame.lock().unwrap() = *screen_buffer;
+    }
+
+    fn get_palette(&self) -> [[u8; 3]; 4] {
+        let palette = ColorPalette::default();
+        [
+            palette.color4,
+            palette.color3,
+            palette.color2,
+            palette.color1,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    //ROM only cartridge which executes NOPs
+    fn write_rom(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("gbemulator-{}-{}.gb", name, std::process::id()));
+        fs::write(&path, vec![0; 0x8000]).unwrap();
+        path
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn exits_with_mismatch_for_other_hash() {
+        let path = write_rom("mismatch");
+        let rom = path.to_str().unwrap();
+
+        let hash = run_frames(&parse_options(&args(&[rom, "--frames", "3"])).unwrap()).unwrap();
+        let expected = format!("{:08x}", hash);
+        let other = format!("{:08x}", hash ^ 1);
+
+        let matching = run(&args(&[rom, "--frames", "3", "--expect", &expected]));
+        let mismatching = run(&args(&[rom, "--frames", "3", "--expect", &other]));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(matching, Ok(0));
+        assert_eq!(mismatching, Ok(EXIT_MISMATCH));
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert_eq!(parse_options(&args(&["game.gb"])).err().unwrap(), USAGE);
+        assert_eq!(
+            parse_options(&args(&["--frames", "10"])).err().unwrap(),
+            USAGE
+        );
+        assert_eq!(
+            parse_options(&args(&["game.gb", "--frames", "ten"]))
+                .err()
+                .unwrap(),
+            "Invalid number of frames: ten"
+        );
+        assert_eq!(
+            parse_options(&args(&["game.gb", "--frames", "1", "--expect", "xyz"]))
+                .err()
+                .unwrap(),
+            "Invalid hash: xyz"
+        );
+        assert!(parse_options(&args(&["game.gb", "--frames", "1", "--speed", "2"])).is_err());
+    }
+
+    #[test]
+    fn reports_missing_rom() {
+        let result = run(&args(&["/nonexistent/game.gb", "--frames", "1"]));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::config::config::Config;
+use crate::rom::rom_file::RomLocation;
+use crate::savegame::savegame_transfer;
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "Usage: gbemulator save <import|export> <rom> <file> [--format sav|vba|srm|hex] [--patch <patch>]
+Without --format the format is taken from the extension of the file.
+A ROM inside a zip archive is selected with <archive>#<rom>.
+Patched games have their own savegame. A patch next to the ROM is used without --patch";
+
+/// Converts the savegame of a ROM from or to the savegame of another emulator or flash cart
+pub fn run(args: &[String], config: &Config) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut format = None;
+    let mut patch = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            let name = match args.next() {
+                Some(name) => name,
+                None => return Err(USAGE.to_string()),
+            };
+
+            match SavegameFormat::from_name(name) {
+                Some(savegame_format) => format = Some(savegame_format),
+                None => return Err(format!("Unknown savegame format: {}", name)),
+            }
+        } else if arg == "--patch" {
+            match args.next() {
+                Some(path) => patch = Some(PathBuf::from(path)),
+                None => return Err(USAGE.to_string()),
+            }
+        } else {
+            paths.push(arg.as_str());
+        }
+    }
+
+    let (command, mut rom_location, file) = match paths[..] {
+        [command, rom_path, file] => (
+            command,
+            RomLocation::from_argument(rom_path),
+            Path::new(file),
+        ),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let format = format.unwrap_or_else(|| savegame_transfer::format_from_path(file));
+    let roms = rom_location.select_single_rom()?;
+    if !roms.is_empty() {
+        return Err(format!(
+            "The archive contains more than one ROM, select one with <archive>#<rom>: {}",
+            roms.join(", ")
+        ));
+    }
+
+    rom_location.patch = patch;
+    rom_location.find_patch();
+
+    let ram_dumper = savegame_transfer::open_savegame(config, &rom_location)?;
+
+    match command {
+        "export" => {
+            savegame_transfer::export_savegame(&ram_dumper, file, format)?;
+            println!("Exported the savegame to {}", file.display());
+        }
+        "import" => {
+            savegame_transfer::import_savegame(&ram_dumper, file, format)?;
+            println!("Imported the savegame from {}", file.display());
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioOutputType {
+    /// The default sound device paces the emulation. Falls back to Silent if there is none
+    #[default]
+    Device,
+    /// No sound, the emulation is paced by a timer
+    Silent,
+}
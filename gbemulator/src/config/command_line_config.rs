@@ -0,0 +1,10 @@
+/// Settings given on the command line. They only apply to the running session
+/// and are never written to the config file
+#[derive(Debug, Default)]
+pub struct CommandLineConfig {
+    //Replaces data_path of the config
+    pub data_path: Option<String>,
+    //DMG boot ROM which runs before the game
+    pub boot_rom_path: Option<String>,
+    pub muted: bool,
+}
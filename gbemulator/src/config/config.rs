@@ -1,5 +1,8 @@
+use crate::config::audio_output_type::AudioOutputType;
 use crate::config::color_palette::ColorPalette;
+use crate::config::command_line_config::CommandLineConfig;
 use crate::config::controls::Controls;
+use crate::config::hotkeys::Hotkeys;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -7,6 +10,24 @@ pub struct Config {
     pub controls: Controls,
     #[serde(default = "ColorPalette::default")]
     pub color_palette: ColorPalette,
+    //PNG file or directory of numbered PNG frames used by the Pocket Camera
+    #[serde(default)]
+    pub camera_image_path: Option<String>,
+    //Directory the Game Boy Printer writes its printouts to. Without it no printer is connected
+    #[serde(default)]
+    pub printer_output_path: Option<String>,
+    //Use Super Game Boy palettes and borders for games which support them
+    #[serde(default = "default_sgb_enabled")]
+    pub sgb_enabled: bool,
+    //Directory for saves, save states and screenshots instead of the platform data directory
+    #[serde(default)]
+    pub data_path: Option<String>,
+    #[serde(default)]
+    pub hotkeys: Hotkeys,
+    #[serde(default)]
+    pub audio_output: AudioOutputType,
+    #[serde(skip)]
+    pub command_line: CommandLineConfig,
 }
 
 impl Config {
@@ -15,17 +36,31 @@ impl Config {
         Ok(config)
     }
 
+    /// Goes through a toml::Value because TOML needs all plain values of a table to be
+    /// written before the nested tables, which is not the order of the fields
     pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
-        let toml = toml::to_string_pretty(&self)?;
+        let value = toml::Value::try_from(self)?;
+        let toml = toml::to_string_pretty(&value)?;
         Ok(toml)
     }
 }
 
+fn default_sgb_enabled() -> bool {
+    true
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             controls: Controls::default(),
             color_palette: ColorPalette::default(),
+            camera_image_path: None,
+            printer_output_path: None,
+            sgb_enabled: true,
+            data_path: None,
+            hotkeys: Hotkeys::default(),
+            audio_output: AudioOutputType::default(),
+            command_line: CommandLineConfig::default(),
         }
     }
 }
@@ -2,22 +2,23 @@ use crate::config::config::Config;
 
 use std::fs;
 use std::io::ErrorKind;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
 pub struct ConfigStorage {
     pub config: Arc<RwLock<Config>>,
-    pub filename: String,
+    pub filename: PathBuf,
 }
 
 impl ConfigStorage {
-    pub fn create_empty(filename: String) -> Self {
+    pub fn create_empty(filename: PathBuf) -> Self {
         ConfigStorage {
             config: Arc::new(RwLock::new(Config::default())),
             filename,
         }
     }
 
-    pub fn create_from_file(filename: String) -> Result<Self, String> {
+    pub fn create_from_file(filename: PathBuf) -> Result<Self, String> {
         let content = match fs::read_to_string(&filename) {
             Ok(content) => content,
             Err(e) => {
@@ -48,6 +49,12 @@ impl ConfigStorage {
             Err(e) => return Err(format!("Error serializing config: {:?}", e)),
         };
 
+        if let Some(directory) = self.filename.parent() {
+            if let Err(e) = fs::create_dir_all(directory) {
+                return Err(format!("Error creating config directory: {:?}", e));
+            }
+        }
+
         match fs::write(&self.filename, toml) {
             Ok(_) => {}
             Err(e) => return Err(format!("Error saving config to file: {:?}", e)),
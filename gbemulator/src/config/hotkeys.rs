@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use winit::event::VirtualKeyCode;
+
+/// Keys of the emulator functions which are not part of the Game Boy
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hotkeys {
+    pub save_state: VirtualKeyCode,
+    pub load_state: VirtualKeyCode,
+    pub previous_state_slot: VirtualKeyCode,
+    pub next_state_slot: VirtualKeyCode,
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Hotkeys {
+            save_state: VirtualKeyCode::F5,
+            load_state: VirtualKeyCode::F8,
+            previous_state_slot: VirtualKeyCode::F6,
+            next_state_slot: VirtualKeyCode::F7,
+        }
+    }
+}
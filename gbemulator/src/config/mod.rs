@@ -1,4 +1,7 @@
+pub mod audio_output_type;
 pub mod color_palette;
+pub mod command_line_config;
 pub mod config;
 pub mod config_storage;
 pub mod controls;
+pub mod hotkeys;
@@ -1 +1,3 @@
 pub mod keyboard_controller;
+pub mod state_hotkey_controller;
+pub mod tilt_controller;
@@ -0,0 +1,61 @@
+use crate::config::config::Config;
+use crate::savestate::save_state_slots::SLOT_COUNT;
+use crate::EmulationSignal;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, RwLock};
+use winit::event::VirtualKeyCode;
+
+/// Saves and loads the selected save state slot with the hotkeys of the config
+pub struct StateHotkeyController {
+    config: Arc<RwLock<Config>>,
+    emulation_signal_sender: Sender<EmulationSignal>,
+    selected_slot: usize,
+    //Holding a key repeats the key press, which should not save the state over and over
+    held_key: Option<VirtualKeyCode>,
+}
+
+impl StateHotkeyController {
+    pub fn new(
+        config: Arc<RwLock<Config>>,
+        emulation_signal_sender: Sender<EmulationSignal>,
+    ) -> Self {
+        StateHotkeyController {
+            config,
+            emulation_signal_sender,
+            selected_slot: 0,
+            held_key: None,
+        }
+    }
+
+    pub fn push_key(&mut self, key_code: VirtualKeyCode) {
+        if self.held_key == Some(key_code) {
+            return;
+        }
+        self.held_key = Some(key_code);
+
+        let config = self.config.read().unwrap();
+        let hotkeys = &config.hotkeys;
+
+        if key_code == hotkeys.save_state {
+            self.send(EmulationSignal::SaveState(self.selected_slot));
+        } else if key_code == hotkeys.load_state {
+            self.send(EmulationSignal::LoadState(self.selected_slot));
+        } else if key_code == hotkeys.previous_state_slot {
+            self.selected_slot = (self.selected_slot + SLOT_COUNT - 1) % SLOT_COUNT;
+            println!("Selected save state slot {}", self.selected_slot + 1);
+        } else if key_code == hotkeys.next_state_slot {
+            self.selected_slot = (self.selected_slot + 1) % SLOT_COUNT;
+            println!("Selected save state slot {}", self.selected_slot + 1);
+        }
+    }
+
+    pub fn release_key(&mut self, key_code: VirtualKeyCode) {
+        if self.held_key == Some(key_code) {
+            self.held_key = None;
+        }
+    }
+
+    fn send(&self, signal: EmulationSignal) {
+        self.emulation_signal_sender.send(signal).unwrap();
+    }
+}
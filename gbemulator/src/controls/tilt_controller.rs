@@ -0,0 +1,76 @@
+use lib_gbemulation::cartridge::accelerometer::SharedAccelerometer;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::VirtualKeyCode;
+
+/// Maps the arrow keys and the mouse position to the tilt of cartridges with an accelerometer.
+/// The arrow keys tilt fully and take precedence over the mouse
+pub struct TiltController {
+    pub accelerometer: SharedAccelerometer,
+    //Left, right, up, down
+    keys_pressed: [bool; 4],
+    mouse_tilt: (f32, f32),
+}
+
+impl TiltController {
+    pub fn new(accelerometer: SharedAccelerometer) -> Self {
+        TiltController {
+            accelerometer,
+            keys_pressed: [false; 4],
+            mouse_tilt: (0.0, 0.0),
+        }
+    }
+
+    pub fn push_key(&mut self, key_code: VirtualKeyCode) {
+        self.set_key(key_code, true);
+    }
+
+    pub fn release_key(&mut self, key_code: VirtualKeyCode) {
+        self.set_key(key_code, false);
+    }
+
+    /// The distance of the cursor to the center of the window is used as tilt
+    pub fn move_cursor(&mut self, position: PhysicalPosition<f64>, window_size: PhysicalSize<u32>) {
+        if window_size.width == 0 || window_size.height == 0 {
+            return;
+        }
+
+        let x = position.x as f32 / window_size.width as f32 * 2.0 - 1.0;
+        let y = position.y as f32 / window_size.height as f32 * 2.0 - 1.0;
+
+        self.mouse_tilt = (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0));
+        self.update();
+    }
+
+    fn set_key(&mut self, key_code: VirtualKeyCode, pressed: bool) {
+        let index = match key_code {
+            VirtualKeyCode::Left => 0,
+            VirtualKeyCode::Right => 1,
+            VirtualKeyCode::Up => 2,
+            VirtualKeyCode::Down => 3,
+            _ => return,
+        };
+
+        self.keys_pressed[index] = pressed;
+        self.update();
+    }
+
+    fn update(&self) {
+        if !self.keys_pressed.contains(&true) {
+            self.accelerometer
+                .set_tilt(self.mouse_tilt.0, self.mouse_tilt.1);
+            return;
+        }
+
+        let x = get_key_axis(self.keys_pressed[0], self.keys_pressed[1]);
+        let y = get_key_axis(self.keys_pressed[2], self.keys_pressed[3]);
+        self.accelerometer.set_tilt(x, y);
+    }
+}
+
+fn get_key_axis(negative: bool, positive: bool) -> f32 {
+    match (negative, positive) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    }
+}
@@ -1,80 +1,331 @@
 use crate::audio_output::CpalAudioOutput;
+use crate::camera::file_image_source::FileImageSource;
+use crate::config::audio_output_type::AudioOutputType;
+use crate::config::config::Config;
+use crate::frame_timer::FrameTimer;
 
 use crate::graphics::gameboy_screen::GameboyScreen;
+use crate::printer::png_printer_output::PngPrinterOutput;
+use crate::rom::rom_file;
+use crate::rom::rom_file::RomLocation;
 use crate::savegame::filesystem_ram_dumper::FilesystemRamDumper;
+use crate::savegame::savegame_transfer;
+use crate::savestate::save_state_slots::SaveStateSlots;
+use crate::storage::data_directory::DataDirectory;
 use crate::EmulationSignal;
 use lib_gbemulation::apu::apu::Apu;
+use lib_gbemulation::apu::{AudioOutput, NullAudioOutput};
 use lib_gbemulation::cartridge;
+use lib_gbemulation::cartridge::accelerometer::SharedAccelerometer;
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
+use lib_gbemulation::cartridge::SavegameStatus;
 use lib_gbemulation::cpu::cpu::Cpu;
 use lib_gbemulation::gpu::gpu::Gpu;
 use lib_gbemulation::io::joypad::Joypad;
+use lib_gbemulation::io::printer::GameBoyPrinter;
+use lib_gbemulation::memory::mmu;
 use lib_gbemulation::memory::mmu::Mmu;
+use lib_gbemulation::state;
 
+use std::fs;
+use std::path::Path;
 use std::sync::mpsc::{channel, Sender};
-use std::sync::{Arc, Mutex};
-use std::{fs, thread};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::thread::JoinHandle;
+use tinyfiledialogs::MessageBoxIcon;
+
+//Unsaved changes to the cartridge ram are written about every five seconds
+const SAVE_INTERVAL_FRAMES: u32 = 300;
 
 pub struct Emulation {
     gameboy_screen: Arc<GameboyScreen>,
     joypad: Arc<Mutex<Joypad>>,
+    accelerometer: SharedAccelerometer,
+    config: Arc<RwLock<Config>>,
 }
 
 impl Emulation {
-    pub fn new(gameboy_screen: Arc<GameboyScreen>, joypad: Arc<Mutex<Joypad>>) -> Self {
+    pub fn new(
+        gameboy_screen: Arc<GameboyScreen>,
+        joypad: Arc<Mutex<Joypad>>,
+        accelerometer: SharedAccelerometer,
+        config: Arc<RwLock<Config>>,
+    ) -> Self {
         Emulation {
             gameboy_screen,
             joypad,
+            accelerometer,
+            config,
         }
     }
 
-    pub fn start(&self, rom_path: &String) -> Result<Sender<EmulationSignal>, String> {
-        let rom = read_rom_from_file(rom_path)?;
-        let ram_dumper = FilesystemRamDumper::new(&rom_path);
+    /// Save state slots of the given ROM
+    pub fn save_state_slots(&self, rom_location: &RomLocation) -> SaveStateSlots {
+        let data_directory = DataDirectory::from_config(&self.config.read().unwrap());
+        SaveStateSlots::new(data_directory.rom_directory(rom_location).states_path())
+    }
+
+    /// Replaces the savegame of the ROM. Must not be called while the ROM is running
+    pub fn import_savegame(
+        &self,
+        rom_location: &RomLocation,
+        path: &Path,
+        format: SavegameFormat,
+    ) -> Result<(), String> {
+        let ram_dumper =
+            savegame_transfer::open_savegame(&self.config.read().unwrap(), rom_location)?;
+        savegame_transfer::import_savegame(&ram_dumper, path, format)
+    }
+
+    /// Runs the ROM in a new thread which stops when it receives the Quit signal
+    pub fn start(
+        &self,
+        rom_location: &RomLocation,
+    ) -> Result<(Sender<EmulationSignal>, JoinHandle<()>), String> {
+        let rom = rom_file::read_rom(rom_location)?;
+
+        let data_directory = DataDirectory::from_config(&self.config.read().unwrap());
+        let rom_directory = data_directory.rom_directory(rom_location);
+        rom_directory.create()?;
+
+        //Keep going without the old savegame instead of refusing to start the game
+        if let Err(message) = rom_directory.migrate_savegame() {
+            eprintln!("{}", message);
+        }
+
+        let savegame_path = rom_directory.savegame_path();
+        let ram_dumper = FilesystemRamDumper::new(savegame_path.clone());
+        let save_state_slots = SaveStateSlots::new(rom_directory.states_path());
         let mut cartridge = cartridge::new_cartridge(rom, Some(Box::new(ram_dumper)))?;
 
+        for warning in cartridge.header().warnings.iter() {
+            eprintln!("Warning: {}", warning);
+        }
+
+        if let SavegameStatus::Resized { expected, actual } = cartridge.savegame_status() {
+            let message = format!(
+                "The savegame has {} bytes but the cartridge has {} bytes of RAM. \
+                It has been adjusted and may not work correctly.",
+                actual, expected
+            );
+            tinyfiledialogs::message_box_ok("Savegame", &message, MessageBoxIcon::Warning);
+        }
+
+        cartridge.set_accelerometer(Box::new(self.accelerometer.clone()));
+
+        if let Some(ref path) = self.config.read().unwrap().camera_image_path {
+            cartridge.set_image_source(Box::new(FileImageSource::new(path)?));
+        }
+
+        let sgb_enabled =
+            cartridge.header().sgb_supported && self.config.read().unwrap().sgb_enabled;
+        self.gameboy_screen.clear_border();
+
+        let printer = match self.config.read().unwrap().printer_output_path {
+            Some(ref path) => Some(GameBoyPrinter::new(Box::new(PngPrinterOutput::new(path)?))),
+            None => None,
+        };
+
+        let boot_rom = match self.config.read().unwrap().command_line.boot_rom_path {
+            Some(ref path) => Some(read_boot_rom(Path::new(path))?),
+            None => None,
+        };
+        let muted = self.config.read().unwrap().command_line.muted;
+        let audio_output_type = self.config.read().unwrap().audio_output;
+
         let (emulation_signal_sender, emulation_signal_receiver) = channel();
         let cloned_sender = emulation_signal_sender.clone();
 
         let screen = Arc::clone(&self.gameboy_screen);
+        let thumbnail_screen = Arc::clone(&self.gameboy_screen);
         let joypad = Arc::clone(&self.joypad);
 
-        thread::Builder::new()
+        let emulation_thread = thread::Builder::new()
             .name("emulation".to_string())
             .spawn(move || {
                 //Cpal needs to be startet from a different thread because of a winit bug on windows
-                let mut audio_output = CpalAudioOutput::new(2048, Some(emulation_signal_sender));
-                let default_device = audio_output.get_default_device_name();
-                audio_output.start(default_device);
+                let mut cpal_audio_output = match audio_output_type {
+                    AudioOutputType::Device => start_audio_device(emulation_signal_sender, muted),
+                    AudioOutputType::Silent => None,
+                };
 
-                let mut apu = Apu::new(&mut audio_output);
+                //Without a sound device the frames are paced by a timer
+                let mut null_audio_output = NullAudioOutput;
+                let mut frame_timer = None;
+                let audio_output: &mut dyn AudioOutput = match cpal_audio_output {
+                    Some(ref mut audio_output) => audio_output,
+                    None => {
+                        frame_timer = Some(FrameTimer::new());
+                        &mut null_audio_output
+                    }
+                };
+
+                let mut apu = Apu::new(audio_output);
                 let mut gpu = Gpu::new(screen);
+
+                if sgb_enabled {
+                    gpu.enable_sgb();
+                }
+
                 let mut mmu = Mmu::new(&mut *cartridge, &mut gpu, &mut apu);
-                let mut cpu = Cpu::new();
+
+                if let Some(printer) = printer {
+                    mmu.serial.set_peer(Box::new(printer));
+                }
+
+                let mut cpu = match boot_rom {
+                    Some(boot_rom) => {
+                        mmu.set_boot_rom(boot_rom);
+                        Cpu::new_with_boot_rom()
+                    }
+                    None => Cpu::new(),
+                };
                 let mut emulation = lib_gbemulation::emulation::Emulation::new();
+                let mut frames_since_save = 0;
+                let mut save_failed = false;
 
                 loop {
-                    let signal = emulation_signal_receiver.recv().unwrap();
+                    let signal = match frame_timer {
+                        Some(ref mut frame_timer) => {
+                            frame_timer.receive(&emulation_signal_receiver)
+                        }
+                        None => emulation_signal_receiver.recv().unwrap(),
+                    };
+
+                    match signal {
+                        EmulationSignal::Quit => {
+                            if let Err(message) = mmu.save() {
+                                eprintln!("Could not save game: {}", message);
+                            }
+                            if let Some(ref audio_output) = cpal_audio_output {
+                                audio_output.stop();
+                            }
+                            break;
+                        }
+                        EmulationSignal::SaveState(slot) => {
+                            let state = state::save_state(&cpu, &mmu);
+                            let frame = thumbnail_screen.current_frame();
+
+                            match save_state_slots.save(slot, &state, &frame) {
+                                Ok(_) => println!("Saved state to slot {}", slot + 1),
+                                Err(message) => eprintln!("{}", message),
+                            }
+                            continue;
+                        }
+                        EmulationSignal::LoadState(slot) => {
+                            match load_state_slot(&save_state_slots, slot, &mut cpu, &mut mmu) {
+                                Ok(_) => println!("Loaded state from slot {}", slot + 1),
+                                Err(message) => eprintln!("{}", message),
+                            }
+                            continue;
+                        }
+                        EmulationSignal::ExportSavegame(path, format) => {
+                            //Unsaved changes are written first so they are part of the export
+                            let result = mmu.save().and_then(|_| {
+                                let ram_dumper = FilesystemRamDumper::new(savegame_path.clone());
+                                savegame_transfer::export_savegame(&ram_dumper, &path, format)
+                            });
 
-                    if let EmulationSignal::Quit = signal {
-                        mmu.save();
-                        audio_output.stop();
-                        break;
+                            match result {
+                                Ok(_) => println!("Exported the savegame to {}", path.display()),
+                                Err(message) => show_error("Savegame", &message),
+                            }
+                            continue;
+                        }
+                        EmulationSignal::Cycle => {}
+                    }
+
+                    //Games disable the ram after saving, which is written at most once per frame
+                    frames_since_save += 1;
+                    if frames_since_save >= SAVE_INTERVAL_FRAMES || mmu.flush_pending() {
+                        frames_since_save = 0;
+                        save_periodically(&mut mmu, &mut save_failed);
                     }
 
                     let joypad = joypad.lock().unwrap();
 
-                    emulation.cycle(&mut cpu, &mut mmu, &joypad);
+                    emulation.run_until_vblank(&mut cpu, &mut mmu, &joypad);
                 }
             })
             .unwrap();
 
-        Ok(cloned_sender)
+        Ok((cloned_sender, emulation_thread))
     }
 }
 
-fn read_rom_from_file(rom_path: &String) -> Result<Vec<u8>, String> {
-    match fs::read(rom_path) {
-        Ok(rom) => Ok(rom),
-        Err(_) => Err(format!("Could not open file {}", rom_path)),
+/// None if no sound device could be opened
+fn start_audio_device(
+    sync_sender: Sender<EmulationSignal>,
+    muted: bool,
+) -> Option<CpalAudioOutput> {
+    let mut audio_output = CpalAudioOutput::new(2048, Some(sync_sender));
+    audio_output.set_muted(muted);
+
+    let result = match audio_output.get_default_device_name() {
+        Some(device_name) => audio_output.start(device_name),
+        None => Err("No sound device found".to_string()),
+    };
+
+    match result {
+        Ok(_) => Some(audio_output),
+        Err(message) => {
+            eprintln!("{}, continuing without sound", message);
+            None
+        }
+    }
+}
+
+pub fn read_boot_rom(path: &Path) -> Result<Vec<u8>, String> {
+    let boot_rom = match fs::read(path) {
+        Ok(boot_rom) => boot_rom,
+        Err(e) => return Err(format!("Could not open boot ROM {}: {}", path.display(), e)),
+    };
+
+    if boot_rom.len() != mmu::BOOT_ROM_SIZE {
+        return Err(format!(
+            "The boot ROM has {} bytes but a DMG boot ROM has {} bytes",
+            boot_rom.len(),
+            mmu::BOOT_ROM_SIZE
+        ));
     }
+
+    Ok(boot_rom)
+}
+
+/// Errors are only reported once instead of every time the save is retried
+fn save_periodically(mmu: &mut Mmu, save_failed: &mut bool) {
+    match mmu.save() {
+        Ok(_) => *save_failed = false,
+        Err(message) => {
+            if !*save_failed {
+                eprintln!("Could not save game: {}", message);
+            }
+            *save_failed = true;
+        }
+    }
+}
+
+/// A state which fails to load halfway would leave the machine broken,
+/// so the previous state is restored in that case
+fn load_state_slot(
+    save_state_slots: &SaveStateSlots,
+    slot: usize,
+    cpu: &mut Cpu,
+    mmu: &mut Mmu,
+) -> Result<(), String> {
+    let data = save_state_slots.load(slot)?;
+    let previous_state = state::save_state(cpu, mmu);
+
+    if let Err(message) = state::load_state(cpu, mmu, &data) {
+        state::load_state(cpu, mmu, &previous_state)?;
+        return Err(format!("Could not load save state: {}", message));
+    }
+
+    Ok(())
+}
+
+pub fn show_error(title: &str, message: &str) {
+    eprintln!("{}", message);
+    tinyfiledialogs::message_box_ok(title, message, MessageBoxIcon::Error);
 }
@@ -0,0 +1,104 @@
+use crate::EmulationSignal;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+//70224 clock cycles per frame at 4.194304 MHz
+const FRAME_DURATION: Duration = Duration::from_nanos(16_742_706);
+
+/// Paces the emulation when there is no sound device which sends the Cycle signals
+pub struct FrameTimer {
+    next_frame: Instant,
+}
+
+impl FrameTimer {
+    pub fn new() -> Self {
+        FrameTimer {
+            next_frame: Instant::now(),
+        }
+    }
+
+    /// Waits for the next signal and returns Cycle once the next frame is due
+    pub fn receive(&mut self, receiver: &Receiver<EmulationSignal>) -> EmulationSignal {
+        loop {
+            //Other signals are handled first, even if the emulation falls behind
+            if let Ok(signal) = receiver.try_recv() {
+                return signal;
+            }
+
+            let now = Instant::now();
+
+            if now >= self.next_frame {
+                //Frames which are too late are dropped instead of being caught up
+                self.next_frame += FRAME_DURATION;
+                if self.next_frame <= now {
+                    self.next_frame = now + FRAME_DURATION;
+                }
+                return EmulationSignal::Cycle;
+            }
+
+            match receiver.recv_timeout(self.next_frame - now) {
+                Ok(signal) => return signal,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return EmulationSignal::Quit,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn drops_late_frames() {
+        let (_sender, receiver) = channel();
+        let mut frame_timer = FrameTimer::new();
+        frame_timer.next_frame = Instant::now() - FRAME_DURATION * 3;
+
+        assert!(matches!(
+            frame_timer.receive(&receiver),
+            EmulationSignal::Cycle
+        ));
+        assert!(frame_timer.next_frame >= Instant::now() + FRAME_DURATION / 2);
+    }
+
+    #[test]
+    fn keeps_frame_rate_when_on_time() {
+        let (_sender, receiver) = channel();
+        let mut frame_timer = FrameTimer::new();
+        let start = frame_timer.next_frame;
+
+        for _ in 0..3 {
+            assert!(matches!(
+                frame_timer.receive(&receiver),
+                EmulationSignal::Cycle
+            ));
+        }
+
+        assert!(start.elapsed() >= FRAME_DURATION * 2);
+        assert_eq!(frame_timer.next_frame, start + FRAME_DURATION * 3);
+    }
+
+    #[test]
+    fn handles_other_signals_first() {
+        let (sender, receiver) = channel();
+        let mut frame_timer = FrameTimer::new();
+        sender.send(EmulationSignal::SaveState(1)).unwrap();
+
+        assert!(matches!(
+            frame_timer.receive(&receiver),
+            EmulationSignal::SaveState(1)
+        ));
+        assert!(matches!(
+            frame_timer.receive(&receiver),
+            EmulationSignal::Cycle
+        ));
+
+        drop(sender);
+        assert!(matches!(
+            frame_timer.receive(&receiver),
+            EmulationSignal::Quit
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::config::config::Config;
 use lib_gbemulation::gpu::{Screen, BUFFER_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
-use std::sync::atomic::{AtomicU8, Ordering};
+use lib_gbemulation::sgb::{BORDER_HEIGHT, BORDER_WIDTH};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 
 pub const MENU_BAR_HEIGHT: i32 = 19;
@@ -10,6 +11,9 @@ pub struct GameboyScreen {
     buffer2: Arc<Mutex<[u8; BUFFER_SIZE]>>,
     current_buffer: Arc<AtomicU8>,
     config: Arc<RwLock<Config>>,
+    //Super Game Boy border, drawn as a separate layer around the screen
+    border: Mutex<Option<Vec<u8>>>,
+    border_changed: AtomicBool,
 }
 
 impl GameboyScreen {
@@ -19,6 +23,59 @@ impl GameboyScreen {
             buffer2: Arc::new(Mutex::new([255; BUFFER_SIZE])),
             current_buffer: Arc::new(AtomicU8::new(1)),
             config,
+            border: Mutex::new(None),
+            border_changed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn has_border(&self) -> bool {
+        self.border.lock().unwrap().is_some()
+    }
+
+    pub fn clear_border(&self) {
+        *self.border.lock().unwrap() = None;
+    }
+
+    /// Uploads the border to the texture if it changed since the last call
+    pub fn draw_border_to_queue(&self, queue: &wgpu::Queue, texture: &wgpu::Texture) {
+        if !self.border_changed.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        let border = self.border.lock().unwrap();
+
+        let pixel_data = match *border {
+            Some(ref pixel_data) => pixel_data,
+            None => return,
+        };
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixel_data,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(4 * BORDER_WIDTH as u32),
+                rows_per_image: std::num::NonZeroU32::new(BORDER_HEIGHT as u32),
+            },
+            wgpu::Extent3d {
+                width: BORDER_WIDTH as u32,
+                height: BORDER_HEIGHT as u32,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Copy of the frame which is currently shown
+    pub fn current_frame(&self) -> [u8; BUFFER_SIZE] {
+        if self.current_buffer.load(Ordering::SeqCst) == 1 {
+            *self.buffer1.lock().unwrap()
+        } else {
+            *self.buffer2.lock().unwrap()
         }
     }
 
@@ -78,6 +135,11 @@ impl Screen for GameboyScreen {
         *buffer = *screen_buffer;
     }
 
+    fn draw_border(&self, border_buffer: &[u8]) {
+        *self.border.lock().unwrap() = Some(border_buffer.to_vec());
+        self.border_changed.store(true, Ordering::SeqCst);
+    }
+
     fn get_palette(&self) -> [[u8; 3]; 4] {
         let palette = &self.config.read().unwrap().color_palette;
         [
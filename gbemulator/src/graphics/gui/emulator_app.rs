@@ -2,9 +2,18 @@ use crate::config::config::Config;
 use crate::graphics::gui::controls_window::ControlsWindow;
 use crate::graphics::gui::main_menu::MainMenu;
 use crate::graphics::gui::palette_window::PaletteWindow;
+use crate::graphics::gui::rom_chooser_window::RomChooserWindow;
+use crate::graphics::gui::save_state_window::SaveStateWindow;
 use crate::graphics::gui::State;
-use egui::{CtxRef, TextureId};
+use crate::rom::rom_file::RomLocation;
+use crate::savestate::save_state_slots::SaveStateSlots;
+use crate::EmulationSignal;
+use egui::{CtxRef, Rect, TextureId, Vec2};
 use epi::Frame;
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
+use lib_gbemulation::gpu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use lib_gbemulation::sgb::{BORDER_HEIGHT, BORDER_SCREEN_X, BORDER_SCREEN_Y, BORDER_WIDTH};
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, RwLock};
 use winit::event::KeyboardInput;
@@ -13,20 +22,36 @@ pub struct EmulatorApp {
     main_menu: MainMenu,
     controls_window: ControlsWindow,
     palette_window: PaletteWindow,
+    save_state_window: SaveStateWindow,
+    rom_chooser_window: RomChooserWindow,
     state: State,
     keyboard_input: Option<KeyboardInput>,
     tex: Option<TextureId>,
+    border_tex: Option<TextureId>,
 }
 
 impl EmulatorApp {
-    pub fn new(rom_filename_sender: Sender<Option<String>>, config: &Arc<RwLock<Config>>) -> Self {
+    pub fn new(
+        rom_location_sender: Sender<RomLocation>,
+        savegame_import_sender: Sender<(PathBuf, SavegameFormat)>,
+        emulation_signal_sender: Sender<EmulationSignal>,
+        config: &Arc<RwLock<Config>>,
+    ) -> Self {
         EmulatorApp {
-            main_menu: MainMenu::new(rom_filename_sender),
+            main_menu: MainMenu::new(
+                rom_location_sender.clone(),
+                savegame_import_sender,
+                emulation_signal_sender.clone(),
+                config.clone(),
+            ),
             controls_window: ControlsWindow::new(config.clone()),
             palette_window: PaletteWindow::new(config.clone()),
+            save_state_window: SaveStateWindow::new(emulation_signal_sender),
+            rom_chooser_window: RomChooserWindow::new(rom_location_sender),
             state: State::new(),
             keyboard_input: None,
             tex: None,
+            border_tex: None,
         }
     }
 
@@ -37,6 +62,33 @@ impl EmulatorApp {
     pub fn set_tex(&mut self, tex: TextureId) {
         self.tex = Some(tex);
     }
+
+    pub fn set_border_tex(&mut self, border_tex: Option<TextureId>) {
+        self.border_tex = border_tex;
+    }
+
+    /// Slots of the game which has been started, None if no game is running
+    pub fn set_save_state_slots(&mut self, save_state_slots: Option<SaveStateSlots>) {
+        self.state.game_running = save_state_slots.is_some();
+        self.save_state_window
+            .set_save_state_slots(save_state_slots);
+    }
+
+    /// Shows the ROMs of a zip archive to start one of them
+    pub fn choose_rom(&mut self, archive: RomLocation, roms: Vec<String>) {
+        self.rom_chooser_window.set_archive(archive, roms);
+        self.state.rom_chooser_window_shown = true;
+    }
+
+    pub fn set_thumbnail_textures(&mut self, thumbnail_textures: Vec<TextureId>) {
+        self.save_state_window
+            .set_thumbnail_textures(thumbnail_textures);
+    }
+
+    /// Thumbnails of the save state window which have to be written to the textures
+    pub fn take_thumbnail_uploads(&mut self) -> Vec<(usize, Vec<u8>)> {
+        self.save_state_window.take_thumbnail_uploads()
+    }
 }
 
 impl epi::App for EmulatorApp {
@@ -48,13 +100,29 @@ impl epi::App for EmulatorApp {
         self.controls_window
             .update(ctx, &mut self.state, self.keyboard_input);
         self.palette_window.update(ctx, &mut self.state);
+        self.save_state_window.update(ctx, &mut self.state);
+        self.rom_chooser_window.update(ctx, &mut self.state);
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            match self.tex {
-                Some(t) => {
+            match (self.tex, self.border_tex) {
+                (Some(t), Some(border)) => {
+                    let (_, rect) = ui.allocate_space(ui.available_size());
+                    let scale = rect.size() / Vec2::new(BORDER_WIDTH as f32, BORDER_HEIGHT as f32);
+
+                    //The screen is visible through the transparent center of the border
+                    let screen_rect = Rect::from_min_size(
+                        rect.min
+                            + Vec2::new(BORDER_SCREEN_X as f32, BORDER_SCREEN_Y as f32) * scale,
+                        Vec2::new(SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32) * scale,
+                    );
+
+                    egui::Image::new(t, screen_rect.size()).paint_at(ui, screen_rect);
+                    egui::Image::new(border, rect.size()).paint_at(ui, rect);
+                }
+                (Some(t), None) => {
                     ui.image(t, ui.available_size());
                 }
-                None => {}
+                _ => {}
             };
         });
     }
@@ -1,15 +1,42 @@
+use crate::config::audio_output_type::AudioOutputType;
+use crate::config::config::Config;
 use crate::graphics::gui::State;
+use crate::rom::rom_file::RomLocation;
+use crate::savegame::savegame_transfer;
+use crate::savestate::save_state_slots::SLOT_COUNT;
+use crate::EmulationSignal;
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, RwLock};
 use std::thread;
 
+const EXPORT_FORMATS: [(&str, SavegameFormat); 4] = [
+    ("Sav", SavegameFormat::Sav),
+    ("Sav (VisualBoyAdvance clock)", SavegameFormat::VbaSav),
+    ("Srm", SavegameFormat::Srm),
+    ("Hex dump", SavegameFormat::Hex),
+];
+
 pub struct MainMenu {
-    rom_filename_sender: Sender<Option<String>>,
+    rom_location_sender: Sender<RomLocation>,
+    savegame_import_sender: Sender<(PathBuf, SavegameFormat)>,
+    emulation_signal_sender: Sender<EmulationSignal>,
+    config: Arc<RwLock<Config>>,
 }
 
 impl MainMenu {
-    pub fn new(rom_filename_sender: Sender<Option<String>>) -> Self {
+    pub fn new(
+        rom_location_sender: Sender<RomLocation>,
+        savegame_import_sender: Sender<(PathBuf, SavegameFormat)>,
+        emulation_signal_sender: Sender<EmulationSignal>,
+        config: Arc<RwLock<Config>>,
+    ) -> Self {
         MainMenu {
-            rom_filename_sender,
+            rom_location_sender,
+            savegame_import_sender,
+            emulation_signal_sender,
+            config,
         }
     }
 
@@ -17,18 +44,47 @@ impl MainMenu {
         egui::menu::bar(ui, |ui| {
             ui.menu_button("File", |ui| {
                 if ui.button("Open").clicked() {
-                    let filename_sender = self.rom_filename_sender.clone();
-                    //Thread is required otherwise this will crash on Windows TODO: Check if this is still true
-                    thread::spawn(move || {
-                        let filename = tinyfiledialogs::open_file_dialog(
-                            "Open",
-                            "",
-                            Some((&["*.gb"], "Gameboy ROM")),
-                        );
-                        filename_sender.send(filename).unwrap();
-                    });
+                    self.open_rom(false);
+                    ui.close_menu();
+                }
+
+                if ui.button("Open with patch...").clicked() {
+                    self.open_rom(true);
                     ui.close_menu();
                 }
+
+                ui.add_enabled_ui(state.game_running, |ui| {
+                    ui.menu_button("Save state", |ui| {
+                        self.slot_buttons(ui, EmulationSignal::SaveState);
+                    });
+
+                    ui.menu_button("Load state", |ui| {
+                        self.slot_buttons(ui, EmulationSignal::LoadState);
+                    });
+
+                    if ui.button("Save states...").clicked() {
+                        state.save_state_window_shown = true;
+                        ui.close_menu();
+                    }
+                });
+            });
+
+            ui.menu_button("Save", |ui| {
+                ui.add_enabled_ui(state.game_running, |ui| {
+                    if ui.button("Import...").clicked() {
+                        self.open_import_dialog();
+                        ui.close_menu();
+                    }
+
+                    ui.menu_button("Export", |ui| {
+                        for (name, format) in EXPORT_FORMATS {
+                            if ui.button(name).clicked() {
+                                self.open_export_dialog(format);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
             });
 
             ui.menu_button("Options", |ui| {
@@ -41,7 +97,94 @@ impl MainMenu {
                     state.palette_window_shown = true;
                     ui.close_menu();
                 }
+
+                //Takes effect when the next game is started
+                ui.menu_button("Sound", |ui| {
+                    let audio_output = &mut self.config.write().unwrap().audio_output;
+                    ui.radio_value(audio_output, AudioOutputType::Device, "Sound device");
+                    ui.radio_value(audio_output, AudioOutputType::Silent, "No sound");
+                });
             });
         });
     }
+
+    /// The game is restarted with the imported savegame
+    fn open_import_dialog(&self) {
+        let savegame_import_sender = self.savegame_import_sender.clone();
+
+        thread::spawn(move || {
+            let filename = tinyfiledialogs::open_file_dialog(
+                "Import savegame",
+                "",
+                Some((&["*.sav", "*.srm", "*.txt", "*.hex"], "Savegame")),
+            );
+
+            if let Some(filename) = filename {
+                let path = PathBuf::from(filename);
+                let format = savegame_transfer::format_from_path(&path);
+                savegame_import_sender.send((path, format)).unwrap();
+            }
+        });
+    }
+
+    fn open_export_dialog(&self, format: SavegameFormat) {
+        let emulation_signal_sender = self.emulation_signal_sender.clone();
+
+        thread::spawn(move || {
+            let pattern = format!("*.{}", format.extension());
+            let filename = tinyfiledialogs::save_file_dialog_with_filter(
+                "Export savegame",
+                &format!("savegame.{}", format.extension()),
+                &[&pattern],
+                "Savegame",
+            );
+
+            if let Some(filename) = filename {
+                let signal = EmulationSignal::ExportSavegame(PathBuf::from(filename), format);
+                emulation_signal_sender.send(signal).unwrap();
+            }
+        });
+    }
+
+    /// Asks for a ROM and, if requested, an IPS, UPS or BPS patch which is applied to it
+    fn open_rom(&self, with_patch: bool) {
+        let rom_location_sender = self.rom_location_sender.clone();
+        //Thread is required otherwise this will crash on Windows TODO: Check if this is still true
+        thread::spawn(move || {
+            let filename = tinyfiledialogs::open_file_dialog(
+                "Open",
+                "",
+                Some((&["*.gb", "*.gbc", "*.sgb", "*.zip", "*.gz"], "Gameboy ROM")),
+            );
+
+            let mut rom_location = match filename {
+                Some(filename) => RomLocation::new(PathBuf::from(filename)),
+                None => return,
+            };
+
+            if with_patch {
+                let patch = tinyfiledialogs::open_file_dialog(
+                    "Open patch",
+                    "",
+                    Some((&["*.ips", "*.ups", "*.bps"], "ROM patch")),
+                );
+
+                match patch {
+                    Some(patch) => rom_location.patch = Some(PathBuf::from(patch)),
+                    None => return,
+                }
+            }
+
+            rom_location_sender.send(rom_location).unwrap();
+        });
+    }
+
+    fn slot_buttons(&self, ui: &mut egui::Ui, signal: fn(usize) -> EmulationSignal) {
+        for slot in 0..SLOT_COUNT {
+            if ui.button(format!("Slot {}", slot + 1)).clicked() {
+                self.emulation_signal_sender.send(signal(slot)).unwrap();
+                ui.close_menu();
+            }
+        }
+    }
 }
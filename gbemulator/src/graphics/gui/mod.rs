@@ -2,10 +2,15 @@ mod controls_window;
 pub mod emulator_app;
 mod main_menu;
 pub mod palette_window;
+mod rom_chooser_window;
+mod save_state_window;
 
 pub struct State {
     controls_window_shown: bool,
     palette_window_shown: bool,
+    save_state_window_shown: bool,
+    rom_chooser_window_shown: bool,
+    game_running: bool,
 }
 
 impl State {
@@ -13,6 +18,9 @@ impl State {
         State {
             controls_window_shown: false,
             palette_window_shown: false,
+            save_state_window_shown: false,
+            rom_chooser_window_shown: false,
+            game_running: false,
         }
     }
 }
@@ -0,0 +1,53 @@
+use crate::graphics::gui::State;
+use crate::rom::rom_file::RomLocation;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+/// Lets the user pick one of the ROMs of a zip archive
+pub struct RomChooserWindow {
+    rom_location_sender: Sender<RomLocation>,
+    //Archive including a patch which has been chosen together with it
+    archive: RomLocation,
+    roms: Vec<String>,
+}
+
+impl RomChooserWindow {
+    pub fn new(rom_location_sender: Sender<RomLocation>) -> Self {
+        RomChooserWindow {
+            rom_location_sender,
+            archive: RomLocation::new(PathBuf::new()),
+            roms: Vec::new(),
+        }
+    }
+
+    pub fn set_archive(&mut self, archive: RomLocation, roms: Vec<String>) {
+        self.archive = archive;
+        self.roms = roms;
+    }
+
+    pub fn update(&mut self, ctx: &egui::CtxRef, state: &mut State) {
+        let mut chosen_rom = None;
+
+        egui::Window::new("Choose ROM")
+            .open(&mut state.rom_chooser_window_shown)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for rom in &self.roms {
+                        if ui.button(rom).clicked() {
+                            chosen_rom = Some(rom.clone());
+                        }
+                    }
+                });
+            });
+
+        if let Some(rom) = chosen_rom {
+            let rom_location = RomLocation {
+                entry: Some(rom),
+                ..self.archive.clone()
+            };
+
+            self.rom_location_sender.send(rom_location).unwrap();
+            state.rom_chooser_window_shown = false;
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::graphics::gui::State;
+use crate::savestate::save_state_slots::{SaveStateSlots, SLOT_COUNT};
+use crate::EmulationSignal;
+use egui::{TextureId, Vec2};
+use lib_gbemulation::gpu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const SLOTS_PER_ROW: usize = 5;
+//States saved with the hotkeys show up in the open window after this time
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Default)]
+struct Slot {
+    modified: Option<SystemTime>,
+    timestamp: Option<u64>,
+}
+
+/// Shows the thumbnails of the save state slots of the running game to save or load one
+pub struct SaveStateWindow {
+    emulation_signal_sender: Sender<EmulationSignal>,
+    save_state_slots: Option<SaveStateSlots>,
+    slots: [Slot; SLOT_COUNT],
+    thumbnail_textures: Vec<TextureId>,
+    //RGBA pixels which have to be written to the texture of the slot
+    thumbnail_uploads: Vec<(usize, Vec<u8>)>,
+    last_refresh: Option<Instant>,
+}
+
+impl SaveStateWindow {
+    pub fn new(emulation_signal_sender: Sender<EmulationSignal>) -> Self {
+        SaveStateWindow {
+            emulation_signal_sender,
+            save_state_slots: None,
+            slots: [Slot::default(); SLOT_COUNT],
+            thumbnail_textures: Vec::new(),
+            thumbnail_uploads: Vec::new(),
+            last_refresh: None,
+        }
+    }
+
+    pub fn set_save_state_slots(&mut self, save_state_slots: Option<SaveStateSlots>) {
+        self.save_state_slots = save_state_slots;
+        self.slots = [Slot::default(); SLOT_COUNT];
+        self.last_refresh = None;
+    }
+
+    /// One texture of the size of the screen for every slot
+    pub fn set_thumbnail_textures(&mut self, thumbnail_textures: Vec<TextureId>) {
+        self.thumbnail_textures = thumbnail_textures;
+    }
+
+    pub fn take_thumbnail_uploads(&mut self) -> Vec<(usize, Vec<u8>)> {
+        std::mem::take(&mut self.thumbnail_uploads)
+    }
+
+    pub fn update(&mut self, ctx: &egui::CtxRef, state: &mut State) {
+        if !state.save_state_window_shown {
+            return;
+        }
+
+        self.refresh();
+
+        let mut signal = None;
+
+        egui::Window::new("Save states")
+            .open(&mut state.save_state_window_shown)
+            .show(ctx, |ui| {
+                if self.save_state_slots.is_none() {
+                    ui.label("No game is running");
+                    return;
+                }
+
+                egui::Grid::new("save_state_slots").show(ui, |ui| {
+                    for (index, slot) in self.slots.iter().enumerate() {
+                        ui.vertical(|ui| {
+                            if let Some(clicked_signal) = self.show_slot(ui, index, slot.timestamp)
+                            {
+                                signal = Some(clicked_signal);
+                            }
+                        });
+
+                        if index % SLOTS_PER_ROW == SLOTS_PER_ROW - 1 {
+                            ui.end_row();
+                        }
+                    }
+                });
+            });
+
+        if let Some(signal) = signal {
+            self.emulation_signal_sender.send(signal).unwrap();
+        }
+    }
+
+    fn show_slot(
+        &self,
+        ui: &mut egui::Ui,
+        index: usize,
+        timestamp: Option<u64>,
+    ) -> Option<EmulationSignal> {
+        let thumbnail_size = Vec2::new(SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32) / 2.0;
+        let mut signal = None;
+
+        match (timestamp, self.thumbnail_textures.get(index)) {
+            (Some(_), Some(texture)) => {
+                if ui
+                    .add(egui::ImageButton::new(*texture, thumbnail_size))
+                    .on_hover_text("Load")
+                    .clicked()
+                {
+                    signal = Some(EmulationSignal::LoadState(index));
+                }
+            }
+            _ => {
+                ui.add_sized(thumbnail_size, egui::Label::new("Empty"));
+            }
+        }
+
+        ui.label(format!("Slot {}", index + 1));
+        ui.small(match timestamp {
+            Some(timestamp) => format_age(timestamp),
+            None => "-".to_string(),
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Save").clicked() {
+                signal = Some(EmulationSignal::SaveState(index));
+            }
+
+            if ui
+                .add_enabled(timestamp.is_some(), egui::Button::new("Load"))
+                .clicked()
+            {
+                signal = Some(EmulationSignal::LoadState(index));
+            }
+        });
+
+        signal
+    }
+
+    //Only slots which have been written since the last refresh are read again
+    fn refresh(&mut self) {
+        let save_state_slots = match self.save_state_slots {
+            Some(ref save_state_slots) => save_state_slots,
+            None => return,
+        };
+
+        if let Some(last_refresh) = self.last_refresh {
+            if last_refresh.elapsed() < REFRESH_INTERVAL {
+                return;
+            }
+        }
+        self.last_refresh = Some(Instant::now());
+
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let modified = save_state_slots.modified(index);
+
+            if modified == slot.modified {
+                continue;
+            }
+
+            slot.modified = modified;
+            slot.timestamp = None;
+
+            if let Some(info) = save_state_slots.read_info(index) {
+                slot.timestamp = Some(info.timestamp);
+                self.thumbnail_uploads.push((index, info.thumbnail));
+            }
+        }
+    }
+}
+
+fn format_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let seconds = now.saturating_sub(timestamp);
+
+    match seconds {
+        0..=59 => "Just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
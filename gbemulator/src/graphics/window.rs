@@ -5,20 +5,27 @@ use std::sync::{Arc, Mutex};
 use crate::config::config_storage::ConfigStorage;
 
 use crate::controls::keyboard_controller::KeyboardController;
-use crate::emulation::Emulation;
+use crate::controls::state_hotkey_controller::StateHotkeyController;
+use crate::controls::tilt_controller::TiltController;
+use crate::emulation::{show_error, Emulation};
 use crate::graphics::fps_checker::FpsChecker;
 use crate::graphics::gui::emulator_app::EmulatorApp;
+use crate::rom::rom_file::RomLocation;
+use crate::savestate::save_state_slots::SLOT_COUNT;
 use crate::EmulationSignal;
 use egui::FontDefinitions;
 use egui_wgpu_backend::ScreenDescriptor;
 use egui_winit_platform::PlatformDescriptor;
 use epi::App;
+use lib_gbemulation::cartridge::accelerometer::SharedAccelerometer;
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
 use lib_gbemulation::gpu::{SCREEN_HEIGHT, SCREEN_WIDTH};
 use lib_gbemulation::io::joypad::Joypad;
+use lib_gbemulation::sgb::{BORDER_HEIGHT, BORDER_WIDTH};
+use std::path::PathBuf;
 use std::rc::Rc;
-use std::string::String;
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::thread::sleep;
+use std::thread::{sleep, JoinHandle};
 use std::time::Duration;
 use wgpu::{FilterMode, Surface};
 use winit::dpi::PhysicalSize;
@@ -35,6 +42,11 @@ pub struct GraphicsWindow<'a> {
     height: u32,
     config_storage: &'a ConfigStorage,
     emulation_signal_sender: Option<Rc<Sender<EmulationSignal>>>,
+    emulation_thread: Option<JoinHandle<()>>,
+    //ROM which is currently running
+    rom_location: Option<RomLocation>,
+    //Save state which is loaded once the first ROM has been started
+    start_state_slot: Option<usize>,
 }
 
 struct ExampleRepaintSignal;
@@ -50,10 +62,19 @@ impl<'a> GraphicsWindow<'a> {
             height,
             config_storage,
             emulation_signal_sender: None,
+            emulation_thread: None,
+            rom_location: None,
+            start_state_slot: None,
         }
     }
 
-    pub async fn start(&mut self, gameboy_screen: Arc<GameboyScreen>) {
+    /// Opens the window and starts the given ROM, if any, from the given save state slot
+    pub async fn start(
+        &mut self,
+        gameboy_screen: Arc<GameboyScreen>,
+        rom_location: Option<RomLocation>,
+        state_slot: Option<usize>,
+    ) {
         let mut event_loop = EventLoop::new();
 
         let size = winit::dpi::PhysicalSize {
@@ -117,13 +138,61 @@ impl<'a> GraphicsWindow<'a> {
             label: Some("Screen Texture"),
         });
 
+        let border_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: BORDER_WIDTH as u32,
+                height: BORDER_HEIGHT as u32,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: Some("Border Texture"),
+        });
+
+        let thumbnail_textures: Vec<wgpu::Texture> = (0..SLOT_COUNT)
+            .map(|_| {
+                device.create_texture(&wgpu::TextureDescriptor {
+                    size: texture_size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    label: Some("Thumbnail Texture"),
+                })
+            })
+            .collect();
+
         let joypad = Arc::new(Mutex::new(Joypad::new()));
 
-        let emulation = Emulation::new(Arc::clone(&gameboy_screen), Arc::clone(&joypad));
+        let accelerometer = SharedAccelerometer::new();
+
+        let emulation = Emulation::new(
+            Arc::clone(&gameboy_screen),
+            Arc::clone(&joypad),
+            accelerometer.clone(),
+            Arc::clone(&self.config_storage.config),
+        );
 
         let keyboard_controller = KeyboardController::new(joypad, &self.config_storage);
+        let mut tilt_controller = TiltController::new(accelerometer);
+
+        let (rom_location_sender, rom_location_receiver) = channel();
+        if let Some(rom_location) = rom_location {
+            rom_location_sender.send(rom_location).unwrap();
+            self.start_state_slot = state_slot;
+        }
+        let (savegame_import_sender, savegame_import_receiver) = channel();
+        //Signals of the GUI and the hotkeys which are passed on to the running emulation
+        let (gui_signal_sender, gui_signal_receiver) = channel();
 
-        let (rom_filename_sender, rom_filename_receiver) = channel();
+        let mut state_hotkey_controller = StateHotkeyController::new(
+            Arc::clone(&self.config_storage.config),
+            gui_signal_sender.clone(),
+        );
 
         let mut platform = egui_winit_platform::Platform::new(PlatformDescriptor {
             physical_width: size.width,
@@ -135,8 +204,21 @@ impl<'a> GraphicsWindow<'a> {
 
         let mut egui_rpass = egui_wgpu_backend::RenderPass::new(&device, config.format, 1);
 
-        let mut emulator_gui_app =
-            EmulatorApp::new(rom_filename_sender, &self.config_storage.config);
+        let mut emulator_gui_app = EmulatorApp::new(
+            rom_location_sender,
+            savegame_import_sender,
+            gui_signal_sender,
+            &self.config_storage.config,
+        );
+
+        emulator_gui_app.set_thumbnail_textures(
+            thumbnail_textures
+                .iter()
+                .map(|texture| {
+                    egui_rpass.egui_texture_from_wgpu_texture(&device, texture, FilterMode::Nearest)
+                })
+                .collect(),
+        );
 
         let repaint_signal = std::sync::Arc::new(ExampleRepaintSignal {});
 
@@ -146,21 +228,34 @@ impl<'a> GraphicsWindow<'a> {
 
         event_loop.run_return(move |event, _, control_flow| {
             platform.handle_event(&event);
-            self.start_emulation(&rom_filename_receiver, &emulation);
+            self.start_emulation(&rom_location_receiver, &emulation, &mut emulator_gui_app);
+            self.import_savegame(&savegame_import_receiver, &emulation, &mut emulator_gui_app);
+
+            while let Ok(signal) = gui_signal_receiver.try_recv() {
+                if let Some(signal_sender) = &self.emulation_signal_sender {
+                    signal_sender.send(signal).unwrap();
+                }
+            }
 
             match event {
                 Event::WindowEvent { event, .. } => match event {
                     WindowEvent::CloseRequested => {
-                        if let Some(signal_sender) = &self.emulation_signal_sender {
-                            signal_sender.send(EmulationSignal::Quit).unwrap();
-                        }
+                        self.stop_emulation();
                         *control_flow = ControlFlow::Exit;
                         println!("Closing...");
                         return;
                     }
                     WindowEvent::KeyboardInput { input, .. } => {
                         emulator_gui_app.set_keyboard_input(input);
-                        handle_inputs(&keyboard_controller, &input);
+                        handle_inputs(
+                            &keyboard_controller,
+                            &mut tilt_controller,
+                            &mut state_hotkey_controller,
+                            &input,
+                        );
+                    }
+                    WindowEvent::CursorMoved { position, .. } => {
+                        tilt_controller.move_cursor(position, window.inner_size());
                     }
                     WindowEvent::Resized(physical_size) => {
                         resize(&surface, &mut config, &device, physical_size);
@@ -200,6 +295,17 @@ impl<'a> GraphicsWindow<'a> {
                         FilterMode::Nearest,
                     ));
 
+                    let border_tex = if gameboy_screen.has_border() {
+                        Some(egui_rpass.egui_texture_from_wgpu_texture(
+                            &device,
+                            &border_texture,
+                            FilterMode::Nearest,
+                        ))
+                    } else {
+                        None
+                    };
+                    emulator_gui_app.set_border_tex(border_tex);
+
                     emulator_gui_app.update(&platform.context(), &mut frame);
 
                     let (_output, paint_commands) = platform.end_frame(Some(&window));
@@ -226,6 +332,16 @@ impl<'a> GraphicsWindow<'a> {
                         .unwrap();
 
                     gameboy_screen.draw_to_queue(&queue, &screen_texture, texture_size);
+                    gameboy_screen.draw_border_to_queue(&queue, &border_texture);
+
+                    for (slot, thumbnail) in emulator_gui_app.take_thumbnail_uploads() {
+                        draw_thumbnail_to_queue(
+                            &queue,
+                            &thumbnail_textures[slot],
+                            texture_size,
+                            &thumbnail,
+                        );
+                    }
 
                     queue.submit(std::iter::once(encoder.finish()));
                     output.present();
@@ -251,38 +367,135 @@ impl<'a> GraphicsWindow<'a> {
 
     fn start_emulation(
         &mut self,
-        rom_filename_receiver: &Receiver<Option<String>>,
+        rom_location_receiver: &Receiver<RomLocation>,
         emulation: &Emulation,
+        emulator_gui_app: &mut EmulatorApp,
     ) {
-        if let Ok(filename) = rom_filename_receiver.try_recv() {
-            let rom_file: String;
-            match filename {
-                Some(file) => rom_file = file,
-                None => {
+        if let Ok(mut rom_location) = rom_location_receiver.try_recv() {
+            match rom_location.select_single_rom() {
+                Ok(roms) if !roms.is_empty() => {
+                    emulator_gui_app.choose_rom(rom_location, roms);
+                    return;
+                }
+                Ok(_) => rom_location.find_patch(),
+                Err(message) => {
+                    show_error("ROM", &message);
                     return;
                 }
             }
-            if let Some(sender) = &self.emulation_signal_sender {
-                //Stop running emulation
-                sender.send(EmulationSignal::Quit).unwrap();
+
+            self.stop_emulation();
+            self.run_rom(rom_location, emulation, emulator_gui_app);
+        }
+    }
+
+    /// The running game is stopped while its savegame is replaced and started again afterwards
+    fn import_savegame(
+        &mut self,
+        savegame_import_receiver: &Receiver<(PathBuf, SavegameFormat)>,
+        emulation: &Emulation,
+        emulator_gui_app: &mut EmulatorApp,
+    ) {
+        if let Ok((path, format)) = savegame_import_receiver.try_recv() {
+            let rom_location = match self.rom_location.clone() {
+                Some(rom_location) => rom_location,
+                None => return,
+            };
+
+            self.stop_emulation();
+
+            match emulation.import_savegame(&rom_location, &path, format) {
+                Ok(_) => println!("Imported the savegame from {}", path.display()),
+                Err(message) => show_error("Savegame", &message),
+            }
+
+            self.run_rom(rom_location, emulation, emulator_gui_app);
+        }
+    }
+
+    fn run_rom(
+        &mut self,
+        rom_location: RomLocation,
+        emulation: &Emulation,
+        emulator_gui_app: &mut EmulatorApp,
+    ) {
+        let (sender, emulation_thread) = match emulation.start(&rom_location) {
+            Ok(result) => result,
+            Err(message) => {
+                show_error("ROM", &message);
+                emulator_gui_app.set_save_state_slots(None);
+                self.rom_location = None;
+                return;
             }
+        };
+
+        if let Some(slot) = self.start_state_slot.take() {
+            sender.send(EmulationSignal::LoadState(slot)).unwrap();
+        }
 
-            let sender = emulation.start(&rom_file).unwrap();
+        self.emulation_signal_sender = Some(Rc::new(sender));
+        self.emulation_thread = Some(emulation_thread);
+        emulator_gui_app.set_save_state_slots(Some(emulation.save_state_slots(&rom_location)));
+        self.rom_location = Some(rom_location);
+    }
+
+    /// Waits until the running emulation has saved the game and stopped
+    fn stop_emulation(&mut self) {
+        if let Some(sender) = self.emulation_signal_sender.take() {
+            sender.send(EmulationSignal::Quit).unwrap();
+        }
 
-            self.emulation_signal_sender = Some(Rc::new(sender));
+        if let Some(emulation_thread) = self.emulation_thread.take() {
+            let _ = emulation_thread.join();
         }
     }
 }
 
-fn handle_inputs(keyboard_controller: &KeyboardController, input: &KeyboardInput) {
+fn handle_inputs(
+    keyboard_controller: &KeyboardController,
+    tilt_controller: &mut TiltController,
+    state_hotkey_controller: &mut StateHotkeyController,
+    input: &KeyboardInput,
+) {
     if let Some(keycode) = input.virtual_keycode {
         match input.state {
-            winit::event::ElementState::Pressed => keyboard_controller.push_key(keycode),
-            winit::event::ElementState::Released => keyboard_controller.release_key(keycode),
+            winit::event::ElementState::Pressed => {
+                keyboard_controller.push_key(keycode);
+                tilt_controller.push_key(keycode);
+                state_hotkey_controller.push_key(keycode);
+            }
+            winit::event::ElementState::Released => {
+                keyboard_controller.release_key(keycode);
+                tilt_controller.release_key(keycode);
+                state_hotkey_controller.release_key(keycode);
+            }
         }
     }
 }
 
+fn draw_thumbnail_to_queue(
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    texture_size: wgpu::Extent3d,
+    thumbnail: &[u8],
+) {
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        thumbnail,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(4 * SCREEN_WIDTH as u32),
+            rows_per_image: std::num::NonZeroU32::new(SCREEN_HEIGHT as u32),
+        },
+        texture_size,
+    );
+}
+
 fn resize(
     surface: &Surface,
     config: &mut wgpu::SurfaceConfiguration,
@@ -1,30 +1,150 @@
 #![windows_subsystem = "windows"]
 
+use crate::cli::options::{Options, USAGE};
+use crate::cli::run_command;
+use crate::cli::save_command;
 use crate::config::config_storage::ConfigStorage;
 use crate::graphics::gameboy_screen::{GameboyScreen, MENU_BAR_HEIGHT};
 
 use crate::graphics::window::GraphicsWindow;
+use crate::storage::data_directory;
 
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::{env, process};
 
 mod audio_output;
+mod camera;
+mod cli;
 mod config;
 mod controls;
 mod emulation;
+mod frame_timer;
 mod graphics;
+mod printer;
+mod rom;
 mod savegame;
+mod savestate;
+mod storage;
 
 pub enum EmulationSignal {
     Cycle,
     Quit,
+    //Slot index of the save state
+    SaveState(usize),
+    LoadState(usize),
+    //File the savegame is written to
+    ExportSavegame(PathBuf, SavegameFormat),
 }
 pub fn main() {
-    let config_storage = ConfigStorage::create_from_file("gbemulator.toml".to_string()).unwrap();
-    let mut window =
-        GraphicsWindow::new(160 * 3, (144 * 3) + MENU_BAR_HEIGHT as u32, &config_storage);
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if !args.is_empty() {
+        attach_console();
+    }
+
+    //Runs without a window and without the config, so the results do not depend on the settings
+    if args.first().map(|arg| arg.as_str()) == Some("run") {
+        match run_command::run(&args[1..]) {
+            Ok(exit_code) => process::exit(exit_code),
+            Err(message) => {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+    }
+
+    let is_save_command = args.first().map(|arg| arg.as_str()) == Some("save");
+
+    let options = if is_save_command {
+        None
+    } else {
+        match Options::parse(&args) {
+            Ok(options) if options.help => {
+                println!("{}", USAGE);
+                return;
+            }
+            Ok(options) => Some(options),
+            Err(message) => {
+                eprintln!("{}\n\n{}", message, USAGE);
+                process::exit(1);
+            }
+        }
+    };
+
+    let config_path = match options
+        .as_ref()
+        .and_then(|options| options.config_path.clone())
+    {
+        Some(config_path) => config_path,
+        None => {
+            let config_path = data_directory::config_file_path();
+
+            if let Err(message) = data_directory::migrate_config(&config_path) {
+                eprintln!("{}", message);
+            }
+
+            config_path
+        }
+    };
+
+    let config_storage = match ConfigStorage::create_from_file(config_path) {
+        Ok(config_storage) => config_storage,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+
+    let options = match options {
+        Some(options) => options,
+        None => {
+            if let Err(message) =
+                save_command::run(&args[1..], &config_storage.config.read().unwrap())
+            {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+            return;
+        }
+    };
+
+    {
+        let command_line = &mut config_storage.config.write().unwrap().command_line;
+        command_line.data_path = options.data_path;
+        command_line.boot_rom_path = options.boot_rom_path;
+        command_line.muted = options.muted;
+    }
+
+    let mut window = GraphicsWindow::new(
+        160 * options.scale,
+        (144 * options.scale) + MENU_BAR_HEIGHT as u32,
+        &config_storage,
+    );
 
     let gameboy_screen = Arc::new(GameboyScreen::new(config_storage.config.clone()));
-    pollster::block_on(window.start(gameboy_screen));
+    pollster::block_on(window.start(gameboy_screen, options.rom_location, options.state_slot));
 
     config_storage.save_to_file().unwrap();
 }
+
+/// The window subsystem starts without a console on Windows. Output of the command line
+/// options goes to the console the emulator was started from, if there is one
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    //Fails if the emulator was not started from a console, e.g. by opening a ROM in the explorer
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
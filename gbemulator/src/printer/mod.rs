@@ -0,0 +1 @@
+pub mod png_printer_output;
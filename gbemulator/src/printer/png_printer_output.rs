@@ -0,0 +1,69 @@
+use lib_gbemulation::io::printer::{PrinterOutput, PRINT_WIDTH};
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes every printed strip to a PNG file in the given directory
+pub struct PngPrinterOutput {
+    directory: PathBuf,
+    print_count: usize,
+}
+
+impl PngPrinterOutput {
+    pub fn new(directory: &str) -> Result<Self, String> {
+        let directory = Path::new(directory);
+
+        if let Err(e) = fs::create_dir_all(directory) {
+            return Err(format!("Could not create directory {:?}: {}", directory, e));
+        }
+
+        Ok(PngPrinterOutput {
+            directory: directory.to_path_buf(),
+            print_count: 0,
+        })
+    }
+
+    fn next_path(&mut self) -> PathBuf {
+        let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs(),
+            Err(_) => 0,
+        };
+
+        //The counter keeps multiple strips printed in the same second apart
+        self.print_count += 1;
+        self.directory
+            .join(format!("print-{}-{}.png", timestamp, self.print_count))
+    }
+}
+
+impl PrinterOutput for PngPrinterOutput {
+    fn print(&mut self, image: Vec<u8>, height: usize) {
+        let path = self.next_path();
+
+        if let Err(e) = write_grayscale_png(&path, &image, height) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+fn write_grayscale_png(path: &Path, image: &[u8], height: usize) -> Result<(), String> {
+    let file = match File::create(path) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("Could not create printout {:?}: {}", path, e)),
+    };
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), PRINT_WIDTH as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let result = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(image));
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Could not write printout {:?}: {}", path, e)),
+    }
+}
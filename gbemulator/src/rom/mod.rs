@@ -0,0 +1,3 @@
+mod patch;
+pub mod rom_file;
+mod zip_archive;
@@ -0,0 +1,469 @@
+use flate2::Crc;
+
+const IPS_MAGIC: &[u8] = b"PATCH";
+const IPS_END: &[u8] = b"EOF";
+const UPS_MAGIC: &[u8] = b"UPS1";
+const BPS_MAGIC: &[u8] = b"BPS1";
+
+//CRC32 of the source, the target and the patch itself at the end of UPS and BPS patches
+const CHECKSUMS_SIZE: usize = 12;
+
+/// Applies an IPS, UPS or BPS patch. The format is detected by the header of the patch
+pub fn apply_patch(rom: &[u8], patch: &[u8], max_size: usize) -> Result<Vec<u8>, String> {
+    if patch.starts_with(IPS_MAGIC) {
+        apply_ips(rom, patch, max_size)
+    } else if patch.starts_with(UPS_MAGIC) {
+        apply_ups(rom, patch, max_size)
+    } else if patch.starts_with(BPS_MAGIC) {
+        apply_bps(rom, patch, max_size)
+    } else {
+        Err("The patch is not an IPS, UPS or BPS file".to_string())
+    }
+}
+
+//Records of a 24 bit offset and a 16 bit size followed by the data. A size of 0 repeats a single
+//byte instead. The end marker can be followed by the size the ROM is truncated to
+fn apply_ips(rom: &[u8], patch: &[u8], max_size: usize) -> Result<Vec<u8>, String> {
+    let mut reader = PatchReader::new(patch, IPS_MAGIC.len());
+    let mut target = rom.to_vec();
+
+    loop {
+        if reader.peek(IPS_END.len())? == IPS_END {
+            reader.read_bytes(IPS_END.len())?;
+            break;
+        }
+
+        let offset = reader.read_u24()?;
+        let (size, data) = match reader.read_u16()? as usize {
+            0 => {
+                let size = reader.read_u16()? as usize;
+                (size, vec![reader.read_u8()?; size])
+            }
+            size => (size, reader.read_bytes(size)?.to_vec()),
+        };
+
+        resize_target(&mut target, offset + size, max_size)?;
+        target[offset..offset + size].copy_from_slice(&data);
+    }
+
+    if reader.remaining() == 3 {
+        target.truncate(reader.read_u24()?);
+    }
+
+    Ok(target)
+}
+
+//Runs of unchanged bytes followed by bytes which are XORed with the source until a zero byte
+fn apply_ups(rom: &[u8], patch: &[u8], max_size: usize) -> Result<Vec<u8>, String> {
+    let (source_crc, target_crc) = verify_checksums(patch)?;
+    let mut reader = PatchReader::new(&patch[..patch.len() - CHECKSUMS_SIZE], UPS_MAGIC.len());
+
+    let source_size = reader.read_number()?;
+    let target_size = reader.read_number()?;
+    verify_source(rom, source_size, source_crc)?;
+
+    let mut target = rom.to_vec();
+    target.truncate(target_size);
+    resize_target(&mut target, target_size, max_size)?;
+
+    let mut offset: usize = 0;
+    while reader.remaining() > 0 {
+        offset = next_offset(offset, reader.read_number()?)?;
+
+        loop {
+            let value = reader.read_u8()?;
+            if offset < target.len() {
+                target[offset] ^= value;
+            }
+            offset = next_offset(offset, 1)?;
+
+            if value == 0 {
+                break;
+            }
+        }
+    }
+
+    verify_target(&target, target_crc)?;
+    Ok(target)
+}
+
+//Commands which copy from the source, the patch or from earlier parts of the target
+fn apply_bps(rom: &[u8], patch: &[u8], max_size: usize) -> Result<Vec<u8>, String> {
+    let (source_crc, target_crc) = verify_checksums(patch)?;
+    let mut reader = PatchReader::new(&patch[..patch.len() - CHECKSUMS_SIZE], BPS_MAGIC.len());
+
+    let source_size = reader.read_number()?;
+    let target_size = reader.read_number()?;
+    verify_source(rom, source_size, source_crc)?;
+
+    if target_size > max_size {
+        return Err("The patched ROM is too large".to_string());
+    }
+
+    let metadata_size = reader.read_number()?;
+    reader.read_bytes(metadata_size)?;
+
+    let mut target = Vec::with_capacity(target_size);
+    let mut source_offset = 0;
+    let mut target_offset = 0;
+
+    while reader.remaining() > 0 {
+        let command = reader.read_number()?;
+        let length = (command >> 2) + 1;
+
+        if target.len() + length > target_size {
+            return Err("The patch writes beyond the end of the ROM".to_string());
+        }
+
+        match command & 0x03 {
+            //Source read, copies from the same position of the source
+            0 => {
+                let position = target.len();
+                target.extend_from_slice(read_range(rom, position, length)?);
+            }
+            //Target read, copies from the patch
+            1 => target.extend_from_slice(reader.read_bytes(length)?),
+            //Source copy, copies from a position relative to the last source copy
+            2 => {
+                source_offset = relative_offset(source_offset, reader.read_number()?)?;
+                target.extend_from_slice(read_range(rom, source_offset, length)?);
+                source_offset += length;
+            }
+            //Target copy, repeats earlier output byte by byte, so the ranges may overlap
+            _ => {
+                target_offset = relative_offset(target_offset, reader.read_number()?)?;
+                for _ in 0..length {
+                    match target.get(target_offset) {
+                        Some(&value) => target.push(value),
+                        None => return Err("The patch is damaged".to_string()),
+                    }
+                    target_offset += 1;
+                }
+            }
+        }
+    }
+
+    if target.len() != target_size {
+        return Err("The patch is damaged".to_string());
+    }
+
+    verify_target(&target, target_crc)?;
+    Ok(target)
+}
+
+//Checks the CRC of the patch and returns the expected CRCs of the source and the target
+fn verify_checksums(patch: &[u8]) -> Result<(u32, u32), String> {
+    if patch.len() < CHECKSUMS_SIZE + 4 {
+        return Err("The patch is damaged".to_string());
+    }
+
+    let checksums = &patch[patch.len() - CHECKSUMS_SIZE..];
+    let read_crc = |index: usize| {
+        u32::from_le_bytes([
+            checksums[index],
+            checksums[index + 1],
+            checksums[index + 2],
+            checksums[index + 3],
+        ])
+    };
+
+    if crc32(&patch[..patch.len() - 4]) != read_crc(8) {
+        return Err("The patch is damaged".to_string());
+    }
+
+    Ok((read_crc(0), read_crc(4)))
+}
+
+fn verify_source(rom: &[u8], source_size: usize, source_crc: u32) -> Result<(), String> {
+    if rom.len() != source_size || crc32(rom) != source_crc {
+        return Err(format!(
+            "The patch is made for a different ROM (expected CRC32 {:08X}, found {:08X})",
+            source_crc,
+            crc32(rom)
+        ));
+    }
+
+    Ok(())
+}
+
+fn verify_target(target: &[u8], target_crc: u32) -> Result<(), String> {
+    if crc32(target) != target_crc {
+        return Err("The patched ROM does not match the checksum of the patch".to_string());
+    }
+
+    Ok(())
+}
+
+fn resize_target(target: &mut Vec<u8>, size: usize, max_size: usize) -> Result<(), String> {
+    if size > max_size {
+        return Err("The patched ROM is too large".to_string());
+    }
+
+    if target.len() < size {
+        target.resize(size, 0);
+    }
+
+    Ok(())
+}
+
+fn read_range(data: &[u8], offset: usize, length: usize) -> Result<&[u8], String> {
+    match offset
+        .checked_add(length)
+        .and_then(|end| data.get(offset..end))
+    {
+        Some(range) => Ok(range),
+        None => Err("The patch reads beyond the end of the ROM".to_string()),
+    }
+}
+
+fn next_offset(offset: usize, distance: usize) -> Result<usize, String> {
+    offset
+        .checked_add(distance)
+        .ok_or_else(|| "The patch is damaged".to_string())
+}
+
+//The lowest bit is the sign, the remaining bits the distance
+fn relative_offset(offset: usize, value: usize) -> Result<usize, String> {
+    let distance = value >> 1;
+
+    let result = if value & 0x01 == 0x01 {
+        offset.checked_sub(distance)
+    } else {
+        offset.checked_add(distance)
+    };
+
+    result.ok_or_else(|| "The patch is damaged".to_string())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(data);
+    crc.sum()
+}
+
+struct PatchReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PatchReader<'a> {
+    fn new(data: &'a [u8], position: usize) -> Self {
+        PatchReader { data, position }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.position)
+    }
+
+    fn peek(&self, length: usize) -> Result<&'a [u8], String> {
+        match self
+            .position
+            .checked_add(length)
+            .and_then(|end| self.data.get(self.position..end))
+        {
+            Some(bytes) => Ok(bytes),
+            None => Err("The patch ends unexpectedly".to_string()),
+        }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let bytes = self.peek(length)?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, String> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u24(&mut self) -> Result<usize, String> {
+        let bytes = self.read_bytes(3)?;
+        Ok((bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize)
+    }
+
+    //Variable length number of UPS and BPS. Every byte adds 7 bits, the highest bit marks the last byte
+    fn read_number(&mut self) -> Result<usize, String> {
+        let mut number: usize = 0;
+        let mut shift: usize = 1;
+
+        loop {
+            let value = self.read_u8()? as usize;
+            number = (value & 0x7F)
+                .checked_mul(shift)
+                .and_then(|value| number.checked_add(value))
+                .ok_or_else(|| "The patch is damaged".to_string())?;
+
+            if value & 0x80 == 0x80 {
+                return Ok(number);
+            }
+
+            shift = shift
+                .checked_mul(0x80)
+                .ok_or_else(|| "The patch is damaged".to_string())?;
+            number = number
+                .checked_add(shift)
+                .ok_or_else(|| "The patch is damaged".to_string())?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROM: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+    const TARGET: [u8; 8] = [0, 1, 0xFF, 3, 4, 5, 6, 7];
+
+    fn encode_number(mut value: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        loop {
+            let low = (value & 0x7F) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                bytes.push(0x80 | low);
+                return bytes;
+            }
+
+            bytes.push(low);
+            value -= 1;
+        }
+    }
+
+    //Appends the CRCs of the source, the target and the patch itself
+    fn finish_patch(mut patch: Vec<u8>, source: &[u8], target: &[u8]) -> Vec<u8> {
+        patch.extend_from_slice(&crc32(source).to_le_bytes());
+        patch.extend_from_slice(&crc32(target).to_le_bytes());
+        let patch_crc = crc32(&patch);
+        patch.extend_from_slice(&patch_crc.to_le_bytes());
+        patch
+    }
+
+    fn ups_patch(records: &[u8]) -> Vec<u8> {
+        let mut patch = UPS_MAGIC.to_vec();
+        patch.extend(encode_number(ROM.len()));
+        patch.extend(encode_number(TARGET.len()));
+        patch.extend_from_slice(records);
+        finish_patch(patch, &ROM, &TARGET)
+    }
+
+    #[test]
+    fn applies_ips() {
+        let mut patch = IPS_MAGIC.to_vec();
+        //Record with data at 0x000002
+        patch.extend_from_slice(&[0x00, 0x00, 0x02, 0x00, 0x01, 0xFF]);
+        //Run of three 0xEE at 0x000008, which grows the ROM
+        patch.extend_from_slice(&[0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x03, 0xEE]);
+        patch.extend_from_slice(IPS_END);
+
+        let target = apply_patch(&ROM, &patch, 0x100).unwrap();
+        assert_eq!(target, [0, 1, 0xFF, 3, 4, 5, 6, 7, 0xEE, 0xEE, 0xEE]);
+    }
+
+    #[test]
+    fn truncates_with_ips() {
+        let mut patch = IPS_MAGIC.to_vec();
+        patch.extend_from_slice(IPS_END);
+        patch.extend_from_slice(&[0x00, 0x00, 0x04]);
+
+        assert_eq!(apply_patch(&ROM, &patch, 0x100).unwrap(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn rejects_ips_beyond_max_size() {
+        let mut patch = IPS_MAGIC.to_vec();
+        patch.extend_from_slice(&[0x00, 0x01, 0x00, 0x00, 0x01, 0xFF]);
+        patch.extend_from_slice(IPS_END);
+
+        assert!(apply_patch(&ROM, &patch, 0x100).is_err());
+    }
+
+    #[test]
+    fn applies_ups() {
+        //Skip two bytes, XOR the third with 0xFD and end the run
+        let patch = ups_patch(&[0x82, 0xFD, 0x00]);
+
+        assert_eq!(apply_patch(&ROM, &patch, 0x100).unwrap(), TARGET);
+    }
+
+    #[test]
+    fn rejects_ups_for_different_rom() {
+        let patch = ups_patch(&[0x82, 0xFD, 0x00]);
+        let mut rom = ROM;
+        rom[7] = 0x42;
+
+        let message = apply_patch(&rom, &patch, 0x100).unwrap_err();
+        assert!(message.contains("different ROM"));
+    }
+
+    #[test]
+    fn rejects_damaged_ups() {
+        let mut patch = ups_patch(&[0x82, 0xFD, 0x00]);
+        patch[6] ^= 0x01;
+
+        assert_eq!(
+            apply_patch(&ROM, &patch, 0x100).unwrap_err(),
+            "The patch is damaged"
+        );
+    }
+
+    #[test]
+    fn rejects_ups_offset_overflow() {
+        //A valid patch which skips to the end of the address space
+        let mut records = encode_number(usize::MAX);
+        records.extend_from_slice(&[0x01, 0x01, 0x00]);
+        let patch = ups_patch(&records);
+
+        assert!(apply_patch(&ROM, &patch, 0x100).is_err());
+    }
+
+    #[test]
+    fn applies_bps() {
+        let target = [0, 1, 0xFF, 3, 4, 5, 6, 7, 0, 1];
+
+        let mut patch = BPS_MAGIC.to_vec();
+        patch.extend(encode_number(ROM.len()));
+        patch.extend(encode_number(target.len()));
+        patch.extend(encode_number(0));
+        //Source read of 2 bytes
+        patch.extend(encode_number((2 - 1) << 2));
+        //Target read of 0xFF
+        patch.extend(encode_number(1));
+        patch.push(0xFF);
+        //Source copy of 5 bytes from offset 3
+        patch.extend(encode_number((5 - 1) << 2 | 2));
+        patch.extend(encode_number(3 << 1));
+        //Target copy of 2 bytes from offset 0
+        patch.extend(encode_number((2 - 1) << 2 | 3));
+        patch.extend(encode_number(0));
+        let patch = finish_patch(patch, &ROM, &target);
+
+        assert_eq!(apply_patch(&ROM, &patch, 0x100).unwrap(), target);
+    }
+
+    #[test]
+    fn rejects_bps_for_different_rom() {
+        let mut patch = BPS_MAGIC.to_vec();
+        patch.extend(encode_number(ROM.len()));
+        patch.extend(encode_number(ROM.len()));
+        patch.extend(encode_number(0));
+        patch.extend(encode_number((ROM.len() - 1) << 2));
+        let patch = finish_patch(patch, &ROM, &ROM);
+
+        assert_eq!(apply_patch(&ROM, &patch, 0x100).unwrap(), ROM);
+        assert!(apply_patch(&TARGET, &patch, 0x100)
+            .unwrap_err()
+            .contains("different ROM"));
+    }
+
+    #[test]
+    fn rejects_unknown_format() {
+        assert!(apply_patch(&ROM, b"NOPATCH", 0x100).is_err());
+    }
+}
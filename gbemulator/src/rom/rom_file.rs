@@ -0,0 +1,339 @@
+use crate::rom::patch;
+use crate::rom::zip_archive::ZipArchive;
+use crate::storage::data_directory;
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4B, 0x03, 0x04];
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+//The largest cartridges have 8 MiB of ROM, anything bigger in an archive is not a ROM
+const MAX_ROM_SIZE: usize = 8 * 1024 * 1024;
+const ROM_EXTENSIONS: [&str; 3] = ["gb", "gbc", "sgb"];
+//Patches next to the ROM are searched in this order
+const PATCH_EXTENSIONS: [&str; 3] = ["bps", "ups", "ips"];
+
+//Separates the archive from the ROM inside of it on the command line
+const ENTRY_SEPARATOR: char = '#';
+
+/// A ROM file or a ROM inside a zip archive. Gzip archives contain a single file
+/// so they do not need an entry
+#[derive(Clone, Debug)]
+pub struct RomLocation {
+    pub path: PathBuf,
+    pub entry: Option<String>,
+    //IPS, UPS or BPS patch which is applied to the ROM when it is loaded
+    pub patch: Option<PathBuf>,
+}
+
+impl RomLocation {
+    pub fn new(path: PathBuf) -> Self {
+        RomLocation {
+            path,
+            entry: None,
+            patch: None,
+        }
+    }
+
+    /// Accepts archive.zip#game.gb to select a ROM inside a zip archive
+    pub fn from_argument(argument: &str) -> Self {
+        if !Path::new(argument).exists() {
+            if let Some((path, entry)) = argument.rsplit_once(ENTRY_SEPARATOR) {
+                return RomLocation {
+                    path: PathBuf::from(path),
+                    entry: Some(entry.to_string()),
+                    patch: None,
+                };
+            }
+        }
+
+        Self::new(PathBuf::from(argument))
+    }
+
+    /// File name of the ROM, which is also used for the savegame. For gzip archives
+    /// it is the name of the archive, e.g. game.gb.gz
+    pub fn rom_file_name(&self) -> String {
+        let path = match self.entry {
+            Some(ref entry) => Path::new(entry),
+            None => self.path.as_path(),
+        };
+
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// File name of the patch, used to keep the saves of the patched game separate
+    pub fn patch_file_name(&self) -> Option<String> {
+        self.patch
+            .as_ref()
+            .and_then(|patch| patch.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+    }
+
+    /// Uses a patch with the name of the ROM next to the ROM or the archive,
+    /// e.g. game.ips for game.gb, unless a patch has been chosen already
+    pub fn find_patch(&mut self) {
+        if self.patch.is_some() {
+            return;
+        }
+
+        let rom_name = data_directory::get_rom_name(&self.rom_file_name());
+        let directory = self.path.parent().unwrap_or_else(|| Path::new(""));
+
+        self.patch = PATCH_EXTENSIONS
+            .iter()
+            .map(|extension| directory.join(format!("{}.{}", rom_name, extension)))
+            .find(|path| path.is_file());
+
+        if let Some(ref patch) = self.patch {
+            println!("Applying patch {}", patch.display());
+        }
+    }
+
+    /// Selects the ROM of a zip archive which contains exactly one. Returns the names
+    /// of the ROMs if there is more than one to choose from
+    pub fn select_single_rom(&mut self) -> Result<Vec<String>, String> {
+        let data = read_file(&self.path)?;
+        if !data.starts_with(&ZIP_MAGIC) {
+            return Ok(Vec::new());
+        }
+
+        let archive = ZipArchive::new(data)?;
+
+        if let Some(ref entry) = self.entry {
+            if !archive.file_names().contains(entry) {
+                return Err(format!(
+                    "{} does not contain {}",
+                    self.path.display(),
+                    entry
+                ));
+            }
+            return Ok(Vec::new());
+        }
+
+        let roms = list_roms(&archive);
+
+        match roms.len() {
+            0 => Err(format!("{} does not contain a ROM", self.path.display())),
+            1 => {
+                self.entry = roms.into_iter().next();
+                Ok(Vec::new())
+            }
+            _ => Ok(roms),
+        }
+    }
+}
+
+/// Reads the ROM, unpacking it if it is inside a zip or gzip archive and applying the patch
+pub fn read_rom(rom_location: &RomLocation) -> Result<Vec<u8>, String> {
+    let rom = unpack_rom(rom_location)?;
+
+    match rom_location.patch {
+        Some(ref path) => match patch::apply_patch(&rom, &read_file(path)?, MAX_ROM_SIZE) {
+            Ok(rom) => Ok(rom),
+            Err(message) => Err(format!("Could not apply {}: {}", path.display(), message)),
+        },
+        None => Ok(rom),
+    }
+}
+
+fn unpack_rom(rom_location: &RomLocation) -> Result<Vec<u8>, String> {
+    let data = read_file(&rom_location.path)?;
+
+    if data.starts_with(&ZIP_MAGIC) {
+        let archive = ZipArchive::new(data)?;
+
+        let entry = match rom_location.entry {
+            Some(ref entry) => entry.clone(),
+            None => match list_roms(&archive).as_slice() {
+                [entry] => entry.clone(),
+                [] => return Err("The zip archive does not contain a ROM".to_string()),
+                _ => return Err("The zip archive contains more than one ROM".to_string()),
+            },
+        };
+
+        return archive.read(&entry, MAX_ROM_SIZE);
+    }
+
+    if data.starts_with(&GZIP_MAGIC) {
+        let mut rom = Vec::new();
+        let result = GzDecoder::new(data.as_slice())
+            .take(MAX_ROM_SIZE as u64 + 1)
+            .read_to_end(&mut rom);
+
+        return match result {
+            Ok(_) if rom.len() > MAX_ROM_SIZE => Err("The gzip archive is too large".to_string()),
+            Ok(_) => Ok(rom),
+            Err(e) => Err(format!("Could not decompress the gzip archive: {}", e)),
+        };
+    }
+
+    Ok(data)
+}
+
+fn list_roms(archive: &ZipArchive) -> Vec<String> {
+    archive
+        .file_names()
+        .into_iter()
+        .filter(|name| {
+            Path::new(name)
+                .extension()
+                .map(|extension| {
+                    ROM_EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str())
+                })
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    match fs::read(path) {
+        Ok(data) => Ok(data),
+        Err(_) => Err(format!("Could not open file {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rom::zip_archive::tests::create_zip;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::env;
+    use std::io::Write;
+
+    const METHOD_STORED: u16 = 0;
+    const METHOD_DEFLATE: u16 = 8;
+    const ROM: &[u8] = b"First ROM";
+    const OTHER_ROM: &[u8] = b"Second ROM";
+
+    fn write_file(name: &str, data: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("gbemulator-{}-{}", std::process::id(), name));
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn multiple_roms_zip() -> Vec<u8> {
+        create_zip(&[
+            ("readme.txt", b"Readme", METHOD_STORED),
+            ("first.gb", ROM, METHOD_DEFLATE),
+            ("roms/second.GBC", OTHER_ROM, METHOD_STORED),
+        ])
+    }
+
+    #[test]
+    fn reads_uncompressed_rom() {
+        let path = write_file("plain.gb", ROM);
+        let rom = read_rom(&RomLocation::new(path.clone()));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(rom.unwrap(), ROM);
+    }
+
+    #[test]
+    fn unpacks_gzip() {
+        let path = write_file("game.gb.gz", &gzip(ROM));
+        let rom = read_rom(&RomLocation::new(path.clone()));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(rom.unwrap(), ROM);
+    }
+
+    #[test]
+    fn rejects_too_large_gzip() {
+        let path = write_file("large.gb.gz", &gzip(&vec![0; MAX_ROM_SIZE + 1]));
+        let rom = read_rom(&RomLocation::new(path.clone()));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(rom.err().unwrap(), "The gzip archive is too large");
+    }
+
+    #[test]
+    fn unpacks_single_rom_of_zip() {
+        let data = create_zip(&[
+            ("readme.txt", b"Readme", METHOD_STORED),
+            ("game.sgb", ROM, METHOD_DEFLATE),
+        ]);
+        let path = write_file("single.zip", &data);
+
+        let mut rom_location = RomLocation::new(path.clone());
+        let choices = rom_location.select_single_rom();
+        let rom = read_rom(&RomLocation::new(path.clone()));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(choices.unwrap(), Vec::<String>::new());
+        assert_eq!(rom_location.entry, Some("game.sgb".to_string()));
+        assert_eq!(rom.unwrap(), ROM);
+    }
+
+    #[test]
+    fn selects_rom_of_zip_by_argument() {
+        let path = write_file("multiple.zip", &multiple_roms_zip());
+        let argument = format!("{}#roms/second.GBC", path.display());
+
+        let mut rom_location = RomLocation::from_argument(&argument);
+        let choices = rom_location.select_single_rom();
+        let rom = read_rom(&rom_location);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(rom_location.path, path);
+        assert_eq!(rom_location.entry, Some("roms/second.GBC".to_string()));
+        assert_eq!(rom_location.rom_file_name(), "second.GBC");
+        assert_eq!(choices.unwrap(), Vec::<String>::new());
+        assert_eq!(rom.unwrap(), OTHER_ROM);
+    }
+
+    #[test]
+    fn lists_roms_of_zip_with_several() {
+        let path = write_file("choices.zip", &multiple_roms_zip());
+
+        let mut rom_location = RomLocation::new(path.clone());
+        let choices = rom_location.select_single_rom();
+        let rom = read_rom(&rom_location);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(choices.unwrap(), vec!["first.gb", "roms/second.GBC"]);
+        assert_eq!(rom_location.entry, None);
+        assert_eq!(
+            rom.err().unwrap(),
+            "The zip archive contains more than one ROM"
+        );
+    }
+
+    #[test]
+    fn rejects_missing_entry_and_zip_without_rom() {
+        let path = write_file("missing.zip", &multiple_roms_zip());
+        let mut rom_location = RomLocation::from_argument(&format!("{}#other.gb", path.display()));
+        let missing = rom_location.select_single_rom();
+        fs::remove_file(&path).unwrap();
+
+        let path = write_file("empty.zip", &create_zip(&[("readme.txt", b"", 0)]));
+        let empty = RomLocation::new(path.clone()).select_single_rom();
+        fs::remove_file(&path).unwrap();
+
+        assert!(missing
+            .err()
+            .unwrap()
+            .ends_with("does not contain other.gb"));
+        assert!(empty.err().unwrap().ends_with("does not contain a ROM"));
+    }
+
+    #[test]
+    fn keeps_existing_path_containing_separator() {
+        let path = write_file("name#with separator.gb", ROM);
+        let rom_location = RomLocation::from_argument(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(rom_location.path, path);
+        assert_eq!(rom_location.entry, None);
+    }
+}
@@ -0,0 +1,414 @@
+use flate2::read::DeflateDecoder;
+use flate2::Crc;
+use std::io::Read;
+
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054B50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x02014B50;
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034B50;
+
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+const CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+const LOCAL_HEADER_SIZE: usize = 30;
+//The end of the central directory can be followed by a comment of up to 64 KiB
+const MAX_COMMENT_SIZE: usize = 0xFFFF;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+const FLAG_ENCRYPTED: u16 = 0x01;
+
+struct ZipEntry {
+    name: String,
+    method: u16,
+    flags: u16,
+    crc: u32,
+    compressed_size: usize,
+    size: usize,
+    local_header_offset: usize,
+}
+
+/// Reads the files of a zip archive. Only what is needed for ROMs is supported,
+/// which leaves out encryption, ZIP64 and archives spanning multiple files
+pub struct ZipArchive {
+    data: Vec<u8>,
+    entries: Vec<ZipEntry>,
+}
+
+impl ZipArchive {
+    pub fn new(data: Vec<u8>) -> Result<Self, String> {
+        let end = find_end_of_central_directory(&data)?;
+        let entry_count = read_u16(&data, end + 10)? as usize;
+        let mut offset = read_u32(&data, end + 16)? as usize;
+        let mut entries = Vec::with_capacity(entry_count);
+
+        for _ in 0..entry_count {
+            if read_u32(&data, offset)? != CENTRAL_DIRECTORY_SIGNATURE {
+                return Err("Zip archive has an invalid central directory".to_string());
+            }
+
+            let name_length = read_u16(&data, offset + 28)? as usize;
+            let extra_length = read_u16(&data, offset + 30)? as usize;
+            let comment_length = read_u16(&data, offset + 32)? as usize;
+            let name_start = offset + CENTRAL_DIRECTORY_HEADER_SIZE;
+
+            let name = match data.get(name_start..name_start + name_length) {
+                Some(name) => String::from_utf8_lossy(name).to_string(),
+                None => return Err("Zip archive is truncated".to_string()),
+            };
+
+            entries.push(ZipEntry {
+                name,
+                method: read_u16(&data, offset + 10)?,
+                flags: read_u16(&data, offset + 8)?,
+                crc: read_u32(&data, offset + 16)?,
+                compressed_size: read_u32(&data, offset + 20)? as usize,
+                size: read_u32(&data, offset + 24)? as usize,
+                local_header_offset: read_u32(&data, offset + 42)? as usize,
+            });
+
+            offset = name_start + name_length + extra_length + comment_length;
+        }
+
+        Ok(ZipArchive { data, entries })
+    }
+
+    /// Names of the files including their directories. Directories themselves are left out
+    pub fn file_names(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.name.ends_with('/'))
+            .map(|entry| entry.name.clone())
+            .collect()
+    }
+
+    pub fn read(&self, name: &str, max_size: usize) -> Result<Vec<u8>, String> {
+        let entry = match self.entries.iter().find(|entry| entry.name == name) {
+            Some(entry) => entry,
+            None => return Err(format!("{} not found in the zip archive", name)),
+        };
+
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            return Err(format!("{} is encrypted", name));
+        }
+
+        if entry.size > max_size {
+            return Err(format!("{} is too large", name));
+        }
+
+        let offset = entry.local_header_offset;
+        if read_u32(&self.data, offset)? != LOCAL_HEADER_SIGNATURE {
+            return Err("Zip archive has an invalid file header".to_string());
+        }
+
+        //The extra field of the local header can differ from the one in the central directory
+        let name_length = read_u16(&self.data, offset + 26)? as usize;
+        let extra_length = read_u16(&self.data, offset + 28)? as usize;
+        let start = offset + LOCAL_HEADER_SIZE + name_length + extra_length;
+
+        let compressed = match self.data.get(start..start + entry.compressed_size) {
+            Some(compressed) => compressed,
+            None => return Err("Zip archive is truncated".to_string()),
+        };
+
+        let data = match entry.method {
+            METHOD_STORED => compressed.to_vec(),
+            METHOD_DEFLATE => {
+                let mut data = Vec::with_capacity(entry.size);
+                let result = DeflateDecoder::new(compressed)
+                    .take(max_size as u64)
+                    .read_to_end(&mut data);
+
+                if let Err(e) = result {
+                    return Err(format!("Could not decompress {}: {}", name, e));
+                }
+                data
+            }
+            method => {
+                return Err(format!(
+                    "{} uses the unsupported compression method {}",
+                    name, method
+                ))
+            }
+        };
+
+        let mut crc = Crc::new();
+        crc.update(&data);
+
+        if data.len() != entry.size || crc.sum() != entry.crc {
+            return Err(format!("{} is damaged", name));
+        }
+
+        Ok(data)
+    }
+}
+
+fn find_end_of_central_directory(data: &[u8]) -> Result<usize, String> {
+    if data.len() < END_OF_CENTRAL_DIRECTORY_SIZE {
+        return Err("File is not a zip archive".to_string());
+    }
+
+    let last = data.len() - END_OF_CENTRAL_DIRECTORY_SIZE;
+    let first = last.saturating_sub(MAX_COMMENT_SIZE);
+
+    for offset in (first..=last).rev() {
+        if read_u32(data, offset)? == END_OF_CENTRAL_DIRECTORY_SIGNATURE {
+            return Ok(offset);
+        }
+    }
+
+    Err("File is not a zip archive".to_string())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_le_bytes([bytes[0], bytes[1]])),
+        None => Err("Zip archive is truncated".to_string()),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err("Zip archive is truncated".to_string()),
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const ROM: &[u8] = b"Game Boy ROM data, Game Boy ROM data, Game Boy ROM data";
+
+    /// Creates a zip archive with the given names, contents and compression methods
+    pub(in crate::rom) fn create_zip(entries: &[(&str, &[u8], u16)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut central_directory = Vec::new();
+
+        for (name, content, method) in entries {
+            let compressed = match *method {
+                METHOD_DEFLATE => {
+                    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                    encoder.write_all(content).unwrap();
+                    encoder.finish().unwrap()
+                }
+                _ => content.to_vec(),
+            };
+
+            let mut crc = Crc::new();
+            crc.update(content);
+
+            //Version, flags, method, time and date, CRC and sizes
+            let mut fields = Vec::new();
+            fields.extend_from_slice(&20u16.to_le_bytes());
+            fields.extend_from_slice(&0u16.to_le_bytes());
+            fields.extend_from_slice(&method.to_le_bytes());
+            fields.extend_from_slice(&[0; 4]);
+            fields.extend_from_slice(&crc.sum().to_le_bytes());
+            fields.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            fields.extend_from_slice(&0u16.to_le_bytes());
+
+            central_directory.extend_from_slice(&CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+            central_directory.extend_from_slice(&20u16.to_le_bytes());
+            central_directory.extend_from_slice(&fields);
+            //Comment length, disk, attributes and the offset of the local header
+            central_directory.extend_from_slice(&[0; 10]);
+            central_directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            central_directory.extend_from_slice(name.as_bytes());
+
+            data.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+            data.extend_from_slice(&fields);
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(&compressed);
+        }
+
+        let central_directory_offset = data.len() as u32;
+        data.extend_from_slice(&central_directory);
+
+        data.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        data.extend_from_slice(&(central_directory.len() as u32).to_le_bytes());
+        data.extend_from_slice(&central_directory_offset.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data
+    }
+
+    fn end_of_central_directory(data: &[u8]) -> usize {
+        data.len() - END_OF_CENTRAL_DIRECTORY_SIZE
+    }
+
+    #[test]
+    fn reads_stored_entry() {
+        let archive = ZipArchive::new(create_zip(&[("game.gb", ROM, METHOD_STORED)])).unwrap();
+        assert_eq!(archive.read("game.gb", ROM.len()).unwrap(), ROM);
+    }
+
+    #[test]
+    fn reads_deflated_entry() {
+        let data = create_zip(&[("game.gb", ROM, METHOD_DEFLATE)]);
+        assert!(data.len() < ROM.len() + 100);
+
+        let archive = ZipArchive::new(data).unwrap();
+        assert_eq!(archive.read("game.gb", ROM.len()).unwrap(), ROM);
+    }
+
+    #[test]
+    fn reads_archive_with_comment() {
+        let mut data = create_zip(&[("game.gb", ROM, METHOD_STORED)]);
+        let comment = b"Archive comment";
+        let comment_length = end_of_central_directory(&data) + 20;
+        data[comment_length..].copy_from_slice(&(comment.len() as u16).to_le_bytes());
+        data.extend_from_slice(comment);
+
+        let archive = ZipArchive::new(data).unwrap();
+        assert_eq!(archive.read("game.gb", ROM.len()).unwrap(), ROM);
+    }
+
+    #[test]
+    fn rejects_crc_mismatch() {
+        let mut data = create_zip(&[("game.gb", ROM, METHOD_STORED)]);
+        data[LOCAL_HEADER_SIZE + "game.gb".len()] ^= 0xFF;
+
+        let archive = ZipArchive::new(data).unwrap();
+        assert_eq!(
+            archive.read("game.gb", ROM.len()).err().unwrap(),
+            "game.gb is damaged"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_end_of_central_directory() {
+        let data = create_zip(&[("game.gb", ROM, METHOD_STORED)]);
+
+        for length in [data.len() - 5, END_OF_CENTRAL_DIRECTORY_SIZE - 1, 0] {
+            assert_eq!(
+                ZipArchive::new(data[..length].to_vec()).err().unwrap(),
+                "File is not a zip archive"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_central_directory() {
+        let data = create_zip(&[("game.gb", ROM, METHOD_STORED)]);
+        let end = end_of_central_directory(&data);
+
+        //The central directory starts behind the end of the file
+        let mut beyond_end = data.clone();
+        beyond_end[end + 16..end + 20].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        assert_eq!(
+            ZipArchive::new(beyond_end).err().unwrap(),
+            "Zip archive is truncated"
+        );
+
+        //The name of the entry goes beyond the end of the file
+        let mut long_name = data.clone();
+        let central_directory = u32::from_le_bytes(data[end + 16..end + 20].try_into().unwrap());
+        let name_length = central_directory as usize + 28;
+        long_name[name_length..name_length + 2].copy_from_slice(&0xFFFFu16.to_le_bytes());
+        assert_eq!(
+            ZipArchive::new(long_name).err().unwrap(),
+            "Zip archive is truncated"
+        );
+
+        //More entries than the central directory contains
+        let mut missing_entry = data;
+        missing_entry[end + 10..end + 12].copy_from_slice(&2u16.to_le_bytes());
+        assert_eq!(
+            ZipArchive::new(missing_entry).err().unwrap(),
+            "Zip archive has an invalid central directory"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_entry() {
+        let mut data = create_zip(&[("game.gb", ROM, METHOD_STORED)]);
+
+        //The entry claims to go beyond the end of the file
+        let end = end_of_central_directory(&data);
+        let central_directory = u32::from_le_bytes(data[end + 16..end + 20].try_into().unwrap());
+        let compressed_size = central_directory as usize + 20;
+        data[compressed_size..compressed_size + 4].copy_from_slice(&0x10000u32.to_le_bytes());
+
+        let archive = ZipArchive::new(data).unwrap();
+        assert_eq!(
+            archive.read("game.gb", ROM.len()).err().unwrap(),
+            "Zip archive is truncated"
+        );
+    }
+
+    #[test]
+    fn rejects_entry_larger_than_max_size() {
+        let archive = ZipArchive::new(create_zip(&[("game.gb", ROM, METHOD_DEFLATE)])).unwrap();
+
+        assert_eq!(
+            archive.read("game.gb", ROM.len() - 1).err().unwrap(),
+            "game.gb is too large"
+        );
+    }
+
+    #[test]
+    fn stops_decompressing_at_max_size() {
+        let large = vec![0; 64 * 1024];
+        let mut data = create_zip(&[("game.gb", &large, METHOD_DEFLATE)]);
+
+        //Claims to be small in the central directory
+        let end = end_of_central_directory(&data);
+        let central_directory = u32::from_le_bytes(data[end + 16..end + 20].try_into().unwrap());
+        let size = central_directory as usize + 24;
+        data[size..size + 4].copy_from_slice(&16u32.to_le_bytes());
+
+        let archive = ZipArchive::new(data).unwrap();
+        assert_eq!(
+            archive.read("game.gb", 1024).err().unwrap(),
+            "game.gb is damaged"
+        );
+    }
+
+    #[test]
+    fn lists_file_names() {
+        let archive = ZipArchive::new(create_zip(&[
+            ("readme.txt", b"Readme", METHOD_STORED),
+            ("roms/", b"", METHOD_STORED),
+            ("roms/first.gb", ROM, METHOD_DEFLATE),
+            ("roms/second.gbc", ROM, METHOD_STORED),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            archive.file_names(),
+            vec!["readme.txt", "roms/first.gb", "roms/second.gbc"]
+        );
+        assert_eq!(archive.read("roms/second.gbc", ROM.len()).unwrap(), ROM);
+        assert_eq!(
+            archive.read("second.gbc", ROM.len()).err().unwrap(),
+            "second.gbc not found in the zip archive"
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_entries() {
+        let mut data = create_zip(&[("game.gb", ROM, 14)]);
+        let archive = ZipArchive::new(data.clone()).unwrap();
+        assert_eq!(
+            archive.read("game.gb", ROM.len()).err().unwrap(),
+            "game.gb uses the unsupported compression method 14"
+        );
+
+        let end = end_of_central_directory(&data);
+        let central_directory = u32::from_le_bytes(data[end + 16..end + 20].try_into().unwrap());
+        let flags = central_directory as usize + 8;
+        data[flags..flags + 2].copy_from_slice(&FLAG_ENCRYPTED.to_le_bytes());
+
+        let archive = ZipArchive::new(data).unwrap();
+        assert_eq!(
+            archive.read("game.gb", ROM.len()).err().unwrap(),
+            "game.gb is encrypted"
+        );
+    }
+}
@@ -1,29 +1,42 @@
 use lib_gbemulation::cartridge::RamDumper;
 use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
 
 pub struct FilesystemRamDumper {
-    filename: String,
+    filename: PathBuf,
 }
 
 impl FilesystemRamDumper {
-    pub fn new(rom_filename: &String) -> Self {
-        let rom_name = if rom_filename.ends_with(".gb") {
-            &rom_filename[..rom_filename.len() - 3]
-        } else {
-            rom_filename
-        };
-
-        let savegame_filename = format!("{}.sav", rom_name);
-
-        FilesystemRamDumper {
-            filename: savegame_filename,
-        }
+    pub fn new(filename: PathBuf) -> Self {
+        FilesystemRamDumper { filename }
     }
 }
 
 impl RamDumper for FilesystemRamDumper {
-    fn dump(&self, data: &Vec<u8>) {
-        fs::write(&self.filename, data).unwrap();
+    /// Writes to a temporary file first and replaces the savegame afterwards,
+    /// so the old savegame is kept if the emulator crashes while writing
+    fn dump(&self, data: &Vec<u8>) -> Result<(), String> {
+        let temp_filename = PathBuf::from(format!("{}.tmp", self.filename.display()));
+
+        let result = File::create(&temp_filename)
+            .and_then(|mut file| {
+                file.write_all(data)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_filename, &self.filename));
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_filename);
+            return Err(format!(
+                "Could not write savegame {}: {}",
+                self.filename.display(),
+                e
+            ));
+        }
+
+        Ok(())
     }
 
     fn load(&self) -> Option<Vec<u8>> {
@@ -1 +1,2 @@
 pub mod filesystem_ram_dumper;
+pub mod savegame_transfer;
@@ -0,0 +1,51 @@
+use crate::config::config::Config;
+use crate::rom::rom_file::RomLocation;
+use crate::savegame::filesystem_ram_dumper::FilesystemRamDumper;
+use crate::storage::data_directory::DataDirectory;
+use lib_gbemulation::cartridge::savegame_format::SavegameFormat;
+use lib_gbemulation::cartridge::RamDumper;
+use std::fs;
+use std::path::Path;
+
+/// Format of a savegame file by its extension. Unknown extensions are treated as .sav
+pub fn format_from_path(path: &Path) -> SavegameFormat {
+    path.extension()
+        .and_then(|extension| SavegameFormat::from_extension(&extension.to_string_lossy()))
+        .unwrap_or(SavegameFormat::Sav)
+}
+
+/// Savegame of the ROM in the data directory, including one of an earlier version
+pub fn open_savegame(
+    config: &Config,
+    rom_location: &RomLocation,
+) -> Result<FilesystemRamDumper, String> {
+    let rom_directory = DataDirectory::from_config(config).rom_directory(rom_location);
+    rom_directory.create()?;
+    rom_directory.migrate_savegame()?;
+
+    Ok(FilesystemRamDumper::new(rom_directory.savegame_path()))
+}
+
+pub fn export_savegame(
+    ram_dumper: &dyn RamDumper,
+    path: &Path,
+    format: SavegameFormat,
+) -> Result<(), String> {
+    let data = ram_dumper.export(format)?;
+
+    match fs::write(path, data) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Could not write {}: {}", path.display(), e)),
+    }
+}
+
+pub fn import_savegame(
+    ram_dumper: &dyn RamDumper,
+    path: &Path,
+    format: SavegameFormat,
+) -> Result<(), String> {
+    match fs::read(path) {
+        Ok(data) => ram_dumper.import(&data, format),
+        Err(e) => Err(format!("Could not read {}: {}", path.display(), e)),
+    }
+}
@@ -0,0 +1 @@
+pub mod save_state_slots;
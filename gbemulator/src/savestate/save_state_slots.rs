@@ -0,0 +1,211 @@
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use lib_gbemulation::gpu::{BUFFER_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
+use std::fs;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SLOT_COUNT: usize = 10;
+
+//Magic (4 bytes), version (1 byte), unix timestamp (8 bytes) and the length of the thumbnail (4 bytes)
+const SLOT_MAGIC: &[u8; 4] = b"GBSS";
+const SLOT_VERSION: u8 = 1;
+const SLOT_HEADER_SIZE: usize = 17;
+
+/// What the slot picker shows of a save state
+pub struct SlotInfo {
+    pub timestamp: u64,
+    /// RGBA pixels of the screen at the time the state was saved
+    pub thumbnail: Vec<u8>,
+}
+
+/// Numbered save state files of one ROM. Every file contains the time it was saved,
+/// a PNG thumbnail of the screen and the compressed state of the machine
+#[derive(Clone)]
+pub struct SaveStateSlots {
+    directory: PathBuf,
+}
+
+impl SaveStateSlots {
+    pub fn new(directory: PathBuf) -> Self {
+        SaveStateSlots { directory }
+    }
+
+    pub fn save(
+        &self,
+        slot: usize,
+        state: &[u8],
+        screen_buffer: &[u8; BUFFER_SIZE],
+    ) -> Result<(), String> {
+        let path = self.slot_path(slot);
+        let thumbnail = encode_thumbnail(screen_buffer)?;
+
+        let mut data = Vec::with_capacity(SLOT_HEADER_SIZE + thumbnail.len() + state.len());
+        data.extend_from_slice(SLOT_MAGIC);
+        data.push(SLOT_VERSION);
+        data.extend_from_slice(&get_timestamp().to_le_bytes());
+        data.extend_from_slice(&(thumbnail.len() as u32).to_le_bytes());
+        data.extend_from_slice(&thumbnail);
+
+        let mut encoder = ZlibEncoder::new(data, Compression::default());
+        let data = match encoder.write_all(state).and_then(|_| encoder.finish()) {
+            Ok(data) => data,
+            Err(e) => return Err(format!("Could not compress save state: {}", e)),
+        };
+
+        //Same as the savegame, the old state is kept if writing the new one fails
+        let temp_path = path.with_extension("tmp");
+
+        let result = File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(&data)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, &path));
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(format!(
+                "Could not write save state {}: {}",
+                path.display(),
+                e
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the uncompressed state of the machine
+    pub fn load(&self, slot: usize) -> Result<Vec<u8>, String> {
+        let data = self.read_slot(slot)?;
+        let (_, _, compressed_state) = split_slot(&data)?;
+
+        let mut state = Vec::new();
+        if let Err(e) = ZlibDecoder::new(compressed_state).read_to_end(&mut state) {
+            return Err(format!("Could not decompress save state: {}", e));
+        }
+
+        Ok(state)
+    }
+
+    /// None if the slot is empty or can not be read
+    pub fn read_info(&self, slot: usize) -> Option<SlotInfo> {
+        let data = self.read_slot(slot).ok()?;
+        let (timestamp, thumbnail, _) = split_slot(&data).ok()?;
+
+        Some(SlotInfo {
+            timestamp,
+            thumbnail: decode_thumbnail(thumbnail).ok()?,
+        })
+    }
+
+    /// Used to notice when a slot has been written
+    pub fn modified(&self, slot: usize) -> Option<SystemTime> {
+        fs::metadata(self.slot_path(slot))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn read_slot(&self, slot: usize) -> Result<Vec<u8>, String> {
+        let path = self.slot_path(slot);
+
+        match fs::read(&path) {
+            Ok(data) => Ok(data),
+            Err(e) => Err(format!(
+                "Could not read save state {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    //Slots are shown starting from 1
+    fn slot_path(&self, slot: usize) -> PathBuf {
+        self.directory.join(format!("slot{}.state", slot + 1))
+    }
+}
+
+//Splits a slot file into the timestamp, the thumbnail and the compressed state
+fn split_slot(data: &[u8]) -> Result<(u64, &[u8], &[u8]), String> {
+    if data.len() < SLOT_HEADER_SIZE || &data[0..4] != SLOT_MAGIC {
+        return Err("File is not a save state".to_string());
+    }
+
+    if data[4] != SLOT_VERSION {
+        return Err(format!("Unsupported save state version: {}", data[4]));
+    }
+
+    let mut timestamp = [0; 8];
+    timestamp.copy_from_slice(&data[5..13]);
+
+    let mut thumbnail_length = [0; 4];
+    thumbnail_length.copy_from_slice(&data[13..17]);
+    let thumbnail_end = SLOT_HEADER_SIZE + u32::from_le_bytes(thumbnail_length) as usize;
+
+    if data.len() < thumbnail_end {
+        return Err("Save state is truncated".to_string());
+    }
+
+    Ok((
+        u64::from_le_bytes(timestamp),
+        &data[SLOT_HEADER_SIZE..thumbnail_end],
+        &data[thumbnail_end..],
+    ))
+}
+
+fn encode_thumbnail(screen_buffer: &[u8; BUFFER_SIZE]) -> Result<Vec<u8>, String> {
+    let mut png_data = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut png_data, SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let result = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(screen_buffer));
+
+    match result {
+        Ok(_) => Ok(png_data),
+        Err(e) => Err(format!("Could not create thumbnail: {}", e)),
+    }
+}
+
+//Only accepts the RGB images written by encode_thumbnail
+fn decode_thumbnail(png_data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = match png::Decoder::new(png_data).read_info() {
+        Ok(reader) => reader,
+        Err(e) => return Err(format!("Could not read thumbnail: {}", e)),
+    };
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = match reader.next_frame(&mut buffer) {
+        Ok(info) => info,
+        Err(e) => return Err(format!("Could not read thumbnail: {}", e)),
+    };
+
+    if info.width as usize != SCREEN_WIDTH
+        || info.height as usize != SCREEN_HEIGHT
+        || info.color_type != png::ColorType::Rgb
+        || info.bit_depth != png::BitDepth::Eight
+    {
+        return Err("Thumbnail has an unexpected format".to_string());
+    }
+
+    let mut thumbnail = Vec::with_capacity(SCREEN_WIDTH * SCREEN_HEIGHT * 4);
+    for pixel in buffer[..info.buffer_size()].chunks(3) {
+        thumbnail.extend_from_slice(pixel);
+        thumbnail.push(255);
+    }
+
+    Ok(thumbnail)
+}
+
+fn get_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
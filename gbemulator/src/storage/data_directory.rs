@@ -0,0 +1,176 @@
+use crate::config::config::Config;
+use crate::rom::rom_file::RomLocation;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const APPLICATION_NAME: &str = "gbemulator";
+const CONFIG_FILENAME: &str = "gbemulator.toml";
+
+const SAVES_FOLDER: &str = "saves";
+const STATES_FOLDER: &str = "states";
+const SCREENSHOTS_FOLDER: &str = "screenshots";
+
+//Extensions which are removed from the ROM filename to get the name of the game
+const ROM_EXTENSIONS: [&str; 5] = ["gb", "gbc", "sgb", "zip", "gz"];
+
+/// Location of the config file. Uses the config directory of the platform,
+/// e.g. ~/.config/gbemulator on Linux, and falls back to the current directory
+pub fn config_file_path() -> PathBuf {
+    match dirs::config_dir() {
+        Some(path) => path.join(APPLICATION_NAME).join(CONFIG_FILENAME),
+        None => PathBuf::from(CONFIG_FILENAME),
+    }
+}
+
+/// Earlier versions stored the config in the current directory. It is copied to the
+/// config path if there is no config yet
+pub fn migrate_config(config_path: &Path) -> Result<(), String> {
+    migrate_file(Path::new(CONFIG_FILENAME), config_path)
+}
+
+/// Directory containing a subfolder for every ROM with its saves, states and screenshots
+pub struct DataDirectory {
+    path: PathBuf,
+}
+
+impl DataDirectory {
+    pub fn new(path: PathBuf) -> Self {
+        DataDirectory { path }
+    }
+
+    /// Uses the data path of the command line or the config, or the data directory
+    /// of the platform, e.g. ~/.local/share/gbemulator on Linux
+    pub fn from_config(config: &Config) -> Self {
+        let data_path = config
+            .command_line
+            .data_path
+            .as_ref()
+            .or(config.data_path.as_ref());
+
+        let path = match data_path {
+            Some(path) => PathBuf::from(path),
+            None => match dirs::data_dir() {
+                Some(path) => path.join(APPLICATION_NAME),
+                None => PathBuf::from("."),
+            },
+        };
+
+        Self::new(path)
+    }
+
+    /// Directory of the ROM. For ROMs inside an archive it is named after the ROM, not the archive.
+    /// Patched ROMs get their own directory, e.g. "game (game.ips)"
+    pub fn rom_directory(&self, rom_location: &RomLocation) -> RomDirectory {
+        let mut rom_name = get_rom_name(&rom_location.rom_file_name());
+
+        if let Some(patch_file_name) = rom_location.patch_file_name() {
+            rom_name = format!("{} ({})", rom_name, patch_file_name);
+        }
+
+        RomDirectory {
+            path: self.path.join(&rom_name),
+            rom_name,
+            legacy_savegame_path: match rom_location.patch {
+                Some(_) => None,
+                None => Some(legacy_savegame_path(&rom_location.path)),
+            },
+        }
+    }
+}
+
+pub struct RomDirectory {
+    path: PathBuf,
+    rom_name: String,
+    //Savegame of an earlier version, which never belongs to a patched ROM
+    legacy_savegame_path: Option<PathBuf>,
+}
+
+impl RomDirectory {
+    /// Creates the subfolders if they do not exist yet
+    pub fn create(&self) -> Result<(), String> {
+        for folder in [SAVES_FOLDER, STATES_FOLDER, SCREENSHOTS_FOLDER] {
+            let path = self.path.join(folder);
+
+            if let Err(e) = fs::create_dir_all(&path) {
+                return Err(format!(
+                    "Could not create directory {}: {}",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn savegame_path(&self) -> PathBuf {
+        self.path
+            .join(SAVES_FOLDER)
+            .join(format!("{}.sav", self.rom_name))
+    }
+
+    pub fn states_path(&self) -> PathBuf {
+        self.path.join(STATES_FOLDER)
+    }
+
+    /// Earlier versions stored the savegame next to the ROM. It is copied to the savegame
+    /// path if there is no savegame yet
+    pub fn migrate_savegame(&self) -> Result<(), String> {
+        match self.legacy_savegame_path {
+            Some(ref legacy_path) => migrate_file(legacy_path, &self.savegame_path()),
+            None => Ok(()),
+        }
+    }
+}
+
+//The old file is kept in case it is still used by an earlier version
+fn migrate_file(legacy_path: &Path, path: &Path) -> Result<(), String> {
+    if path.exists() || !legacy_path.is_file() {
+        return Ok(());
+    }
+
+    if let Some(directory) = path.parent() {
+        if let Err(e) = fs::create_dir_all(directory) {
+            return Err(format!(
+                "Could not create directory {}: {}",
+                directory.display(),
+                e
+            ));
+        }
+    }
+
+    if let Err(e) = fs::copy(legacy_path, path) {
+        return Err(format!(
+            "Could not copy {} to {}: {}",
+            legacy_path.display(),
+            path.display(),
+            e
+        ));
+    }
+
+    println!("Copied {} to {}", legacy_path.display(), path.display());
+    Ok(())
+}
+
+/// Name of the ROM file without its extensions, e.g. "game" for game.gbc or game.gb.zip
+pub fn get_rom_name(rom_file_name: &str) -> String {
+    let mut name = rom_file_name.to_string();
+
+    while let Some((stem, extension)) = name.rsplit_once('.') {
+        if stem.is_empty() || !ROM_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
+            break;
+        }
+
+        name = stem.to_string();
+    }
+
+    name
+}
+
+//Earlier versions only removed a .gb extension, so game.gbc was saved as game.gbc.sav
+fn legacy_savegame_path(rom_path: &Path) -> PathBuf {
+    let rom_path = rom_path.to_string_lossy();
+    let rom_name = rom_path.strip_suffix(".gb").unwrap_or(&rom_path);
+
+    PathBuf::from(format!("{}.sav", rom_name))
+}
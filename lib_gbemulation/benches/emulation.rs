@@ -0,0 +1,109 @@
+//! Measures how many frames per second the emulation core can produce without any frontend.
+//! Runs a small built-in program by default. Set GB_BENCH_ROM to a ROM path to benchmark a
+//! real game and GB_BENCH_FRAMES to change the amount of emulated frames.
+
+use lib_gbemulation::apu::apu::Apu;
+use lib_gbemulation::apu::AudioOutput;
+use lib_gbemulation::cartridge;
+use lib_gbemulation::cpu::cpu::Cpu;
+use lib_gbemulation::emulation;
+use lib_gbemulation::emulation::Emulation;
+use lib_gbemulation::gpu::gpu::Gpu;
+use lib_gbemulation::gpu::{Screen, BUFFER_SIZE};
+use lib_gbemulation::io::joypad::Joypad;
+use lib_gbemulation::memory::mmu::Mmu;
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::time::Instant;
+
+const DEFAULT_FRAMES: usize = 3000;
+const REAL_FPS: f64 = emulation::FPS as f64;
+
+struct NullScreen;
+
+impl Screen for NullScreen {
+    fn draw(&self, _screen_buffer: &[u8; BUFFER_SIZE]) {}
+
+    fn get_palette(&self) -> [[u8; 3]; 4] {
+        [[255, 255, 255], [170, 170, 170], [85, 85, 85], [0, 0, 0]]
+    }
+}
+
+struct NullAudioOutput;
+
+impl AudioOutput for NullAudioOutput {
+    fn output(&mut self, _sample: (i16, i16)) {}
+
+    fn get_sample_rate(&self) -> u32 {
+        44100
+    }
+}
+
+/// Builds a ROM only cartridge with a loop that uses loads, ALU, CB, stack and call instructions
+fn create_bench_rom() -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+
+    let program: [u8; 29] = [
+        0x21, 0x00, 0xC0, // 0x100: LD HL,0xC000
+        0x7E, //             0x103: LD A,(HL)
+        0x3C, //             INC A
+        0x22, //             LD (HL+),A
+        0x80, //             ADD A,B
+        0x47, //             LD B,A
+        0xCB, 0x37, //       SWAP A
+        0xA9, //             XOR C
+        0x4F, //             LD C,A
+        0xC5, //             PUSH BC
+        0xD1, //             POP DE
+        0xCD, 0x1B, 0x01, // CALL 0x011B
+        0x7C, //             LD A,H
+        0xFE, 0xDF, //       CP 0xDF
+        0x20, 0xED, //       JR NZ,0x103
+        0x21, 0x00, 0xC0, // LD HL,0xC000
+        0x18, 0xE8, //       JR 0x103
+        0x13, //             0x11B: INC DE
+        0xC9, //             RET
+    ];
+
+    rom[0x100..0x100 + program.len()].copy_from_slice(&program);
+    rom
+}
+
+fn main() {
+    let rom = match env::var("GB_BENCH_ROM") {
+        Ok(path) => fs::read(&path).expect("Could not read GB_BENCH_ROM"),
+        Err(_) => create_bench_rom(),
+    };
+
+    let frames = env::var("GB_BENCH_FRAMES")
+        .ok()
+        .and_then(|frames| frames.parse().ok())
+        .unwrap_or(DEFAULT_FRAMES);
+
+    let mut cartridge = cartridge::new_cartridge(rom, None).unwrap();
+    let mut audio_output = NullAudioOutput;
+    let mut apu = Apu::new(&mut audio_output);
+    let mut gpu = Gpu::new(Arc::new(NullScreen));
+    let mut mmu = Mmu::new(&mut *cartridge, &mut gpu, &mut apu);
+    let mut cpu = Cpu::new();
+    let joypad = Joypad::new();
+    let mut emulation = Emulation::new();
+
+    let start = Instant::now();
+
+    for _ in 0..frames {
+        emulation.run_until_vblank(&mut cpu, &mut mmu, &joypad);
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let frames_per_second = frames as f64 / elapsed;
+
+    println!(
+        "{} frames in {:.3}s: {:.1} frames/s ({:.1}x realtime)",
+        frames,
+        elapsed,
+        frames_per_second,
+        frames_per_second / REAL_FPS
+    );
+}
@@ -5,6 +5,7 @@ use crate::apu::channel::wave_channel::WaveChannel;
 use crate::apu::mixer::Mixer;
 use crate::apu::{AudioOutput, Channel};
 use crate::emulation::CPU_CLOCK_HZ;
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary::is_bit_set;
 
 const SQUARE_CHANNEL_1_START_ADDRESS: u16 = 0xFF10;
@@ -46,8 +47,8 @@ impl<'a> Apu<'a> {
         }
     }
 
-    pub fn step(&mut self, clock_cycles: u8) {
-        self.clock += clock_cycles as u16;
+    pub fn step(&mut self, clock_cycles: u16) {
+        self.clock += clock_cycles;
 
         if self.enbaled {
             self.frame_sequencer.step(clock_cycles);
@@ -75,6 +76,42 @@ impl<'a> Apu<'a> {
         }
     }
 
+    /// Amount of clock cycles until the next sample is output or the frame sequencer triggers.
+    /// Stepping in chunks no longer than this keeps the output identical to stepping every cycle
+    pub fn cycles_until_next_event(&self) -> u16 {
+        let cycles_until_sample = self.output_step - self.clock;
+
+        if !self.enbaled {
+            return cycles_until_sample;
+        }
+
+        cycles_until_sample.min(self.frame_sequencer.cycles_until_next_trigger() as u16)
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        self.frame_sequencer.save_state(state);
+        self.square_channel1.save_state(state);
+        self.square_channel2.save_state(state);
+        self.wave_channel.save_state(state);
+        self.noise_channel.save_state(state);
+        self.mixer.save_state(state);
+        state.write_u16(self.clock);
+        state.write_bool(self.enbaled);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.frame_sequencer.load_state(state)?;
+        self.square_channel1.load_state(state)?;
+        self.square_channel2.load_state(state)?;
+        self.wave_channel.load_state(state)?;
+        self.noise_channel.load_state(state)?;
+        self.mixer.load_state(state)?;
+        //The audio output can use a different sample rate than when the state was saved
+        self.clock = state.read_u16()?.min(self.output_step - 1);
+        self.enbaled = state.read_bool()?;
+        Ok(())
+    }
+
     pub fn write(&mut self, address: u16, value: u8) {
         match address {
             SQUARE_CHANNEL_1_START_ADDRESS..=SQUARE_CHANNEL_1_END_ADDRESS => {
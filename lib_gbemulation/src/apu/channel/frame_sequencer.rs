@@ -1,4 +1,5 @@
 use crate::emulation::CPU_CLOCK_HZ;
+use crate::state::{StateReader, StateWriter};
 
 const CYCLES_VOLUME_ENVELOPE_TIMER: u32 = (CPU_CLOCK_HZ / 64) as u32;
 const CYCLES_LENGTH_COUNTER_TIMER: u32 = (CPU_CLOCK_HZ / 256) as u32;
@@ -25,7 +26,20 @@ impl FrameSequencer {
         }
     }
 
-    pub fn step(&mut self, clock_cycles: u8) {
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u32(self.volume_envelope_timer);
+        state.write_u32(self.length_counter_timer);
+        state.write_u32(self.sweep_timer);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.volume_envelope_timer = state.read_u32()? % CYCLES_VOLUME_ENVELOPE_TIMER;
+        self.length_counter_timer = state.read_u32()? % CYCLES_LENGTH_COUNTER_TIMER;
+        self.sweep_timer = state.read_u32()? % CYCLES_SWEEP_TIMER;
+        Ok(())
+    }
+
+    pub fn step(&mut self, clock_cycles: u16) {
         cycle_timer(
             &mut self.volume_envelope_timer,
             CYCLES_VOLUME_ENVELOPE_TIMER,
@@ -48,6 +62,13 @@ impl FrameSequencer {
         );
     }
 
+    /// Amount of clock cycles until the next of the timers triggers
+    pub fn cycles_until_next_trigger(&self) -> u32 {
+        (CYCLES_VOLUME_ENVELOPE_TIMER - self.volume_envelope_timer)
+            .min(CYCLES_LENGTH_COUNTER_TIMER - self.length_counter_timer)
+            .min(CYCLES_SWEEP_TIMER - self.sweep_timer)
+    }
+
     pub fn reset(&mut self) {
         self.volume_envelope_timer = 0;
         self.length_counter_timer = 0;
@@ -55,7 +76,7 @@ impl FrameSequencer {
     }
 }
 
-fn cycle_timer(timer: &mut u32, limit: u32, trigger: &mut bool, clock_cycles: u8) {
+fn cycle_timer(timer: &mut u32, limit: u32, trigger: &mut bool, clock_cycles: u16) {
     *timer += clock_cycles as u32;
 
     if limit <= *timer {
@@ -1,3 +1,5 @@
+use crate::state::{StateReader, StateWriter};
+
 pub struct FrequencySweep {
     pub frequency: u16,
     period: i8,
@@ -27,6 +29,27 @@ impl FrequencySweep {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u16(self.frequency);
+        state.write_u8(self.period as u8);
+        state.write_u8(self.period_load);
+        state.write_u8(self.period_counter);
+        state.write_u8(self.negate);
+        state.write_u8(self.shift);
+        state.write_bool(self.enabled);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.frequency = state.read_u16()?;
+        self.period = state.read_u8()? as i8;
+        self.period_load = state.read_u8()?;
+        self.period_counter = state.read_u8()?;
+        self.negate = state.read_u8()?;
+        self.shift = state.read_u8()?;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+
     pub fn write(&mut self, value: u8) {
         self.period_load = (value & 0x70) >> 4;
         self.negate = (value & 0x08) >> 3;
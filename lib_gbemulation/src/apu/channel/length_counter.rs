@@ -1,3 +1,5 @@
+use crate::state::{StateReader, StateWriter};
+
 pub struct LengthCounter {
     enabled: bool,
     counter: u16,
@@ -18,6 +20,17 @@ impl LengthCounter {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.enabled);
+        state.write_u16(self.counter);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.enabled = state.read_bool()?;
+        self.counter = state.read_u16()?.min(self.counter_size);
+        Ok(())
+    }
+
     pub fn set_length(&mut self, value: u8) {
         self.counter = self.counter_size - value as u16;
     }
@@ -2,6 +2,7 @@ use crate::apu::channel::frame_sequencer::FrameSequencer;
 use crate::apu::channel::length_counter::{LengthCounter, LengthCounterResult};
 use crate::apu::channel::volume_envelope::VolumeEnvelope;
 use crate::apu::Channel;
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary::is_bit_set;
 
 const DIVISOR_CODE_MAP: [usize; 8] = [8, 16, 32, 48, 64, 80, 96, 112];
@@ -33,6 +34,29 @@ impl NoiseChannel {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        self.volume_envelope.save_state(state);
+        self.length_counter.save_state(state);
+        state.write_u32(self.timer as u32);
+        state.write_u16(self.lfsr);
+        state.write_u8(self.clock_shift);
+        state.write_u8(self.lfsr_width_mode);
+        state.write_u8(self.divisor_code);
+        state.write_bool(self.enabled);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.volume_envelope.load_state(state)?;
+        self.length_counter.load_state(state)?;
+        self.timer = state.read_u32()? as i32;
+        self.lfsr = state.read_u16()?;
+        self.clock_shift = state.read_u8()? & 0x0F;
+        self.lfsr_width_mode = state.read_u8()? & 0x01;
+        self.divisor_code = state.read_u8()? & 0x07;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+
     fn set_length_counter_length(&mut self, value: u8) {
         self.length_counter.set_length(value & 0x3F);
     }
@@ -111,7 +135,7 @@ impl Channel for NoiseChannel {
         self.volume_envelope.process_signal(signal)
     }
 
-    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u8) {
+    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u16) {
         if !self.enabled {
             return;
         }
@@ -119,12 +143,13 @@ impl Channel for NoiseChannel {
         self.handle_length_counter(frame_sequencer);
         self.handle_volume_envelope(frame_sequencer);
 
-        if self.timer <= 0 {
+        self.timer -= clock_cycles as i32;
+
+        //The period can be shorter than the amount of cycles stepped
+        while self.timer <= 0 {
             self.cycle_lfsr();
             self.timer += self.get_period();
         }
-
-        self.timer -= clock_cycles as i32;
     }
 
     fn write(&mut self, address: u16, value: u8) {
@@ -3,6 +3,7 @@ use crate::apu::channel::frequency_sweep::{FrequencySweep, FrequencySweepResult}
 use crate::apu::channel::length_counter::{LengthCounter, LengthCounterResult};
 use crate::apu::channel::volume_envelope::VolumeEnvelope;
 use crate::apu::Channel;
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary::is_bit_set;
 use std::i16;
 
@@ -81,6 +82,33 @@ impl SquareChannel {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u16(self.frequency);
+        if let Some(ref frequency_sweep) = self.frequency_sweep {
+            frequency_sweep.save_state(state);
+        }
+        state.write_u8(self.duty);
+        self.volume_envelope.save_state(state);
+        self.length_counter.save_state(state);
+        state.write_u16(self.timer as u16);
+        state.write_u8(self.waveform_pointer);
+        state.write_bool(self.enabled);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.frequency = state.read_u16()? & 0x7FF;
+        if let Some(ref mut frequency_sweep) = self.frequency_sweep {
+            frequency_sweep.load_state(state)?;
+        }
+        self.duty = state.read_u8()? & 0x03;
+        self.volume_envelope.load_state(state)?;
+        self.length_counter.load_state(state)?;
+        self.timer = state.read_u16()? as i16;
+        self.waveform_pointer = state.read_u8()? % 8;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+
     fn get_period(&self) -> i16 {
         (2048 - self.frequency as i16) * 4
     }
@@ -122,7 +150,7 @@ impl SquareChannel {
 }
 
 impl Channel for SquareChannel {
-    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u8) {
+    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u16) {
         if !self.enabled {
             return;
         }
@@ -131,7 +159,10 @@ impl Channel for SquareChannel {
         self.handle_length_counter(frame_sequencer);
         self.handle_frequency_sweep(frame_sequencer);
 
-        if self.timer <= 0 {
+        self.timer -= clock_cycles as i16;
+
+        //The period can be shorter than the amount of cycles stepped
+        while self.timer <= 0 {
             self.timer += self.get_period();
 
             self.waveform_pointer += 1;
@@ -140,10 +171,6 @@ impl Channel for SquareChannel {
                 self.waveform_pointer = 0;
             }
         }
-
-        if self.get_period() >= clock_cycles as i16 {
-            self.timer -= clock_cycles as i16;
-        }
     }
 
     fn output(&self) -> i16 {
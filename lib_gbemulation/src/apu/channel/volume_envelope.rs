@@ -1,3 +1,5 @@
+use crate::state::{StateReader, StateWriter};
+
 pub struct VolumeEnvelope {
     pub starting_volume: u8,
     pub add_mode: u8,
@@ -19,6 +21,25 @@ impl VolumeEnvelope {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.starting_volume);
+        state.write_u8(self.add_mode);
+        state.write_u8(self.period);
+        state.write_u8(self.period_load);
+        state.write_u8(self.current_volume);
+        state.write_u8(self.period_counter);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.starting_volume = state.read_u8()?;
+        self.add_mode = state.read_u8()?;
+        self.period = state.read_u8()?;
+        self.period_load = state.read_u8()?;
+        self.current_volume = state.read_u8()?;
+        self.period_counter = state.read_u8()?;
+        Ok(())
+    }
+
     pub fn step(&mut self) {
         if self.period == 0 {
             return;
@@ -1,6 +1,7 @@
 use crate::apu::channel::frame_sequencer::FrameSequencer;
 use crate::apu::channel::length_counter::{LengthCounter, LengthCounterResult};
 use crate::apu::Channel;
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary::is_bit_set;
 use std::i16;
 
@@ -76,6 +77,29 @@ impl WaveChannel {
         self.wavetable_pointer = 0;
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u16(self.frequency);
+        self.length_counter.save_state(state);
+        state.write_u16(self.timer as u16);
+        state.write_u8(self.wavetable_pointer);
+        state.write_bool(self.enabled);
+        state.write_bytes(&self.wavetable);
+        state.write_u8(self.volume_code);
+        state.write_bool(self.dac_enabled);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.frequency = state.read_u16()? & 0x7FF;
+        self.length_counter.load_state(state)?;
+        self.timer = state.read_u16()? as i16;
+        self.wavetable_pointer = state.read_u8()? % 32;
+        self.enabled = state.read_bool()?;
+        state.read_bytes_into(&mut self.wavetable)?;
+        self.volume_code = state.read_u8()? & 0x03;
+        self.dac_enabled = state.read_bool()?;
+        Ok(())
+    }
+
     fn get_period(&self) -> i16 {
         (2048 - self.frequency as i16) * 2
     }
@@ -117,14 +141,17 @@ impl WaveChannel {
 }
 
 impl Channel for WaveChannel {
-    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u8) {
+    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u16) {
         if !self.enabled {
             return;
         }
 
         self.handle_length_counter(frame_sequencer);
 
-        if self.timer <= 0 {
+        self.timer -= clock_cycles as i16;
+
+        //The period can be shorter than the amount of cycles stepped
+        while self.timer <= 0 {
             self.timer += self.get_period();
 
             self.wavetable_pointer += 1;
@@ -133,10 +160,6 @@ impl Channel for WaveChannel {
                 self.wavetable_pointer = 0;
             }
         }
-
-        if self.get_period() >= clock_cycles as i16 {
-            self.timer -= clock_cycles as i16;
-        }
     }
 
     fn output(&self) -> i16 {
@@ -2,6 +2,7 @@ use crate::apu::channel::noise_channel::NoiseChannel;
 use crate::apu::channel::square_channel::SquareChannel;
 use crate::apu::channel::wave_channel::WaveChannel;
 use crate::apu::Channel;
+use crate::state::{StateReader, StateWriter};
 
 const BASE_ADDRESS: u16 = 0xFF24;
 
@@ -30,6 +31,15 @@ impl Mixer {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.get_channel_enables());
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.set_channel_enables(state.read_u8()?);
+        Ok(())
+    }
+
     pub fn mix(
         &self,
         enabled: bool,
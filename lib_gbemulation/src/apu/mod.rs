@@ -9,8 +9,19 @@ pub trait AudioOutput {
     fn get_sample_rate(&self) -> u32;
 }
 
+/// Discards the samples, for running without an audio device
+pub struct NullAudioOutput;
+
+impl AudioOutput for NullAudioOutput {
+    fn output(&mut self, _sample: (i16, i16)) {}
+
+    fn get_sample_rate(&self) -> u32 {
+        44100
+    }
+}
+
 trait Channel {
     fn output(&self) -> i16;
-    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u8);
+    fn step(&mut self, frame_sequencer: &FrameSequencer, clock_cycles: u16);
     fn write(&mut self, address: u16, value: u8);
 }
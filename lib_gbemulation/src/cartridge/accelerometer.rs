@@ -0,0 +1,47 @@
+use std::sync::{Arc, Mutex};
+
+/// Source of the tilt of cartridges with an accelerometer.
+/// Values are in g, -1.0 to 1.0 covers normal tilting
+pub trait Accelerometer {
+    /// Returns x (positive when tilted to the right) and y (positive when tilted towards the player)
+    fn read_tilt(&self) -> (f32, f32);
+}
+
+/// Accelerometer lying flat on a table
+pub struct NullAccelerometer;
+
+impl Accelerometer for NullAccelerometer {
+    fn read_tilt(&self) -> (f32, f32) {
+        (0.0, 0.0)
+    }
+}
+
+/// Accelerometer which can be updated from a different thread, e.g. by the input handling of a frontend
+#[derive(Clone)]
+pub struct SharedAccelerometer {
+    tilt: Arc<Mutex<(f32, f32)>>,
+}
+
+impl Default for SharedAccelerometer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedAccelerometer {
+    pub fn new() -> Self {
+        SharedAccelerometer {
+            tilt: Arc::new(Mutex::new((0.0, 0.0))),
+        }
+    }
+
+    pub fn set_tilt(&self, x: f32, y: f32) {
+        *self.tilt.lock().unwrap() = (x, y);
+    }
+}
+
+impl Accelerometer for SharedAccelerometer {
+    fn read_tilt(&self) -> (f32, f32) {
+        *self.tilt.lock().unwrap()
+    }
+}
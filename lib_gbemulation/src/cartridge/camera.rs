@@ -0,0 +1,370 @@
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus, EXT_RAM_ADDRESS};
+use crate::state::{StateReader, StateWriter};
+
+pub const SENSOR_WIDTH: usize = 128;
+pub const SENSOR_HEIGHT: usize = 112;
+
+const RAM_SIZE: usize = 128 * 1024;
+const REGISTER_COUNT: usize = 0x36;
+const DITHER_MATRIX_ADDRESS: usize = 0x06;
+//Captured image is stored as tiles in ram bank 0
+const IMAGE_RAM_OFFSET: usize = 0x100;
+//Exposure time at which the sensor values are used unchanged
+const EXPOSURE_REFERENCE: u32 = 0x1000;
+const EDGE_ENHANCEMENT_RATIOS: [f32; 8] = [0.5, 0.75, 1.0, 1.25, 2.0, 3.0, 4.0, 5.0];
+
+/// Provides the image seen by the camera sensor
+pub trait ImageSource {
+    /// Returns SENSOR_WIDTH * SENSOR_HEIGHT grayscale values, 0 is black and 255 is white
+    fn capture(&mut self) -> Vec<u8>;
+}
+
+/// Source used if no image source is connected. Sees a horizontal gradient
+pub struct NullImageSource;
+
+impl ImageSource for NullImageSource {
+    fn capture(&mut self) -> Vec<u8> {
+        (0..SENSOR_WIDTH * SENSOR_HEIGHT)
+            .map(|index| ((index % SENSOR_WIDTH) * 255 / (SENSOR_WIDTH - 1)) as u8)
+            .collect()
+    }
+}
+
+pub struct PocketCamera {
+    cartridge_base: CartridgeBase,
+    image_source: Box<dyn ImageSource + Send>,
+    registers: [u8; REGISTER_COUNT],
+    registers_selected: bool,
+}
+
+impl PocketCamera {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let cartridge_base =
+            CartridgeBase::new(rom, header, true, Some(RAM_SIZE), true, ram_dumper);
+
+        PocketCamera {
+            cartridge_base,
+            image_source: Box::new(NullImageSource),
+            registers: [0; REGISTER_COUNT],
+            registers_selected: false,
+        }
+    }
+
+    fn write_register(&mut self, register: usize, value: u8) {
+        match register {
+            0x00 => {
+                //Only the capture bit and the edge mode bits can be written
+                self.registers[0] = value & 0x07;
+
+                //There is no clock in the cartridge so the capture finishes immediately
+                if value & 0x01 == 0x01 {
+                    self.capture();
+                    self.registers[0] &= 0x06;
+                }
+            }
+            0x01..=0x35 => self.registers[register] = value,
+            _ => {}
+        }
+    }
+
+    fn capture(&mut self) {
+        let image = self.image_source.capture();
+        let tiles = process_image(&image, &self.registers);
+
+        if let Some(ref mut ram) = self.cartridge_base.ram {
+            ram[IMAGE_RAM_OFFSET..IMAGE_RAM_OFFSET + tiles.len()].copy_from_slice(&tiles);
+        }
+
+        self.cartridge_base.mark_ram_dirty();
+    }
+}
+
+impl Cartridge for PocketCamera {
+    fn read(&self, address: u16) -> u8 {
+        self.cartridge_base.read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0..=0x1FFF => {
+                self.cartridge_base.set_ram_enabled(value & 0x0F == 0x0A);
+            }
+            0x2000..=0x3FFF => {
+                self.cartridge_base.rom_bank = value & 0x3F;
+            }
+            //Bit 4 maps the camera registers instead of the ram
+            0x4000..=0x5FFF => {
+                self.registers_selected = value & 0x10 == 0x10;
+                self.cartridge_base.ram_bank = value & 0x0F;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.registers_selected {
+            //Registers are mirrored every 0x80 bytes
+            let register = (address as usize - EXT_RAM_ADDRESS) & 0x7F;
+            self.write_register(register, value);
+            return;
+        }
+
+        self.cartridge_base.write_ram(address, value);
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if self.registers_selected {
+            //Only the status register can be read
+            return match (address as usize - EXT_RAM_ADDRESS) & 0x7F {
+                0x00 => self.registers[0],
+                _ => 0x00,
+            };
+        }
+
+        self.cartridge_base.read_ram(address)
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
+    }
+
+    fn load_savegame(&mut self) {
+        self.cartridge_base.load_savegame();
+    }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_bytes(&self.registers);
+        state.write_bool(self.registers_selected);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        state.read_bytes_into(&mut self.registers)?;
+        self.registers_selected = state.read_bool()?;
+        Ok(())
+    }
+
+    fn set_image_source(&mut self, image_source: Box<dyn ImageSource + Send>) {
+        self.image_source = image_source;
+    }
+}
+
+/// Converts a grayscale image into the 2bpp tiles the camera stores in ram, using the exposure,
+/// edge enhancement, invert and dithering matrix settings of the given sensor registers
+pub fn process_image(image: &[u8], registers: &[u8; REGISTER_COUNT]) -> Vec<u8> {
+    let exposure = (registers[0x02] as u32) << 8 | registers[0x03] as u32;
+    let edge_enhancement = registers[0x01] & 0xE0 == 0xE0;
+    let edge_ratio = EDGE_ENHANCEMENT_RATIOS[(registers[0x04] >> 4 & 0x07) as usize];
+    let invert = registers[0x04] & 0x08 == 0x08;
+
+    let exposed: Vec<f32> = (0..SENSOR_WIDTH * SENSOR_HEIGHT)
+        .map(|index| {
+            let value = *image.get(index).unwrap_or(&0) as u32;
+            (value * exposure / EXPOSURE_REFERENCE).min(255) as f32
+        })
+        .collect();
+
+    let get_exposed = |x: isize, y: isize| {
+        let x = x.clamp(0, SENSOR_WIDTH as isize - 1) as usize;
+        let y = y.clamp(0, SENSOR_HEIGHT as isize - 1) as usize;
+        exposed[y * SENSOR_WIDTH + x]
+    };
+
+    let mut tiles = vec![0; SENSOR_WIDTH * SENSOR_HEIGHT / 4];
+
+    for y in 0..SENSOR_HEIGHT {
+        for x in 0..SENSOR_WIDTH {
+            let (sx, sy) = (x as isize, y as isize);
+            let mut value = get_exposed(sx, sy);
+
+            if edge_enhancement {
+                let neighbours = get_exposed(sx - 1, sy)
+                    + get_exposed(sx + 1, sy)
+                    + get_exposed(sx, sy - 1)
+                    + get_exposed(sx, sy + 1);
+                value += (value * 4.0 - neighbours) * edge_ratio;
+            }
+
+            let mut value = value.clamp(0.0, 255.0) as u8;
+
+            if invert {
+                value = 255 - value;
+            }
+
+            //Every pixel of a 4x4 block has its own three thresholds
+            let matrix_index = DITHER_MATRIX_ADDRESS + ((x & 3) + (y & 3) * 4) * 3;
+            let thresholds = &registers[matrix_index..matrix_index + 3];
+
+            let color = if value < thresholds[0] {
+                3
+            } else if value < thresholds[1] {
+                2
+            } else if value < thresholds[2] {
+                1
+            } else {
+                0
+            };
+
+            let tile = (y / 8) * (SENSOR_WIDTH / 8) + x / 8;
+            let index = tile * 16 + (y % 8) * 2;
+            let bit = 7 - (x % 8);
+
+            tiles[index] |= (color & 0x01) << bit;
+            tiles[index + 1] |= (color >> 1) << bit;
+        }
+    }
+
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: [u8; 3] = [0x40, 0x80, 0xC0];
+    //First tile row of the image in ram
+    const IMAGE_ADDRESS: u16 = 0xA100;
+
+    struct ConstantImageSource(u8);
+
+    impl ImageSource for ConstantImageSource {
+        fn capture(&mut self) -> Vec<u8> {
+            vec![self.0; SENSOR_WIDTH * SENSOR_HEIGHT]
+        }
+    }
+
+    fn registers(exposure: u16, thresholds: [u8; 3]) -> [u8; REGISTER_COUNT] {
+        let mut registers = [0; REGISTER_COUNT];
+        registers[0x02..0x04].copy_from_slice(&exposure.to_be_bytes());
+
+        for pixel in 0..16 {
+            let index = DITHER_MATRIX_ADDRESS + pixel * 3;
+            registers[index..index + 3].copy_from_slice(&thresholds);
+        }
+
+        registers
+    }
+
+    fn process_constant_image(value: u8, registers: &[u8; REGISTER_COUNT]) -> Vec<u8> {
+        process_image(&ConstantImageSource(value).capture(), registers)
+    }
+
+    //Both bytes of the first row of the first tile
+    fn first_row(tiles: &[u8]) -> [u8; 2] {
+        [tiles[0], tiles[1]]
+    }
+
+    #[test]
+    fn dithers_with_thresholds() {
+        let registers = registers(EXPOSURE_REFERENCE as u16, THRESHOLDS);
+
+        assert_eq!(
+            first_row(&process_constant_image(0x10, &registers)),
+            [0xFF, 0xFF]
+        );
+        assert_eq!(
+            first_row(&process_constant_image(0x60, &registers)),
+            [0x00, 0xFF]
+        );
+        assert_eq!(
+            first_row(&process_constant_image(0x90, &registers)),
+            [0xFF, 0x00]
+        );
+        assert_eq!(
+            first_row(&process_constant_image(0xD0, &registers)),
+            [0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn uses_thresholds_of_each_matrix_pixel() {
+        let mut registers = registers(EXPOSURE_REFERENCE as u16, [0x00; 3]);
+        //Pixel x = 0, y = 1 of every 4x4 block
+        let index = DITHER_MATRIX_ADDRESS + 4 * 3;
+        registers[index..index + 3].copy_from_slice(&[0xFF; 3]);
+
+        let tiles = process_constant_image(0x80, &registers);
+
+        for (row, bytes) in tiles[..16].chunks(2).enumerate() {
+            let expected = if row % 4 == 1 { 0x88 } else { 0x00 };
+            assert_eq!(bytes, [expected, expected], "row {}", row);
+        }
+    }
+
+    #[test]
+    fn inverts_image() {
+        let mut registers = registers(EXPOSURE_REFERENCE as u16, THRESHOLDS);
+        assert_eq!(
+            first_row(&process_constant_image(0x10, &registers)),
+            [0xFF, 0xFF]
+        );
+
+        registers[0x04] |= 0x08;
+        assert_eq!(
+            first_row(&process_constant_image(0x10, &registers)),
+            [0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn exposure_scales_brightness() {
+        let reference = EXPOSURE_REFERENCE as u16;
+
+        let tiles = process_constant_image(0x60, &registers(reference, THRESHOLDS));
+        assert_eq!(first_row(&tiles), [0x00, 0xFF]);
+
+        let tiles = process_constant_image(0x60, &registers(reference * 2, THRESHOLDS));
+        assert_eq!(first_row(&tiles), [0x00, 0x00]);
+
+        let tiles = process_constant_image(0x60, &registers(reference / 2, THRESHOLDS));
+        assert_eq!(first_row(&tiles), [0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn captures_into_ram() {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0xFC;
+        let header = CartridgeHeader::parse(&rom).unwrap();
+
+        let mut camera = PocketCamera::new(rom, header, None);
+        camera.set_image_source(Box::new(ConstantImageSource(0x90)));
+        camera.write(0x0000, 0x0A);
+
+        camera.write(0x4000, 0x10);
+        let registers = registers(EXPOSURE_REFERENCE as u16, THRESHOLDS);
+        for (register, value) in registers.iter().enumerate().skip(1) {
+            camera.write_ram(0xA000 + register as u16, *value);
+        }
+        camera.write_ram(0xA000, 0x01);
+        assert_eq!(camera.read_ram(0xA000), 0x00);
+
+        camera.write(0x4000, 0x00);
+        assert_eq!(camera.read_ram(IMAGE_ADDRESS - 1), 0x00);
+
+        let expected = process_constant_image(0x90, &registers);
+        for (offset, value) in expected.iter().enumerate() {
+            assert_eq!(camera.read_ram(IMAGE_ADDRESS + offset as u16), *value);
+        }
+        assert_eq!(camera.read_ram(IMAGE_ADDRESS + expected.len() as u16), 0x00);
+        assert_eq!(camera.read_ram(IMAGE_ADDRESS), 0xFF);
+    }
+}
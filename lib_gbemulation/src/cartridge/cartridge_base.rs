@@ -1,6 +1,10 @@
-use crate::cartridge::{create_ram, RamDumper, EXT_RAM_ADDRESS, EXT_RAM_SIZE};
+use crate::cartridge::header::{CartridgeHeader, ROM_BANK_SIZE};
+use crate::cartridge::savegame_format::SAVEGAME_TRAILER_SIZES;
+use crate::cartridge::{create_ram, RamDumper, SavegameStatus, EXT_RAM_ADDRESS, EXT_RAM_SIZE};
+use crate::state::{StateReader, StateWriter};
 
 pub struct CartridgeBase {
+    pub header: CartridgeHeader,
     pub rom: Vec<u8>,
     pub ram: Option<Vec<u8>>,
     pub rom_bank: u8,
@@ -8,11 +12,19 @@ pub struct CartridgeBase {
     pub ram_enabled: bool,
     has_battery: bool,
     ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    //Ram has been changed since it was last dumped
+    ram_dirty: bool,
+    //Ram has been disabled after it was changed. Dumped by the frontend after the frame
+    flush_pending: bool,
+    //Additional data like the state of a clock which is dumped after the ram
+    savegame_trailer: Vec<u8>,
+    savegame_status: SavegameStatus,
 }
 
 impl CartridgeBase {
     pub fn new(
-        rom: Vec<u8>,
+        mut rom: Vec<u8>,
+        header: CartridgeHeader,
         has_ram: bool,
         ram_size: Option<usize>,
         has_battery: bool,
@@ -20,7 +32,13 @@ impl CartridgeBase {
     ) -> Self {
         let ram = if has_ram { create_ram(ram_size) } else { None };
 
+        //Trimmed ROMs are padded to the size in the header
+        if rom.len() < header.rom_size {
+            rom.resize(header.rom_size, 0xFF);
+        }
+
         let mut base = CartridgeBase {
+            header,
             rom,
             ram,
             rom_bank: 1,
@@ -28,6 +46,10 @@ impl CartridgeBase {
             ram_enabled: false,
             has_battery,
             ram_dumper,
+            ram_dirty: false,
+            flush_pending: false,
+            savegame_trailer: Vec::new(),
+            savegame_status: SavegameStatus::NotFound,
         };
 
         base.load_savegame();
@@ -40,7 +62,7 @@ impl CartridgeBase {
             0x0..=0x3FFF => self.rom[address as usize],
             //Bank 01-7F
             0x4000..=0x7FFF => {
-                let offset = 0x4000 * self.rom_bank as usize;
+                let offset = self.rom_bank_offset(self.rom_bank as usize);
                 self.rom[(address as usize - 0x4000) + offset]
             }
             _ => panic!("Address unknown: 0x{:X}", address),
@@ -55,9 +77,30 @@ impl CartridgeBase {
         let ram_bank = self.ram_bank as usize;
 
         if let Some(ref mut ram) = self.ram {
-            let offset = EXT_RAM_SIZE * ram_bank;
-            ram[(address as usize - EXT_RAM_ADDRESS) + offset] = value;
+            let index = get_ram_index(ram.len(), ram_bank, address);
+            ram[index] = value;
+            self.ram_dirty = true;
+        }
+    }
+
+    /// Games disable the ram after they are done writing to it, so a flush is requested to
+    /// not lose the savegame if the emulator is not closed properly. Dumping here would stall
+    /// games which toggle the ram around every access
+    pub fn set_ram_enabled(&mut self, enabled: bool) {
+        if self.ram_enabled && !enabled && self.has_battery && self.ram_dirty {
+            self.flush_pending = true;
         }
+
+        self.ram_enabled = enabled;
+    }
+
+    pub fn flush_pending(&self) -> bool {
+        self.flush_pending
+    }
+
+    /// Used by cartridges which change the ram without going through write_ram
+    pub fn mark_ram_dirty(&mut self) {
+        self.ram_dirty = true;
     }
 
     pub fn read_ram(&self, address: u16) -> u8 {
@@ -68,36 +111,212 @@ impl CartridgeBase {
         let ram_bank = self.ram_bank as usize;
 
         if let Some(ref ram) = self.ram {
-            let offset = EXT_RAM_SIZE * ram_bank;
-            return ram[(address as usize - EXT_RAM_ADDRESS) + offset];
+            return ram[get_ram_index(ram.len(), ram_bank, address)];
         }
 
         0
     }
 
-    pub fn dump_savegame(&self) {
-        if !self.has_battery {
-            return;
+    /// Offset of the given bank in the ROM. Bank numbers beyond the ROM size wrap around
+    /// because the cartridge does not connect the upper bank lines
+    pub fn rom_bank_offset(&self, rom_bank: usize) -> usize {
+        ROM_BANK_SIZE * (rom_bank % self.header.rom_banks())
+    }
+
+    /// Reads from the given bank, ignoring which bank is currently selected
+    pub fn read_rom(&self, rom_bank: usize, address: u16) -> u8 {
+        self.rom[self.rom_bank_offset(rom_bank) + (address as usize & (ROM_BANK_SIZE - 1))]
+    }
+
+    /// Dumps the ram followed by the trailer if it has been changed since the last dump.
+    /// The ram stays dirty if dumping fails so it is retried the next time
+    pub fn dump_savegame(&mut self) -> Result<(), String> {
+        //A failed dump is retried by the periodic save instead of after every frame
+        self.flush_pending = false;
+
+        if !self.has_battery || !self.ram_dirty {
+            return Ok(());
         }
 
         if let Some(ref ram) = self.ram {
             if let Some(ref dumper) = self.ram_dumper {
-                dumper.dump(ram)
+                let mut data = ram.clone();
+                data.extend_from_slice(&self.savegame_trailer);
+                dumper.dump(&data)?;
             }
         }
+
+        self.ram_dirty = false;
+        Ok(())
+    }
+
+    /// Sets the data which is dumped after the ram
+    pub fn set_savegame_trailer(&mut self, trailer: Vec<u8>) {
+        self.savegame_trailer = trailer;
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.rom_bank);
+        state.write_u8(self.ram_bank);
+        state.write_bool(self.ram_enabled);
+
+        if let Some(ref ram) = self.ram {
+            state.write_bytes(ram);
+        }
+    }
+
+    /// The loaded ram is written to the savegame with the next dump
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = state.read_u8()?;
+        self.ram_bank = state.read_u8()?;
+        self.ram_enabled = state.read_bool()?;
+
+        if let Some(ref mut ram) = self.ram {
+            state.read_bytes_into(ram)?;
+            self.ram_dirty = true;
+        }
+
+        Ok(())
     }
 
+    /// Data which was found behind the ram of the loaded savegame
+    pub fn savegame_trailer(&self) -> &[u8] {
+        &self.savegame_trailer
+    }
+
+    pub fn savegame_status(&self) -> SavegameStatus {
+        self.savegame_status.clone()
+    }
+
+    /// Loads the savegame into the ram. A savegame of the wrong size is padded with zeros or
+    /// truncated unless the additional bytes are one of the known trailers
     pub fn load_savegame(&mut self) {
         if !self.has_battery {
             return;
         }
 
-        if let Some(ref dumper) = self.ram_dumper {
-            if let Some(data) = dumper.load() {
-                if let Some(ref mut ram) = self.ram {
-                    *ram = data;
-                }
-            }
+        let mut data = match self.ram_dumper {
+            Some(ref dumper) => match dumper.load() {
+                Some(data) => data,
+                None => return,
+            },
+            None => return,
+        };
+
+        if let Some(ref mut ram) = self.ram {
+            let expected = ram.len();
+            let actual = data.len();
+
+            self.savegame_trailer =
+                if actual > expected && SAVEGAME_TRAILER_SIZES.contains(&(actual - expected)) {
+                    data.split_off(expected)
+                } else {
+                    Vec::new()
+                };
+
+            self.savegame_status = if data.len() == expected {
+                SavegameStatus::Loaded
+            } else {
+                data.resize(expected, 0);
+                SavegameStatus::Resized { expected, actual }
+            };
+
+            *ram = data;
+            self.ram_dirty = false;
+        }
+    }
+}
+
+//Bank numbers and addresses beyond the RAM size wrap around
+fn get_ram_index(ram_size: usize, ram_bank: usize, address: u16) -> usize {
+    let ram_banks = (ram_size / EXT_RAM_SIZE).max(1);
+    let offset = EXT_RAM_SIZE * (ram_bank % ram_banks);
+    ((address as usize - EXT_RAM_ADDRESS) + offset) % ram_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct MemoryRamDumper {
+        dumps: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl RamDumper for MemoryRamDumper {
+        fn dump(&self, data: &Vec<u8>) -> Result<(), String> {
+            self.dumps.lock().unwrap().push(data.clone());
+            Ok(())
+        }
+
+        fn load(&self) -> Option<Vec<u8>> {
+            None
         }
     }
+
+    fn new_cartridge_base(has_battery: bool) -> (CartridgeBase, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let rom = vec![0; 0x8000];
+        let header = CartridgeHeader::parse(&rom).unwrap();
+        let dumps = Arc::new(Mutex::new(Vec::new()));
+        let ram_dumper = MemoryRamDumper {
+            dumps: dumps.clone(),
+        };
+
+        let cartridge_base = CartridgeBase::new(
+            rom,
+            header,
+            true,
+            Some(EXT_RAM_SIZE),
+            has_battery,
+            Some(Box::new(ram_dumper)),
+        );
+
+        (cartridge_base, dumps)
+    }
+
+    #[test]
+    fn requests_flush_when_changed_ram_is_disabled() {
+        let (mut cartridge_base, dumps) = new_cartridge_base(true);
+
+        cartridge_base.set_ram_enabled(true);
+        cartridge_base.write_ram(0xA000, 0x42);
+        assert!(!cartridge_base.flush_pending());
+
+        cartridge_base.set_ram_enabled(false);
+        assert!(cartridge_base.flush_pending());
+        assert!(dumps.lock().unwrap().is_empty());
+
+        cartridge_base.dump_savegame().unwrap();
+        assert!(!cartridge_base.flush_pending());
+        assert_eq!(dumps.lock().unwrap().len(), 1);
+        assert_eq!(dumps.lock().unwrap()[0][0], 0x42);
+    }
+
+    #[test]
+    fn does_not_request_flush_for_unchanged_ram() {
+        let (mut cartridge_base, _) = new_cartridge_base(true);
+
+        cartridge_base.set_ram_enabled(true);
+        cartridge_base.read_ram(0xA000);
+        cartridge_base.set_ram_enabled(false);
+        assert!(!cartridge_base.flush_pending());
+
+        //Disabling the ram again does not request another flush
+        cartridge_base.set_ram_enabled(true);
+        cartridge_base.write_ram(0xA000, 0x42);
+        cartridge_base.set_ram_enabled(false);
+        cartridge_base.dump_savegame().unwrap();
+        cartridge_base.set_ram_enabled(false);
+        assert!(!cartridge_base.flush_pending());
+    }
+
+    #[test]
+    fn does_not_request_flush_without_battery() {
+        let (mut cartridge_base, _) = new_cartridge_base(false);
+
+        cartridge_base.set_ram_enabled(true);
+        cartridge_base.write_ram(0xA000, 0x42);
+        cartridge_base.set_ram_enabled(false);
+        assert!(!cartridge_base.flush_pending());
+    }
 }
@@ -0,0 +1,420 @@
+use std::fmt;
+
+const LOGO_ADDRESS: usize = 0x104;
+const TITLE_ADDRESS: usize = 0x134;
+const CGB_FLAG_ADDRESS: usize = 0x143;
+const NEW_LICENSEE_ADDRESS: usize = 0x144;
+const SGB_FLAG_ADDRESS: usize = 0x146;
+const CARTRIDGE_TYPE_ADDRESS: usize = 0x147;
+const ROM_SIZE_ADDRESS: usize = 0x148;
+const RAM_SIZE_ADDRESS: usize = 0x149;
+const DESTINATION_ADDRESS: usize = 0x14A;
+const OLD_LICENSEE_ADDRESS: usize = 0x14B;
+const VERSION_ADDRESS: usize = 0x14C;
+const HEADER_CHECKSUM_ADDRESS: usize = 0x14D;
+const GLOBAL_CHECKSUM_ADDRESS: usize = 0x14E;
+
+pub const HEADER_END_ADDRESS: usize = 0x150;
+pub const ROM_BANK_SIZE: usize = 0x4000;
+
+//Old licensee code which means the new licensee code has to be used
+const USE_NEW_LICENSEE: u8 = 0x33;
+
+pub const NINTENDO_LOGO: [u8; 48] = [
+    0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C, 0x00, 0x0D,
+    0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6, 0xDD, 0xDD, 0xD9, 0x99,
+    0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC, 0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E,
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CgbSupport {
+    None,
+    Supported,
+    Only,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Licensee {
+    Old(u8),
+    New(String),
+}
+
+/// Problems with the header which do not prevent the ROM from being loaded
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeaderWarning {
+    /// The boot ROM would lock up but some unlicensed cartridges still work on emulators
+    InvalidLogo,
+    InvalidHeaderChecksum(u8),
+    /// The size of the file is used instead
+    UnknownRomSize(u8),
+    /// The cartridge is treated as having no ram
+    UnknownRamSize(u8),
+    /// The ROM is smaller than the header says and is padded
+    Truncated {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for HeaderWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeaderWarning::InvalidLogo => write!(f, "Nintendo logo in the header is invalid"),
+            HeaderWarning::InvalidHeaderChecksum(checksum) => {
+                write!(f, "Header checksum is invalid: 0x{:X}", checksum)
+            }
+            HeaderWarning::UnknownRomSize(value) => write!(f, "Unknown ROM size: 0x{:X}", value),
+            HeaderWarning::UnknownRamSize(value) => write!(f, "Unknown RAM size: 0x{:X}", value),
+            HeaderWarning::Truncated { expected, actual } => write!(
+                f,
+                "ROM is truncated: header specifies {} bytes but file has {} bytes",
+                expected, actual
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CartridgeHeader {
+    pub title: String,
+    pub cgb_support: CgbSupport,
+    pub sgb_supported: bool,
+    pub licensee: Licensee,
+    pub cartridge_type: u8,
+    pub rom_size: usize,
+    pub ram_size: Option<usize>,
+    pub japanese: bool,
+    pub version: u8,
+    pub header_checksum: u8,
+    pub global_checksum: u16,
+    pub logo_valid: bool,
+    pub header_checksum_valid: bool,
+    pub global_checksum_valid: bool,
+    pub warnings: Vec<HeaderWarning>,
+}
+
+impl CartridgeHeader {
+    pub fn parse(rom: &[u8]) -> Result<Self, String> {
+        Self::parse_at(rom, 0)
+    }
+
+    /// Parses a header which is located at the given offset. Used by multicarts which have
+    /// a header for every game
+    pub fn parse_at(rom: &[u8], offset: usize) -> Result<Self, String> {
+        if rom.len() < offset + HEADER_END_ADDRESS {
+            return Err(format!(
+                "ROM is too small to contain a header: {} bytes",
+                rom.len()
+            ));
+        }
+
+        let header = &rom[offset..offset + HEADER_END_ADDRESS];
+        let mut warnings = Vec::new();
+
+        let rom_size = match get_rom_size(header[ROM_SIZE_ADDRESS]) {
+            Some(rom_size) => rom_size,
+            None => {
+                warnings.push(HeaderWarning::UnknownRomSize(header[ROM_SIZE_ADDRESS]));
+                rom.len().max(2 * ROM_BANK_SIZE).next_power_of_two()
+            }
+        };
+
+        if rom.len() < rom_size {
+            warnings.push(HeaderWarning::Truncated {
+                expected: rom_size,
+                actual: rom.len(),
+            });
+        }
+
+        let ram_size = get_ram_size(header[RAM_SIZE_ADDRESS]);
+        if ram_size.is_none() {
+            warnings.push(HeaderWarning::UnknownRamSize(header[RAM_SIZE_ADDRESS]));
+        }
+
+        let cgb_support = match header[CGB_FLAG_ADDRESS] {
+            0xC0 => CgbSupport::Only,
+            0x80 => CgbSupport::Supported,
+            _ => CgbSupport::None,
+        };
+
+        let old_licensee = header[OLD_LICENSEE_ADDRESS];
+        let licensee = if old_licensee == USE_NEW_LICENSEE {
+            Licensee::New(read_string(&header[NEW_LICENSEE_ADDRESS..SGB_FLAG_ADDRESS]))
+        } else {
+            Licensee::Old(old_licensee)
+        };
+
+        //On CGB cartridges the last byte of the title is the CGB flag
+        let title_end = match cgb_support {
+            CgbSupport::None => NEW_LICENSEE_ADDRESS,
+            _ => CGB_FLAG_ADDRESS,
+        };
+
+        let header_checksum = header[HEADER_CHECKSUM_ADDRESS];
+        let global_checksum = (header[GLOBAL_CHECKSUM_ADDRESS] as u16) << 8
+            | header[GLOBAL_CHECKSUM_ADDRESS + 1] as u16;
+
+        //Same checks as the boot ROM. The global checksum is not checked by hardware
+        let logo_valid = contains_logo(rom, offset);
+        let header_checksum_valid = calculate_header_checksum(header) == header_checksum;
+
+        if !logo_valid {
+            warnings.push(HeaderWarning::InvalidLogo);
+        }
+
+        if !header_checksum_valid {
+            warnings.push(HeaderWarning::InvalidHeaderChecksum(header_checksum));
+        }
+
+        Ok(CartridgeHeader {
+            title: read_string(&header[TITLE_ADDRESS..title_end]),
+            cgb_support,
+            //SGB functions are only enabled if the new licensee code is used
+            sgb_supported: header[SGB_FLAG_ADDRESS] == 0x03 && old_licensee == USE_NEW_LICENSEE,
+            licensee,
+            cartridge_type: header[CARTRIDGE_TYPE_ADDRESS],
+            rom_size,
+            ram_size: ram_size.unwrap_or(None),
+            japanese: header[DESTINATION_ADDRESS] == 0x00,
+            version: header[VERSION_ADDRESS],
+            header_checksum,
+            global_checksum,
+            logo_valid,
+            header_checksum_valid,
+            global_checksum_valid: calculate_global_checksum(rom, offset) == global_checksum,
+            warnings,
+        })
+    }
+
+    pub fn rom_banks(&self) -> usize {
+        self.rom_size / ROM_BANK_SIZE
+    }
+}
+
+/// Checks if the header starting at the given offset contains the Nintendo logo
+pub fn contains_logo(rom: &[u8], offset: usize) -> bool {
+    let start = offset + LOGO_ADDRESS;
+    let end = offset + TITLE_ADDRESS;
+
+    end <= rom.len() && rom[start..end] == NINTENDO_LOGO
+}
+
+fn get_rom_size(value: u8) -> Option<usize> {
+    match value {
+        0x00..=0x08 => Some((32 * 1024) << value),
+        //Sizes only used by a few unofficial cartridges
+        0x52 => Some(72 * ROM_BANK_SIZE),
+        0x53 => Some(80 * ROM_BANK_SIZE),
+        0x54 => Some(96 * ROM_BANK_SIZE),
+        _ => None,
+    }
+}
+
+//None if the code is unknown, Some(None) if the cartridge has no ram
+fn get_ram_size(value: u8) -> Option<Option<usize>> {
+    match value {
+        0x00 => Some(None),
+        0x01 => Some(Some(2 * 1024)),
+        0x02 => Some(Some(8 * 1024)),
+        0x03 => Some(Some(32 * 1024)),
+        0x04 => Some(Some(128 * 1024)),
+        0x05 => Some(Some(64 * 1024)),
+        _ => None,
+    }
+}
+
+fn calculate_header_checksum(rom: &[u8]) -> u8 {
+    rom[TITLE_ADDRESS..HEADER_CHECKSUM_ADDRESS]
+        .iter()
+        .fold(0u8, |checksum, value| {
+            checksum.wrapping_sub(*value).wrapping_sub(1)
+        })
+}
+
+//Sum of all bytes except the checksum itself
+fn calculate_global_checksum(rom: &[u8], offset: usize) -> u16 {
+    let checksum_address = offset + GLOBAL_CHECKSUM_ADDRESS;
+
+    rom.iter()
+        .enumerate()
+        .filter(|(address, _)| *address != checksum_address && *address != checksum_address + 1)
+        .fold(0u16, |checksum, (_, value)| {
+            checksum.wrapping_add(*value as u16)
+        })
+}
+
+fn read_string(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|value| **value != 0)
+        .map(|value| *value as char)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //MBC1 with 64 KiB of ROM and 8 KiB of ram, valid logo and checksums
+    fn new_rom() -> Vec<u8> {
+        let mut rom = vec![0; 4 * ROM_BANK_SIZE];
+        rom[LOGO_ADDRESS..TITLE_ADDRESS].copy_from_slice(&NINTENDO_LOGO);
+        rom[TITLE_ADDRESS..TITLE_ADDRESS + 6].copy_from_slice(b"TETRIS");
+        rom[CARTRIDGE_TYPE_ADDRESS] = 0x03;
+        rom[ROM_SIZE_ADDRESS] = 0x01;
+        rom[RAM_SIZE_ADDRESS] = 0x02;
+        rom[DESTINATION_ADDRESS] = 0x01;
+        rom[OLD_LICENSEE_ADDRESS] = 0x01;
+        rom[VERSION_ADDRESS] = 0x02;
+        update_checksums(&mut rom);
+        rom
+    }
+
+    fn update_checksums(rom: &mut [u8]) {
+        rom[HEADER_CHECKSUM_ADDRESS] = calculate_header_checksum(rom);
+        let global_checksum = calculate_global_checksum(rom, 0);
+        rom[GLOBAL_CHECKSUM_ADDRESS..GLOBAL_CHECKSUM_ADDRESS + 2]
+            .copy_from_slice(&global_checksum.to_be_bytes());
+    }
+
+    #[test]
+    fn parses_valid_header() {
+        let header = CartridgeHeader::parse(&new_rom()).unwrap();
+
+        assert_eq!(header.title, "TETRIS");
+        assert_eq!(header.cgb_support, CgbSupport::None);
+        assert!(!header.sgb_supported);
+        assert_eq!(header.licensee, Licensee::Old(0x01));
+        assert_eq!(header.cartridge_type, 0x03);
+        assert_eq!(header.rom_size, 64 * 1024);
+        assert_eq!(header.rom_banks(), 4);
+        assert_eq!(header.ram_size, Some(8 * 1024));
+        assert!(!header.japanese);
+        assert_eq!(header.version, 0x02);
+        assert!(header.logo_valid);
+        assert!(header.header_checksum_valid);
+        assert!(header.global_checksum_valid);
+        assert_eq!(header.warnings, Vec::new());
+    }
+
+    #[test]
+    fn parses_cgb_and_sgb_flags() {
+        let mut rom = new_rom();
+        rom[TITLE_ADDRESS..CGB_FLAG_ADDRESS].copy_from_slice(b"POKEMON_GLDAAUE");
+        rom[NEW_LICENSEE_ADDRESS..SGB_FLAG_ADDRESS].copy_from_slice(b"01");
+        rom[SGB_FLAG_ADDRESS] = 0x03;
+        rom[OLD_LICENSEE_ADDRESS] = USE_NEW_LICENSEE;
+        rom[DESTINATION_ADDRESS] = 0x00;
+
+        rom[CGB_FLAG_ADDRESS] = 0x80;
+        let header = CartridgeHeader::parse(&rom).unwrap();
+        assert_eq!(header.cgb_support, CgbSupport::Supported);
+        assert_eq!(header.title, "POKEMON_GLDAAUE");
+        assert!(header.sgb_supported);
+        assert_eq!(header.licensee, Licensee::New("01".to_string()));
+        assert!(header.japanese);
+
+        rom[CGB_FLAG_ADDRESS] = 0xC0;
+        assert_eq!(
+            CartridgeHeader::parse(&rom).unwrap().cgb_support,
+            CgbSupport::Only
+        );
+
+        //The SGB flag is ignored with an old licensee code
+        rom[OLD_LICENSEE_ADDRESS] = 0x01;
+        assert!(!CartridgeHeader::parse(&rom).unwrap().sgb_supported);
+    }
+
+    #[test]
+    fn rejects_rom_without_header() {
+        let rom = new_rom();
+
+        assert!(CartridgeHeader::parse(&[]).is_err());
+        assert!(CartridgeHeader::parse(&rom[..HEADER_END_ADDRESS - 1]).is_err());
+        assert!(CartridgeHeader::parse(&rom[..HEADER_END_ADDRESS]).is_ok());
+        assert!(CartridgeHeader::parse_at(&rom, rom.len() - HEADER_END_ADDRESS + 1).is_err());
+    }
+
+    #[test]
+    fn warns_about_invalid_header_checksum() {
+        let mut rom = new_rom();
+        rom[HEADER_CHECKSUM_ADDRESS] ^= 0xFF;
+        let header_checksum = rom[HEADER_CHECKSUM_ADDRESS];
+
+        let header = CartridgeHeader::parse(&rom).unwrap();
+        assert!(!header.header_checksum_valid);
+        assert_eq!(
+            header.warnings,
+            vec![HeaderWarning::InvalidHeaderChecksum(header_checksum)]
+        );
+    }
+
+    #[test]
+    fn warns_about_invalid_logo() {
+        let mut rom = new_rom();
+        rom[LOGO_ADDRESS + 10] ^= 0xFF;
+
+        let header = CartridgeHeader::parse(&rom).unwrap();
+        assert!(!header.logo_valid);
+        assert!(header.header_checksum_valid);
+        assert_eq!(header.warnings, vec![HeaderWarning::InvalidLogo]);
+    }
+
+    #[test]
+    fn warns_about_unknown_sizes() {
+        let mut rom = new_rom();
+        rom[ROM_SIZE_ADDRESS] = 0x20;
+        rom[RAM_SIZE_ADDRESS] = 0x07;
+        update_checksums(&mut rom);
+
+        //The size of the file is used for the ROM and there is no ram
+        let header = CartridgeHeader::parse(&rom).unwrap();
+        assert_eq!(header.rom_size, rom.len());
+        assert_eq!(header.ram_size, None);
+        assert_eq!(
+            header.warnings,
+            vec![
+                HeaderWarning::UnknownRomSize(0x20),
+                HeaderWarning::UnknownRamSize(0x07),
+            ]
+        );
+
+        //Trimmed ROMs are rounded up to a power of two
+        let header = CartridgeHeader::parse(&rom[..3 * ROM_BANK_SIZE]).unwrap();
+        assert_eq!(header.rom_size, 4 * ROM_BANK_SIZE);
+    }
+
+    #[test]
+    fn parses_unofficial_rom_size() {
+        let mut rom = new_rom();
+        rom[ROM_SIZE_ADDRESS] = 0x52;
+
+        let header = CartridgeHeader::parse(&rom).unwrap();
+        assert_eq!(header.rom_banks(), 72);
+    }
+
+    #[test]
+    fn warns_about_truncated_rom() {
+        let rom = new_rom();
+
+        let header = CartridgeHeader::parse(&rom[..ROM_BANK_SIZE]).unwrap();
+        assert_eq!(header.rom_size, 4 * ROM_BANK_SIZE);
+        assert!(header.warnings.contains(&HeaderWarning::Truncated {
+            expected: 4 * ROM_BANK_SIZE,
+            actual: ROM_BANK_SIZE,
+        }));
+    }
+
+    #[test]
+    fn parses_header_at_offset() {
+        let mut rom = vec![0xFF; 2 * ROM_BANK_SIZE];
+        rom.extend_from_slice(&new_rom());
+
+        let header = CartridgeHeader::parse_at(&rom, 2 * ROM_BANK_SIZE).unwrap();
+        assert_eq!(header.title, "TETRIS");
+        assert!(header.logo_valid);
+        assert!(header.header_checksum_valid);
+    }
+}
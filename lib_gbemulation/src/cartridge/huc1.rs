@@ -0,0 +1,162 @@
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::infrared;
+use crate::cartridge::infrared::{InfraredPort, NullInfraredPort};
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+
+const IR_MODE: u8 = 0x0E;
+
+pub struct HuC1 {
+    cartridge_base: CartridgeBase,
+    infrared_port: Box<dyn InfraredPort + Send>,
+    ir_selected: bool,
+}
+
+impl HuC1 {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        //HuC1 cartridges always have battery backed ram
+        let ram_size = header.ram_size;
+        let mut cartridge_base = CartridgeBase::new(rom, header, true, ram_size, true, ram_dumper);
+
+        //RAM does not need to be enabled
+        cartridge_base.ram_enabled = true;
+
+        HuC1 {
+            cartridge_base,
+            infrared_port: Box::new(NullInfraredPort),
+            ir_selected: false,
+        }
+    }
+}
+
+impl Cartridge for HuC1 {
+    fn read(&self, address: u16) -> u8 {
+        self.cartridge_base.read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            //Selects if IR or RAM is mapped to 0xA000-0xBFFF
+            0x0..=0x1FFF => {
+                self.ir_selected = value & 0x0F == IR_MODE;
+            }
+            0x2000..=0x3FFF => {
+                let bank_number = value & 0x3F;
+                self.cartridge_base.rom_bank = if bank_number == 0 { 1 } else { bank_number };
+            }
+            0x4000..=0x5FFF => {
+                self.cartridge_base.ram_bank = value & 0x03;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ir_selected {
+            self.infrared_port.set_led(value & 0x01 == 0x01);
+            return;
+        }
+
+        self.cartridge_base.write_ram(address, value);
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if self.ir_selected {
+            return infrared::read_register(&*self.infrared_port);
+        }
+
+        self.cartridge_base.read_ram(address)
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
+    }
+
+    fn load_savegame(&mut self) {
+        self.cartridge_base.load_savegame();
+    }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_bool(self.ir_selected);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.ir_selected = state.read_bool()?;
+        Ok(())
+    }
+
+    fn set_infrared_port(&mut self, infrared_port: Box<dyn InfraredPort + Send>) {
+        self.infrared_port = infrared_port;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::infrared::InfraredLink;
+
+    const REGISTER_ADDRESS: u16 = 0xA000;
+
+    fn new_huc1() -> HuC1 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0xFF;
+        rom[0x149] = 0x02;
+        let header = CartridgeHeader::parse(&rom).unwrap();
+
+        HuC1::new(rom, header, None)
+    }
+
+    #[test]
+    fn loops_back_infrared_between_two_cartridges() {
+        let mut first = new_huc1();
+        let mut second = new_huc1();
+        let (first_link, second_link) = InfraredLink::create_pair();
+        first.set_infrared_port(Box::new(first_link));
+        second.set_infrared_port(Box::new(second_link));
+        first.write(0x0000, IR_MODE);
+        second.write(0x0000, IR_MODE);
+
+        assert_eq!(second.read_ram(REGISTER_ADDRESS), 0xC0);
+
+        first.write_ram(REGISTER_ADDRESS, 0x01);
+        assert_eq!(second.read_ram(REGISTER_ADDRESS), 0xC1);
+        assert_eq!(first.read_ram(REGISTER_ADDRESS), 0xC0);
+
+        second.write_ram(REGISTER_ADDRESS, 0x01);
+        first.write_ram(REGISTER_ADDRESS, 0x00);
+        assert_eq!(first.read_ram(REGISTER_ADDRESS), 0xC1);
+        assert_eq!(second.read_ram(REGISTER_ADDRESS), 0xC0);
+    }
+
+    #[test]
+    fn infrared_mode_does_not_touch_ram() {
+        let mut huc1 = new_huc1();
+        huc1.write_ram(REGISTER_ADDRESS, 0x42);
+
+        huc1.write(0x0000, IR_MODE);
+        huc1.write_ram(REGISTER_ADDRESS, 0x01);
+        assert_eq!(huc1.read_ram(REGISTER_ADDRESS), 0xC0);
+
+        huc1.write(0x0000, 0x00);
+        assert_eq!(huc1.read_ram(REGISTER_ADDRESS), 0x42);
+    }
+}
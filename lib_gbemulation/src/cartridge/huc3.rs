@@ -0,0 +1,427 @@
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::infrared;
+use crate::cartridge::infrared::{InfraredPort, NullInfraredPort};
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MINUTES_PER_DAY: u64 = 1440;
+//Minutes (2 bytes), days (2 bytes) and the unix timestamp of the last update (8 bytes)
+const RTC_SAVE_SIZE: usize = 12;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Mode {
+    RamReadOnly,
+    RamReadWrite,
+    RtcCommand,
+    RtcResponse,
+    RtcSemaphore,
+    Infrared,
+}
+
+/// Speaker of the HuC3. Receives the argument of the tone command, 0 turns it off
+pub trait ToneGenerator {
+    fn set_tone(&mut self, tone: u8);
+}
+
+pub struct NullToneGenerator;
+
+impl ToneGenerator for NullToneGenerator {
+    fn set_tone(&mut self, _tone: u8) {}
+}
+
+pub struct HuC3 {
+    cartridge_base: CartridgeBase,
+    infrared_port: Box<dyn InfraredPort + Send>,
+    tone_generator: Box<dyn ToneGenerator + Send>,
+    mode: Mode,
+    rtc: Rtc,
+    access_index: u8,
+    access_flags: u8,
+    response: u8,
+}
+
+impl HuC3 {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let ram_size = header.ram_size;
+        let mut cartridge_base = CartridgeBase::new(rom, header, true, ram_size, true, ram_dumper);
+
+        let rtc = Rtc::load(cartridge_base.savegame_trailer());
+        cartridge_base.set_savegame_trailer(rtc.save());
+
+        HuC3 {
+            cartridge_base,
+            infrared_port: Box::new(NullInfraredPort),
+            tone_generator: Box::new(NullToneGenerator),
+            mode: Mode::RamReadOnly,
+            rtc,
+            access_index: 0,
+            access_flags: 0,
+            response: 0,
+        }
+    }
+
+    fn write_rtc(&mut self, value: u8) {
+        self.rtc.write(self.access_index, value);
+
+        //Only a changed clock needs to be saved. Otherwise it is caught up when loading
+        self.cartridge_base.set_savegame_trailer(self.rtc.save());
+        self.cartridge_base.mark_ram_dirty();
+    }
+
+    fn execute_rtc_command(&mut self, value: u8) {
+        let argument = value & 0x0F;
+
+        match value >> 4 & 0x07 {
+            //Read the nibble at the access index and increment it
+            0x1 => {
+                self.rtc.update();
+                self.response = self.rtc.read(self.access_index);
+                self.access_index = self.access_index.wrapping_add(1);
+            }
+            //Write the nibble at the access index
+            0x2 => self.write_rtc(argument),
+            //Write the nibble at the access index and increment it
+            0x3 => {
+                self.write_rtc(argument);
+                self.access_index = self.access_index.wrapping_add(1);
+            }
+            0x4 => self.access_index = self.access_index & 0xF0 | argument,
+            0x5 => self.access_index = self.access_index & 0x0F | argument << 4,
+            0x6 => {
+                self.access_flags = argument;
+                self.tone_generator.set_tone(argument);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Cartridge for HuC3 {
+    fn read(&self, address: u16) -> u8 {
+        self.cartridge_base.read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            //Selects what is mapped to 0xA000-0xBFFF
+            0x0..=0x1FFF => {
+                self.mode = match value & 0x0F {
+                    0x0A => Mode::RamReadWrite,
+                    0x0B => Mode::RtcCommand,
+                    0x0C => Mode::RtcResponse,
+                    0x0D => Mode::RtcSemaphore,
+                    0x0E => Mode::Infrared,
+                    _ => Mode::RamReadOnly,
+                };
+
+                self.cartridge_base.set_ram_enabled(
+                    self.mode == Mode::RamReadOnly || self.mode == Mode::RamReadWrite,
+                );
+            }
+            0x2000..=0x3FFF => {
+                self.cartridge_base.rom_bank = value & 0x7F;
+            }
+            0x4000..=0x5FFF => {
+                self.cartridge_base.ram_bank = value & 0x03;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        match self.mode {
+            Mode::RamReadWrite => self.cartridge_base.write_ram(address, value),
+            Mode::RtcCommand => self.execute_rtc_command(value),
+            Mode::Infrared => self.infrared_port.set_led(value & 0x01 == 0x01),
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        match self.mode {
+            Mode::RamReadOnly | Mode::RamReadWrite => self.cartridge_base.read_ram(address),
+            Mode::RtcResponse => {
+                if self.access_flags == 0x02 {
+                    return 0x01;
+                }
+
+                self.response
+            }
+            //Commands are executed immediately so the RTC is always ready
+            Mode::RtcSemaphore => 0x01,
+            Mode::Infrared => infrared::read_register(&*self.infrared_port),
+            Mode::RtcCommand => 0xFF,
+        }
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
+    }
+
+    fn load_savegame(&mut self) {
+        self.cartridge_base.load_savegame();
+        self.rtc = Rtc::load(self.cartridge_base.savegame_trailer());
+        self.cartridge_base.set_savegame_trailer(self.rtc.save());
+    }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    //The clock follows the system time so it is not restored from the state
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_u8(match self.mode {
+            Mode::RamReadOnly => 0,
+            Mode::RamReadWrite => 1,
+            Mode::RtcCommand => 2,
+            Mode::RtcResponse => 3,
+            Mode::RtcSemaphore => 4,
+            Mode::Infrared => 5,
+        });
+        state.write_u8(self.access_index);
+        state.write_u8(self.access_flags);
+        state.write_u8(self.response);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.mode = match state.read_u8()? {
+            1 => Mode::RamReadWrite,
+            2 => Mode::RtcCommand,
+            3 => Mode::RtcResponse,
+            4 => Mode::RtcSemaphore,
+            5 => Mode::Infrared,
+            _ => Mode::RamReadOnly,
+        };
+        self.access_index = state.read_u8()?;
+        self.access_flags = state.read_u8()?;
+        self.response = state.read_u8()?;
+        Ok(())
+    }
+
+    fn set_infrared_port(&mut self, infrared_port: Box<dyn InfraredPort + Send>) {
+        self.infrared_port = infrared_port;
+    }
+
+    fn set_tone_generator(&mut self, tone_generator: Box<dyn ToneGenerator + Send>) {
+        self.tone_generator = tone_generator;
+    }
+}
+
+//Counts minutes of the day and days. It follows the system time, also while the emulator is not running
+struct Rtc {
+    minutes: u16,
+    days: u16,
+    last_update: u64,
+}
+
+impl Rtc {
+    fn load(data: &[u8]) -> Self {
+        //Clocks saved by other emulators for MBC3 cartridges use a different format
+        if data.len() != RTC_SAVE_SIZE {
+            return Rtc {
+                minutes: 0,
+                days: 0,
+                last_update: get_timestamp(),
+            };
+        }
+
+        let mut timestamp = [0; 8];
+        timestamp.copy_from_slice(&data[4..12]);
+
+        let mut rtc = Rtc {
+            minutes: u16::from_le_bytes([data[0], data[1]]),
+            days: u16::from_le_bytes([data[2], data[3]]),
+            last_update: u64::from_le_bytes(timestamp),
+        };
+
+        rtc.update();
+        rtc
+    }
+
+    fn save(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(RTC_SAVE_SIZE);
+        data.extend_from_slice(&self.minutes.to_le_bytes());
+        data.extend_from_slice(&self.days.to_le_bytes());
+        data.extend_from_slice(&self.last_update.to_le_bytes());
+        data
+    }
+
+    fn update(&mut self) {
+        let elapsed_minutes = get_timestamp().saturating_sub(self.last_update) / 60;
+        self.last_update += elapsed_minutes * 60;
+
+        let minutes = self.minutes as u64 + elapsed_minutes;
+        self.minutes = (minutes % MINUTES_PER_DAY) as u16;
+        self.days = self.days.wrapping_add((minutes / MINUTES_PER_DAY) as u16);
+    }
+
+    //Index 0-2 are the nibbles of the minutes, 3-6 the nibbles of the days
+    fn read(&self, index: u8) -> u8 {
+        match index {
+            0..=2 => (self.minutes >> (index * 4)) as u8 & 0x0F,
+            3..=6 => (self.days >> ((index - 3) * 4)) as u8 & 0x0F,
+            _ => 0,
+        }
+    }
+
+    //The nibbles which are not written keep counting, so the clock is caught up first
+    fn write(&mut self, index: u8, value: u8) {
+        self.update();
+
+        match index {
+            0..=2 => {
+                let shift = index * 4;
+                self.minutes = self.minutes & !(0x0F << shift) | (value as u16) << shift;
+            }
+            3..=6 => {
+                let shift = (index - 3) * 4;
+                self.days = self.days & !(0x0F << shift) | (value as u16) << shift;
+            }
+            _ => return,
+        }
+
+        self.last_update = get_timestamp();
+    }
+}
+
+fn get_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::infrared::InfraredLink;
+
+    const MODE_ADDRESS: u16 = 0x0000;
+    const REGISTER_ADDRESS: u16 = 0xA000;
+
+    fn new_huc3() -> HuC3 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0xFE;
+        let header = CartridgeHeader::parse(&rom).unwrap();
+
+        HuC3::new(rom, header, None)
+    }
+
+    fn rtc_command(huc3: &mut HuC3, command: u8) {
+        huc3.write(MODE_ADDRESS, 0x0B);
+        huc3.write_ram(REGISTER_ADDRESS, command);
+    }
+
+    fn rtc_response(huc3: &mut HuC3) -> u8 {
+        huc3.write(MODE_ADDRESS, 0x0C);
+        huc3.read_ram(REGISTER_ADDRESS)
+    }
+
+    fn read_nibble(huc3: &mut HuC3) -> u8 {
+        rtc_command(huc3, 0x10);
+        rtc_response(huc3)
+    }
+
+    fn set_access_index(huc3: &mut HuC3, index: u8) {
+        rtc_command(huc3, 0x40 | index & 0x0F);
+        rtc_command(huc3, 0x50 | index >> 4);
+    }
+
+    #[test]
+    fn writes_and_reads_rtc_nibbles() {
+        let mut huc3 = new_huc3();
+
+        //Minutes 0x123 and days 0x0042, lowest nibble first
+        set_access_index(&mut huc3, 0x00);
+        for nibble in [0x3, 0x2, 0x1, 0x2, 0x4, 0x0, 0x0] {
+            rtc_command(&mut huc3, 0x30 | nibble);
+        }
+
+        set_access_index(&mut huc3, 0x00);
+        let nibbles: Vec<u8> = (0..7).map(|_| read_nibble(&mut huc3)).collect();
+        assert_eq!(nibbles, vec![0x3, 0x2, 0x1, 0x2, 0x4, 0x0, 0x0]);
+        assert_eq!(huc3.rtc.minutes, 0x123);
+        assert_eq!(huc3.rtc.days, 0x0042);
+    }
+
+    #[test]
+    fn write_command_keeps_access_index() {
+        let mut huc3 = new_huc3();
+
+        set_access_index(&mut huc3, 0x03);
+        rtc_command(&mut huc3, 0x25);
+        rtc_command(&mut huc3, 0x27);
+
+        assert_eq!(read_nibble(&mut huc3), 0x7);
+        assert_eq!(read_nibble(&mut huc3), 0x0);
+        assert_eq!(huc3.rtc.days, 0x0007);
+    }
+
+    #[test]
+    fn sets_both_nibbles_of_access_index() {
+        let mut huc3 = new_huc3();
+        huc3.rtc.days = 0x0009;
+
+        rtc_command(&mut huc3, 0x43);
+        rtc_command(&mut huc3, 0x51);
+        assert_eq!(huc3.access_index, 0x13);
+        assert_eq!(read_nibble(&mut huc3), 0x0);
+
+        rtc_command(&mut huc3, 0x50);
+        rtc_command(&mut huc3, 0x43);
+        assert_eq!(huc3.access_index, 0x03);
+        assert_eq!(read_nibble(&mut huc3), 0x9);
+    }
+
+    #[test]
+    fn writing_a_nibble_keeps_elapsed_time() {
+        let mut rtc = Rtc {
+            minutes: 0,
+            days: 0,
+            last_update: get_timestamp() - 2 * MINUTES_PER_DAY * 60 - 5 * 60,
+        };
+
+        rtc.write(0, 0x7);
+
+        assert_eq!(rtc.minutes, 0x7);
+        assert_eq!(rtc.days, 2);
+    }
+
+    #[test]
+    fn loops_back_infrared_between_two_cartridges() {
+        let mut first = new_huc3();
+        let mut second = new_huc3();
+        let (first_link, second_link) = InfraredLink::create_pair();
+        first.set_infrared_port(Box::new(first_link));
+        second.set_infrared_port(Box::new(second_link));
+        first.write(MODE_ADDRESS, 0x0E);
+        second.write(MODE_ADDRESS, 0x0E);
+
+        assert_eq!(second.read_ram(REGISTER_ADDRESS), 0xC0);
+
+        first.write_ram(REGISTER_ADDRESS, 0x01);
+        assert_eq!(second.read_ram(REGISTER_ADDRESS), 0xC1);
+        assert_eq!(first.read_ram(REGISTER_ADDRESS), 0xC0);
+
+        second.write_ram(REGISTER_ADDRESS, 0x01);
+        first.write_ram(REGISTER_ADDRESS, 0x00);
+        assert_eq!(first.read_ram(REGISTER_ADDRESS), 0xC1);
+        assert_eq!(second.read_ram(REGISTER_ADDRESS), 0xC0);
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+
+/// Infrared LED and receiver of a cartridge. Implement it to connect the cartridge to something
+/// that sends and receives light
+pub trait InfraredPort {
+    fn set_led(&mut self, on: bool);
+    fn is_light_detected(&self) -> bool;
+}
+
+/// Port that never receives any light
+pub struct NullInfraredPort;
+
+impl InfraredPort for NullInfraredPort {
+    fn set_led(&mut self, _on: bool) {}
+
+    fn is_light_detected(&self) -> bool {
+        false
+    }
+}
+
+/// One end of an infrared link. The LED of each end is seen by the receiver of the other one
+pub struct InfraredLink {
+    leds: Arc<Mutex<[bool; 2]>>,
+    index: usize,
+}
+
+impl InfraredLink {
+    /// Creates two ends facing each other. Can be used to connect two local emulator instances
+    pub fn create_pair() -> (InfraredLink, InfraredLink) {
+        let leds = Arc::new(Mutex::new([false; 2]));
+
+        (
+            InfraredLink {
+                leds: Arc::clone(&leds),
+                index: 0,
+            },
+            InfraredLink { leds, index: 1 },
+        )
+    }
+}
+
+impl InfraredPort for InfraredLink {
+    fn set_led(&mut self, on: bool) {
+        self.leds.lock().unwrap()[self.index] = on;
+    }
+
+    fn is_light_detected(&self) -> bool {
+        self.leds.lock().unwrap()[1 - self.index]
+    }
+}
+
+/// Value read from the IR register of HuC cartridges
+pub fn read_register(port: &dyn InfraredPort) -> u8 {
+    if port.is_light_detected() {
+        0xC1
+    } else {
+        0xC0
+    }
+}
@@ -1,5 +1,12 @@
 use crate::cartridge::cartridge_base::CartridgeBase;
-use crate::cartridge::{get_ram_size, Cartridge, RamDumper, CARTRIDGE_TYPE_ADDRESS};
+use crate::cartridge::header;
+use crate::cartridge::header::{CartridgeHeader, ROM_BANK_SIZE};
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+
+//Multicarts contain a full game every 16 banks
+const MULTICART_GAME_BANKS: usize = 0x10;
+const MULTICART_ROM_SIZE: usize = 1024 * 1024;
 
 enum Mode {
     RomBankingMode,
@@ -9,59 +16,103 @@ enum Mode {
 pub struct Mbc1 {
     cartridge_base: CartridgeBase,
     selected_mode: Mode,
+    //Lower 5 bits of the rom bank
+    bank1: u8,
+    //Upper 2 bits of the rom bank or the ram bank
+    bank2: u8,
+    //Bank mapped to 0x0000-0x3FFF
+    rom_bank0: u8,
+    //MBC1M multicarts only connect 4 bits of bank1
+    multicart: bool,
 }
 
 impl Mbc1 {
-    pub fn new(rom: Vec<u8>, ram_dumper: Option<Box<dyn RamDumper + Send>>) -> Self {
-        let cartridge_type = rom[CARTRIDGE_TYPE_ADDRESS];
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let cartridge_type = header.cartridge_type;
         let has_ram = cartridge_type == 0x02 || cartridge_type == 0x03;
         let has_battery = cartridge_type == 0x03;
-        let ram_size = get_ram_size(&rom);
+        let ram_size = header.ram_size;
+        let multicart = is_multicart(&rom);
 
-        let cartridge_base = CartridgeBase::new(rom, has_ram, ram_size, has_battery, ram_dumper);
+        let cartridge_base =
+            CartridgeBase::new(rom, header, has_ram, ram_size, has_battery, ram_dumper);
 
-        Mbc1 {
+        let mut mbc1 = Mbc1 {
             cartridge_base,
             selected_mode: Mode::RomBankingMode,
+            bank1: 1,
+            bank2: 0,
+            rom_bank0: 0,
+            multicart,
+        };
+
+        mbc1.update_banks();
+        mbc1
+    }
+
+    fn update_banks(&mut self) {
+        let (bank1, bank2) = if self.multicart {
+            (self.bank1 & 0x0F, self.bank2 << 4)
+        } else {
+            (self.bank1, self.bank2 << 5)
+        };
+
+        self.cartridge_base.rom_bank = bank2 | bank1;
+
+        //In RAM banking mode bank2 also switches the area of bank 0 and the ram bank
+        match self.selected_mode {
+            Mode::RomBankingMode => {
+                self.rom_bank0 = 0;
+                self.cartridge_base.ram_bank = 0;
+            }
+            Mode::RamBankingMode => {
+                self.rom_bank0 = bank2;
+                self.cartridge_base.ram_bank = self.bank2;
+            }
         }
     }
 }
 
 impl Cartridge for Mbc1 {
     fn read(&self, address: u16) -> u8 {
-        self.cartridge_base.read(address)
+        match address {
+            0x0..=0x3FFF => self
+                .cartridge_base
+                .read_rom(self.rom_bank0 as usize, address),
+            _ => self.cartridge_base.read(address),
+        }
     }
 
     fn write(&mut self, address: u16, value: u8) {
         match address {
             0x0..=0x1FFF => {
-                self.cartridge_base.ram_enabled = value == 0x0A;
+                self.cartridge_base.set_ram_enabled(value & 0x0F == 0x0A);
             }
             //Address range for rom bank number
             0x2000..=0x3FFF => {
-                //0 is also 1
-                let bank_number = if value == 0 { 1 } else { value };
-                //Only set lower 5 bits
-                self.cartridge_base.rom_bank =
-                    self.cartridge_base.rom_bank & 0x60 | bank_number & 0x1F;
+                //0 is also 1. Checked before the upper bit is dropped on multicarts
+                let bank_number = value & 0x1F;
+                self.bank1 = if bank_number == 0 { 1 } else { bank_number };
+                self.update_banks();
+            }
+            //Address range for RAM bank number or upper bits of rom bank number
+            0x4000..=0x5FFF => {
+                self.bank2 = value & 0x03;
+                self.update_banks();
             }
-            //Address range for RAM bank number
-            0x4000..=0x5FFF => match self.selected_mode {
-                Mode::RamBankingMode => {
-                    self.cartridge_base.ram_bank = value;
-                }
-                Mode::RomBankingMode => {
-                    //Only set upper 2 bits
-                    self.cartridge_base.rom_bank =
-                        self.cartridge_base.rom_bank | (value & 0x03) << 5;
-                }
-            },
             //Select Mode
-            0x6000..=0x7FFF => match value {
-                0 => self.selected_mode = Mode::RomBankingMode,
-                1 => self.selected_mode = Mode::RamBankingMode,
-                _ => {}
-            },
+            0x6000..=0x7FFF => {
+                self.selected_mode = if value & 0x01 == 0 {
+                    Mode::RomBankingMode
+                } else {
+                    Mode::RamBankingMode
+                };
+                self.update_banks();
+            }
             _ => {}
         }
     }
@@ -74,11 +125,60 @@ impl Cartridge for Mbc1 {
         self.cartridge_base.read_ram(address)
     }
 
-    fn dump_savegame(&self) {
-        self.cartridge_base.dump_savegame();
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
     }
 
     fn load_savegame(&mut self) {
         self.cartridge_base.load_savegame();
     }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_u8(match self.selected_mode {
+            Mode::RomBankingMode => 0,
+            Mode::RamBankingMode => 1,
+        });
+        state.write_u8(self.bank1);
+        state.write_u8(self.bank2);
+        state.write_u8(self.rom_bank0);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.selected_mode = match state.read_u8()? {
+            1 => Mode::RamBankingMode,
+            _ => Mode::RomBankingMode,
+        };
+        self.bank1 = state.read_u8()?;
+        self.bank2 = state.read_u8()?;
+        self.rom_bank0 = state.read_u8()?;
+        Ok(())
+    }
+}
+
+//Multicarts have the same size as regular 1MB cartridges but contain a
+//header with the Nintendo logo for every game
+fn is_multicart(rom: &[u8]) -> bool {
+    if rom.len() != MULTICART_ROM_SIZE {
+        return false;
+    }
+
+    let games_found = (1..4)
+        .filter(|game| header::contains_logo(rom, game * MULTICART_GAME_BANKS * ROM_BANK_SIZE))
+        .count();
+
+    games_found >= 2
 }
@@ -1,18 +1,40 @@
 use crate::cartridge::cartridge_base::CartridgeBase;
-use crate::cartridge::{Cartridge, RamDumper, CARTRIDGE_TYPE_ADDRESS};
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus, EXT_RAM_ADDRESS};
+use crate::state::{StateReader, StateWriter};
+
+//512 half-bytes built into the MBC2
+const RAM_SIZE: usize = 512;
 
 pub struct Mbc2 {
     cartridge_base: CartridgeBase,
 }
 
 impl Mbc2 {
-    pub fn new(rom: Vec<u8>, ram_dumper: Option<Box<dyn RamDumper + Send>>) -> Self {
-        let cartridge_type = rom[CARTRIDGE_TYPE_ADDRESS];
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let cartridge_type = header.cartridge_type;
         let has_battery = cartridge_type == 0x06;
 
-        let cartridge_base = CartridgeBase::new(rom, true, Some(512), has_battery, ram_dumper);
+        let cartridge_base =
+            CartridgeBase::new(rom, header, true, Some(RAM_SIZE), has_battery, ram_dumper);
 
-        Mbc2 { cartridge_base }
+        let mut mbc2 = Mbc2 { cartridge_base };
+        mbc2.normalize_ram();
+        mbc2
+    }
+
+    //Other emulators store the 512 half-bytes with the upper nibble set.
+    //Bring a loaded savegame into the internal format
+    fn normalize_ram(&mut self) {
+        if let Some(ref mut ram) = self.cartridge_base.ram {
+            for value in ram.iter_mut() {
+                *value &= 0x0F;
+            }
+        }
     }
 }
 
@@ -23,45 +45,64 @@ impl Cartridge for Mbc2 {
 
     fn write(&mut self, address: u16, value: u8) {
         match address {
-            0x0..=0x1FFF => {
+            //Bit 8 of the address selects the register
+            0x0..=0x3FFF => {
                 if address & 0x100 == 0 {
-                    self.cartridge_base.ram_enabled = value == 0x0A
-                }
-            }
-            0x2000..=0x3FFF => {
-                if address & 0x100 != 0x100 {
-                    return;
+                    self.cartridge_base.set_ram_enabled(value & 0x0F == 0x0A);
+                } else {
+                    let bank_number = value & 0x0F;
+                    self.cartridge_base.rom_bank = if bank_number == 0 { 1 } else { bank_number };
                 }
-
-                let bank_number = if value == 0 { 1 } else { value };
-
-                self.cartridge_base.rom_bank = bank_number & 0xF;
             }
             _ => {}
         }
     }
 
     fn write_ram(&mut self, address: u16, value: u8) {
-        match address {
-            0xA000..=0xA1FF => {
-                self.cartridge_base.write_ram(address, value & 0xF);
-            }
-            _ => {}
-        }
+        self.cartridge_base
+            .write_ram(get_ram_address(address), value & 0x0F);
     }
 
     fn read_ram(&self, address: u16) -> u8 {
-        match address {
-            0xA000..=0xA1FF => self.cartridge_base.read_ram(address) & 0xF,
-            _ => 0,
+        if !self.cartridge_base.ram_enabled {
+            return 0xFF;
         }
+
+        //Only the lower 4 bits are connected, the upper bits read as 1
+        self.cartridge_base.read_ram(get_ram_address(address)) | 0xF0
     }
 
-    fn dump_savegame(&self) {
-        self.cartridge_base.dump_savegame();
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
     }
 
     fn load_savegame(&mut self) {
         self.cartridge_base.load_savegame();
+        self.normalize_ram();
+    }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
     }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)
+    }
+}
+
+//The 512 bytes are mirrored across the whole external ram area
+fn get_ram_address(address: u16) -> u16 {
+    EXT_RAM_ADDRESS as u16 + (address & (RAM_SIZE as u16 - 1))
 }
@@ -0,0 +1,524 @@
+use crate::cartridge::accelerometer::{Accelerometer, NullAccelerometer};
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+
+//93LC56: 128 words of 16 bits
+const EEPROM_SIZE: usize = 256;
+const EEPROM_WORDS: u8 = 128;
+//Start bit is not counted. 2 bit opcode followed by 8 address bits
+const EEPROM_COMMAND_BITS: u8 = 10;
+
+const ACCELEROMETER_CENTER: f32 = 0x81D0 as f32;
+const ACCELEROMETER_ONE_G: f32 = 0x70 as f32;
+const ACCELEROMETER_ERASED: u16 = 0x8000;
+
+enum EepromState {
+    //Waiting for the start bit
+    Idle,
+    Command {
+        bits: u16,
+        count: u8,
+    },
+    Read {
+        address: u8,
+        word: u16,
+        count: u8,
+    },
+    Write {
+        address: u8,
+        all: bool,
+        bits: u16,
+        count: u8,
+    },
+    //Command is finished. Nothing happens until chip select goes low
+    Done,
+}
+
+impl EepromState {
+    fn save_state(&self, state: &mut StateWriter) {
+        //Tag followed by address, all, bits or word and count
+        let (tag, address, all, bits, count) = match *self {
+            EepromState::Idle => (0, 0, false, 0, 0),
+            EepromState::Command { bits, count } => (1, 0, false, bits, count),
+            EepromState::Read {
+                address,
+                word,
+                count,
+            } => (2, address, false, word, count),
+            EepromState::Write {
+                address,
+                all,
+                bits,
+                count,
+            } => (3, address, all, bits, count),
+            EepromState::Done => (4, 0, false, 0, 0),
+        };
+
+        state.write_u8(tag);
+        state.write_u8(address);
+        state.write_bool(all);
+        state.write_u16(bits);
+        state.write_u8(count);
+    }
+
+    fn load_state(state: &mut StateReader) -> Result<Self, String> {
+        let tag = state.read_u8()?;
+        let address = state.read_u8()? % EEPROM_WORDS;
+        let all = state.read_bool()?;
+        let bits = state.read_u16()?;
+        let count = state.read_u8()?;
+
+        match tag {
+            0 => Ok(EepromState::Idle),
+            1 if count < EEPROM_COMMAND_BITS => Ok(EepromState::Command { bits, count }),
+            2 if count < 16 => Ok(EepromState::Read {
+                address,
+                word: bits,
+                count,
+            }),
+            3 if count < 16 => Ok(EepromState::Write {
+                address,
+                all,
+                bits,
+                count,
+            }),
+            4 => Ok(EepromState::Done),
+            _ => Err("Invalid EEPROM state".to_string()),
+        }
+    }
+}
+
+pub struct Mbc7 {
+    cartridge_base: CartridgeBase,
+    accelerometer: Box<dyn Accelerometer + Send>,
+    //The second enable register at 0x4000-0x5FFF
+    ram_enabled2: bool,
+    x_latch: u16,
+    y_latch: u16,
+    latch_erased: bool,
+    eeprom_state: EepromState,
+    eeprom_write_enabled: bool,
+    chip_select: bool,
+    clock: bool,
+    data_in: bool,
+    data_out: bool,
+}
+
+impl Mbc7 {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        //The EEPROM is stored as ram so it is saved through the ram dumper
+        let cartridge_base =
+            CartridgeBase::new(rom, header, true, Some(EEPROM_SIZE), true, ram_dumper);
+
+        Mbc7 {
+            cartridge_base,
+            accelerometer: Box::new(NullAccelerometer),
+            ram_enabled2: false,
+            x_latch: ACCELEROMETER_ERASED,
+            y_latch: ACCELEROMETER_ERASED,
+            latch_erased: false,
+            eeprom_state: EepromState::Idle,
+            eeprom_write_enabled: false,
+            chip_select: false,
+            clock: false,
+            data_in: false,
+            data_out: true,
+        }
+    }
+
+    fn is_ram_enabled(&self) -> bool {
+        self.cartridge_base.ram_enabled && self.ram_enabled2
+    }
+
+    fn latch_accelerometer(&mut self) {
+        let (x, y) = self.accelerometer.read_tilt();
+        self.x_latch = to_accelerometer_value(x);
+        self.y_latch = to_accelerometer_value(y);
+    }
+
+    fn write_eeprom_pins(&mut self, value: u8) {
+        let chip_select = value & 0x80 == 0x80;
+        let clock = value & 0x40 == 0x40;
+        self.data_in = value & 0x02 == 0x02;
+
+        if !chip_select {
+            self.eeprom_state = EepromState::Idle;
+        } else if clock && !self.clock {
+            //Data is shifted on the rising edge of the clock
+            self.clock_eeprom();
+        }
+
+        self.chip_select = chip_select;
+        self.clock = clock;
+    }
+
+    fn read_eeprom_pins(&self) -> u8 {
+        (self.chip_select as u8) << 7
+            | (self.clock as u8) << 6
+            | (self.data_in as u8) << 1
+            | self.data_out as u8
+    }
+
+    fn clock_eeprom(&mut self) {
+        let bit = self.data_in as u16;
+
+        self.eeprom_state = match self.eeprom_state {
+            EepromState::Idle if self.data_in => EepromState::Command { bits: 0, count: 0 },
+            EepromState::Idle => EepromState::Idle,
+            EepromState::Command { bits, count } => {
+                let bits = bits << 1 | bit;
+
+                if count + 1 == EEPROM_COMMAND_BITS {
+                    self.execute_eeprom_command(bits)
+                } else {
+                    EepromState::Command {
+                        bits,
+                        count: count + 1,
+                    }
+                }
+            }
+            EepromState::Read {
+                address,
+                word,
+                count,
+            } => {
+                self.data_out = word & 0x8000 == 0x8000;
+
+                //Reading continues with the next word
+                if count + 1 == 16 {
+                    let address = (address + 1) % EEPROM_WORDS;
+                    EepromState::Read {
+                        address,
+                        word: self.read_eeprom_word(address),
+                        count: 0,
+                    }
+                } else {
+                    EepromState::Read {
+                        address,
+                        word: word << 1,
+                        count: count + 1,
+                    }
+                }
+            }
+            EepromState::Write {
+                address,
+                all,
+                bits,
+                count,
+            } => {
+                let bits = bits << 1 | bit;
+
+                if count + 1 < 16 {
+                    EepromState::Write {
+                        address,
+                        all,
+                        bits,
+                        count: count + 1,
+                    }
+                } else {
+                    if all {
+                        self.fill_eeprom(bits);
+                    } else {
+                        self.write_eeprom_word(address, bits);
+                    }
+
+                    self.data_out = true;
+                    EepromState::Done
+                }
+            }
+            EepromState::Done => EepromState::Done,
+        };
+    }
+
+    fn execute_eeprom_command(&mut self, command: u16) -> EepromState {
+        let address = (command & 0x7F) as u8;
+
+        match command >> 8 {
+            //READ. A dummy zero is output first
+            0b10 => {
+                self.data_out = false;
+                EepromState::Read {
+                    address,
+                    word: self.read_eeprom_word(address),
+                    count: 0,
+                }
+            }
+            //WRITE
+            0b01 => EepromState::Write {
+                address,
+                all: false,
+                bits: 0,
+                count: 0,
+            },
+            //ERASE
+            0b11 => {
+                self.write_eeprom_word(address, 0xFFFF);
+                self.data_out = true;
+                EepromState::Done
+            }
+            _ => match command >> 6 & 0x03 {
+                //EWDS
+                0b00 => {
+                    self.eeprom_write_enabled = false;
+                    EepromState::Done
+                }
+                //WRAL
+                0b01 => EepromState::Write {
+                    address: 0,
+                    all: true,
+                    bits: 0,
+                    count: 0,
+                },
+                //ERAL
+                0b10 => {
+                    self.fill_eeprom(0xFFFF);
+                    self.data_out = true;
+                    EepromState::Done
+                }
+                //EWEN
+                _ => {
+                    self.eeprom_write_enabled = true;
+                    EepromState::Done
+                }
+            },
+        }
+    }
+
+    fn read_eeprom_word(&self, address: u8) -> u16 {
+        match self.cartridge_base.ram {
+            Some(ref ram) => {
+                let index = address as usize * 2;
+                (ram[index] as u16) << 8 | ram[index + 1] as u16
+            }
+            None => 0xFFFF,
+        }
+    }
+
+    fn write_eeprom_word(&mut self, address: u8, value: u16) {
+        if !self.eeprom_write_enabled {
+            return;
+        }
+
+        if let Some(ref mut ram) = self.cartridge_base.ram {
+            let index = address as usize * 2;
+            ram[index] = (value >> 8) as u8;
+            ram[index + 1] = value as u8;
+        }
+
+        self.cartridge_base.mark_ram_dirty();
+    }
+
+    fn fill_eeprom(&mut self, value: u16) {
+        for address in 0..EEPROM_WORDS {
+            self.write_eeprom_word(address, value);
+        }
+    }
+}
+
+impl Cartridge for Mbc7 {
+    fn read(&self, address: u16) -> u8 {
+        self.cartridge_base.read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0..=0x1FFF => {
+                self.cartridge_base.set_ram_enabled(value == 0x0A);
+            }
+            0x2000..=0x3FFF => {
+                self.cartridge_base.rom_bank = value & 0x7F;
+            }
+            0x4000..=0x5FFF => {
+                self.ram_enabled2 = value == 0x40;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        //Registers are only mapped to 0xA000-0xAFFF
+        if !self.is_ram_enabled() || address > 0xAFFF {
+            return;
+        }
+
+        match address >> 4 & 0x0F {
+            0x0 if value == 0x55 => {
+                self.x_latch = ACCELEROMETER_ERASED;
+                self.y_latch = ACCELEROMETER_ERASED;
+                self.latch_erased = true;
+            }
+            0x1 if value == 0xAA && self.latch_erased => {
+                self.latch_accelerometer();
+                self.latch_erased = false;
+            }
+            0x8 => self.write_eeprom_pins(value),
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if !self.is_ram_enabled() || address > 0xAFFF {
+            return 0xFF;
+        }
+
+        match address >> 4 & 0x0F {
+            0x2 => self.x_latch as u8,
+            0x3 => (self.x_latch >> 8) as u8,
+            0x4 => self.y_latch as u8,
+            0x5 => (self.y_latch >> 8) as u8,
+            0x6 => 0x00,
+            0x8 => self.read_eeprom_pins(),
+            _ => 0xFF,
+        }
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
+    }
+
+    fn load_savegame(&mut self) {
+        self.cartridge_base.load_savegame();
+    }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_bool(self.ram_enabled2);
+        state.write_u16(self.x_latch);
+        state.write_u16(self.y_latch);
+        state.write_bool(self.latch_erased);
+        state.write_bool(self.eeprom_write_enabled);
+        self.eeprom_state.save_state(state);
+        state.write_bool(self.chip_select);
+        state.write_bool(self.clock);
+        state.write_bool(self.data_in);
+        state.write_bool(self.data_out);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.ram_enabled2 = state.read_bool()?;
+        self.x_latch = state.read_u16()?;
+        self.y_latch = state.read_u16()?;
+        self.latch_erased = state.read_bool()?;
+        self.eeprom_write_enabled = state.read_bool()?;
+
+        self.eeprom_state = EepromState::load_state(state)?;
+        self.chip_select = state.read_bool()?;
+        self.clock = state.read_bool()?;
+        self.data_in = state.read_bool()?;
+        self.data_out = state.read_bool()?;
+
+        Ok(())
+    }
+
+    fn set_accelerometer(&mut self, accelerometer: Box<dyn Accelerometer + Send>) {
+        self.accelerometer = accelerometer;
+    }
+}
+
+fn to_accelerometer_value(tilt: f32) -> u16 {
+    (ACCELEROMETER_CENTER + tilt * ACCELEROMETER_ONE_G) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EEPROM_PINS_ADDRESS: u16 = 0xA080;
+
+    fn new_mbc7() -> Mbc7 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0x22;
+        let header = CartridgeHeader::parse(&rom).unwrap();
+
+        let mut mbc7 = Mbc7::new(rom, header, None);
+        mbc7.write(0x0000, 0x0A);
+        mbc7.write(0x4000, 0x40);
+        mbc7
+    }
+
+    fn send_bits(mbc7: &mut Mbc7, value: u16, count: u8) {
+        for index in (0..count).rev() {
+            let data_in = ((value >> index) as u8 & 0x01) << 1;
+            mbc7.write_ram(EEPROM_PINS_ADDRESS, 0x80 | data_in);
+            mbc7.write_ram(EEPROM_PINS_ADDRESS, 0xC0 | data_in);
+        }
+    }
+
+    fn end_command(mbc7: &mut Mbc7) {
+        mbc7.write_ram(EEPROM_PINS_ADDRESS, 0x00);
+    }
+
+    fn read_word(mbc7: &mut Mbc7, address: u8) -> u16 {
+        //Start bit, READ and the address. The dummy zero is output with the last address bit
+        send_bits(mbc7, 0b110 << 8 | address as u16, 11);
+
+        let mut word = 0;
+        for _ in 0..16 {
+            send_bits(mbc7, 0, 1);
+            word = word << 1 | (mbc7.read_ram(EEPROM_PINS_ADDRESS) & 0x01) as u16;
+        }
+
+        end_command(mbc7);
+        word
+    }
+
+    #[test]
+    fn writes_and_reads_eeprom() {
+        let mut mbc7 = new_mbc7();
+
+        //EWEN
+        send_bits(&mut mbc7, 0b100_1100_0000, 11);
+        end_command(&mut mbc7);
+
+        //WRITE
+        send_bits(&mut mbc7, 0b101 << 8 | 0x05, 11);
+        send_bits(&mut mbc7, 0xBEEF, 16);
+        end_command(&mut mbc7);
+
+        assert_eq!(read_word(&mut mbc7, 0x05), 0xBEEF);
+        assert_eq!(read_word(&mut mbc7, 0x06), 0x0000);
+    }
+
+    #[test]
+    fn continues_command_after_loading_state() {
+        let mut mbc7 = new_mbc7();
+
+        send_bits(&mut mbc7, 0b100_1100_0000, 11);
+        end_command(&mut mbc7);
+
+        //Save in the middle of the data bits of a WRITE
+        send_bits(&mut mbc7, 0b101 << 8 | 0x05, 11);
+        send_bits(&mut mbc7, 0xBE, 8);
+
+        let mut state = StateWriter::new();
+        mbc7.save_state(&mut state);
+        let data = state.into_data();
+
+        let mut loaded = new_mbc7();
+        loaded.load_state(&mut StateReader::new(&data)).unwrap();
+
+        send_bits(&mut loaded, 0xEF, 8);
+        end_command(&mut loaded);
+
+        assert_eq!(read_word(&mut loaded, 0x05), 0xBEEF);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header::{CartridgeHeader, ROM_BANK_SIZE};
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+
+//The menu is located in the last 32KB of the ROM
+const MENU_SIZE: usize = 2 * ROM_BANK_SIZE;
+
+/// MMM01 multicart mapper. It starts unmapped with the menu in the last 32KB of the ROM visible.
+/// The menu selects the outer banks of a game and then locks the configuration
+pub struct Mmm01 {
+    cartridge_base: CartridgeBase,
+    mapped: bool,
+    //Bits 0-4 of the rom bank
+    rom_bank_low: u8,
+    //Bits 5-6 of the rom bank. Only writable while unmapped
+    rom_bank_mid: u8,
+    //Bits 7-8 of the rom bank. Only writable while unmapped
+    rom_bank_high: u8,
+    //Bits of rom_bank_low which are fixed by the menu
+    rom_bank_mask: u8,
+    ram_bank_low: u8,
+    ram_bank_high: u8,
+}
+
+impl Mmm01 {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let has_ram = header.cartridge_type == 0x0C || header.cartridge_type == 0x0D;
+        let has_battery = header.cartridge_type == 0x0D;
+        let ram_size = header.ram_size;
+
+        let cartridge_base =
+            CartridgeBase::new(rom, header, has_ram, ram_size, has_battery, ram_dumper);
+
+        Mmm01 {
+            cartridge_base,
+            mapped: false,
+            rom_bank_low: 1,
+            rom_bank_mid: 0,
+            rom_bank_high: 0,
+            rom_bank_mask: 0,
+            ram_bank_low: 0,
+            ram_bank_high: 0,
+        }
+    }
+
+    fn get_outer_bank(&self) -> usize {
+        (self.rom_bank_high as usize) << 7 | (self.rom_bank_mid as usize) << 5
+    }
+
+    fn update_ram_bank(&mut self) {
+        self.cartridge_base.ram_bank = self.ram_bank_high << 2 | self.ram_bank_low;
+    }
+}
+
+impl Cartridge for Mmm01 {
+    fn read(&self, address: u16) -> u8 {
+        if !self.mapped {
+            let rom_size = self.cartridge_base.rom.len();
+            let menu_offset = rom_size.saturating_sub(MENU_SIZE);
+            return self.cartridge_base.rom[(menu_offset + address as usize) % rom_size];
+        }
+
+        match address {
+            //Bank 0 of the selected game
+            0x0..=0x3FFF => {
+                let bank =
+                    self.get_outer_bank() | (self.rom_bank_low & self.rom_bank_mask) as usize;
+                self.cartridge_base.read_rom(bank, address)
+            }
+            _ => {
+                let bank = self.get_outer_bank() | self.rom_bank_low as usize;
+                self.cartridge_base.read_rom(bank, address)
+            }
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0..=0x1FFF => {
+                self.cartridge_base.set_ram_enabled(value & 0x0F == 0x0A);
+
+                //Bit 6 locks the configuration and maps the selected game
+                if !self.mapped && value & 0x40 == 0x40 {
+                    self.mapped = true;
+                }
+            }
+            0x2000..=0x3FFF => {
+                let bank_number = if value & 0x1F == 0 { 1 } else { value & 0x1F };
+                self.rom_bank_low =
+                    self.rom_bank_low & self.rom_bank_mask | bank_number & !self.rom_bank_mask;
+
+                if !self.mapped {
+                    self.rom_bank_mid = value >> 5 & 0x03;
+                }
+            }
+            0x4000..=0x5FFF => {
+                self.ram_bank_low = value & 0x03;
+
+                if !self.mapped {
+                    self.ram_bank_high = value >> 2 & 0x03;
+                    self.rom_bank_high = value >> 4 & 0x03;
+                }
+
+                self.update_ram_bank();
+            }
+            //Masks bits 1-4 of the low rom bank
+            0x6000..=0x7FFF if !self.mapped => {
+                self.rom_bank_mask = (value >> 1) & 0x1E;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        self.cartridge_base.write_ram(address, value);
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        self.cartridge_base.read_ram(address)
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
+    }
+
+    fn load_savegame(&mut self) {
+        self.cartridge_base.load_savegame();
+    }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_bool(self.mapped);
+        state.write_u8(self.rom_bank_low);
+        state.write_u8(self.rom_bank_mid);
+        state.write_u8(self.rom_bank_high);
+        state.write_u8(self.rom_bank_mask);
+        state.write_u8(self.ram_bank_low);
+        state.write_u8(self.ram_bank_high);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.mapped = state.read_bool()?;
+        self.rom_bank_low = state.read_u8()?;
+        self.rom_bank_mid = state.read_u8()?;
+        self.rom_bank_high = state.read_u8()?;
+        self.rom_bank_mask = state.read_u8()?;
+        self.ram_bank_low = state.read_u8()?;
+        self.ram_bank_high = state.read_u8()?;
+        Ok(())
+    }
+}
+
+/// MMM01 cartridges have the header of the menu at the start of the last 32KB
+pub fn find_menu_header(rom: &[u8]) -> Option<CartridgeHeader> {
+    if rom.len() <= MENU_SIZE {
+        return None;
+    }
+
+    match CartridgeHeader::parse_at(rom, rom.len() - MENU_SIZE) {
+        Ok(header) if (0x0B..=0x0D).contains(&header.cartridge_type) => Some(header),
+        _ => None,
+    }
+}
@@ -1,53 +1,142 @@
+use crate::cartridge::accelerometer::Accelerometer;
+use crate::cartridge::camera::{ImageSource, PocketCamera};
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::huc1::HuC1;
+use crate::cartridge::huc3::{HuC3, ToneGenerator};
+use crate::cartridge::infrared::InfraredPort;
 use crate::cartridge::mbc1::Mbc1;
 use crate::cartridge::mbc2::Mbc2;
+use crate::cartridge::mbc7::Mbc7;
+use crate::cartridge::mmm01::Mmm01;
 use crate::cartridge::rom_only::RomOnlyCartridge;
+use crate::cartridge::sachen::Sachen;
+use crate::cartridge::savegame_format::SavegameFormat;
+use crate::cartridge::wisdom_tree::WisdomTree;
+use crate::state::{StateReader, StateWriter};
 
+pub mod accelerometer;
+pub mod camera;
 pub mod cartridge_base;
+pub mod header;
+pub mod huc1;
+pub mod huc3;
+pub mod infrared;
 pub mod mbc1;
 pub mod mbc2;
+pub mod mbc7;
+pub mod mmm01;
 pub mod rom_only;
+pub mod sachen;
+pub mod savegame_format;
+pub mod wisdom_tree;
 
 pub const EXT_RAM_SIZE: usize = 8192;
 pub const EXT_RAM_ADDRESS: usize = 0xA000;
-const CARTRIDGE_TYPE_ADDRESS: usize = 0x147;
-const RAM_SIZE_ADDRESS: usize = 0x149;
 
 pub trait Cartridge {
     fn read(&self, address: u16) -> u8;
     fn write(&mut self, address: u16, value: u8);
     fn write_ram(&mut self, address: u16, value: u8);
     fn read_ram(&self, address: u16) -> u8;
-    fn dump_savegame(&self);
+    /// Dumps the battery backed ram if it has been changed since the last dump
+    fn dump_savegame(&mut self) -> Result<(), String>;
     fn load_savegame(&mut self);
+    fn header(&self) -> &CartridgeHeader;
+    /// Result of loading the savegame, used to warn about savegames of the wrong size
+    fn savegame_status(&self) -> SavegameStatus;
+    /// The game disabled the ram after changing it, so it should be dumped soon
+    fn flush_pending(&self) -> bool;
+    /// Writes the bank registers and the ram to a save state
+    fn save_state(&self, state: &mut StateWriter);
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String>;
+
+    /// Connects the infrared port of cartridges that have one
+    fn set_infrared_port(&mut self, _infrared_port: Box<dyn InfraredPort + Send>) {}
+
+    /// Connects the speaker of cartridges that have one
+    fn set_tone_generator(&mut self, _tone_generator: Box<dyn ToneGenerator + Send>) {}
+
+    /// Connects the tilt input of cartridges with an accelerometer
+    fn set_accelerometer(&mut self, _accelerometer: Box<dyn Accelerometer + Send>) {}
+
+    /// Connects the image sensor of camera cartridges
+    fn set_image_source(&mut self, _image_source: Box<dyn ImageSource + Send>) {}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SavegameStatus {
+    /// There is no savegame or the cartridge has no battery
+    NotFound,
+    Loaded,
+    /// The savegame did not match the ram size and has been padded or truncated
+    Resized {
+        expected: usize,
+        actual: usize,
+    },
 }
 
 pub trait RamDumper {
-    fn dump(&self, data: &Vec<u8>);
+    fn dump(&self, data: &Vec<u8>) -> Result<(), String>;
     fn load(&self) -> Option<Vec<u8>>;
+
+    /// Converts the savegame to the format of another emulator or flash cart
+    fn export(&self, format: SavegameFormat) -> Result<Vec<u8>, String> {
+        match self.load() {
+            Some(savegame) => Ok(savegame_format::export_savegame(&savegame, format)),
+            None => Err("There is no savegame to export".to_string()),
+        }
+    }
+
+    /// Replaces the savegame. The clock of the current savegame is kept if the
+    /// imported one does not have one
+    fn import(&self, data: &[u8], format: SavegameFormat) -> Result<(), String> {
+        let mut savegame = savegame_format::import_savegame(data, format)?;
+
+        if let Some(current_savegame) = self.load() {
+            let (_, imported_trailer) = savegame_format::split_trailer(&savegame);
+            let (_, current_trailer) = savegame_format::split_trailer(&current_savegame);
+
+            if imported_trailer.is_empty() {
+                savegame.extend_from_slice(current_trailer);
+            }
+        }
+
+        self.dump(&savegame)
+    }
 }
 
 pub fn new_cartridge(
     rom: Vec<u8>,
     ram_dumper: Option<Box<dyn RamDumper + Send>>,
 ) -> Result<Box<dyn Cartridge + Send>, String> {
-    let cartridge_type = rom[CARTRIDGE_TYPE_ADDRESS];
-    match cartridge_type {
-        0x00 | 0x08..=0x09 => Ok(Box::new(RomOnlyCartridge::new(rom, ram_dumper))),
-        0x01..=0x03 => Ok(Box::new(Mbc1::new(rom, ram_dumper))),
-        0x05..=0x06 => Ok(Box::new(Mbc2::new(rom, ram_dumper))),
-        _ => Err(format!("Unknown cartridge type: 0x{:X}", cartridge_type)),
+    //The header at the start of an MMM01 ROM belongs to one of the games
+    if let Some(header) = mmm01::find_menu_header(&rom) {
+        return Ok(Box::new(Mmm01::new(rom, header, ram_dumper)));
+    }
+
+    if let Some(header) = sachen::find_scrambled_header(&rom) {
+        return Ok(Box::new(Sachen::new(rom, header, ram_dumper)));
+    }
+
+    let header = CartridgeHeader::parse(&rom)?;
+
+    //Wisdom Tree does not have its own cartridge type
+    if wisdom_tree::is_wisdom_tree(&rom, &header) {
+        return Ok(Box::new(WisdomTree::new(rom, header, ram_dumper)));
     }
-}
 
-pub fn get_ram_size(rom: &Vec<u8>) -> Option<usize> {
-    match rom[RAM_SIZE_ADDRESS] {
-        0x00 => None,
-        0x01 => Some(2 * 1024),
-        0x02 => Some(8 * 1024),
-        0x03 => Some(32 * 1024),
-        0x04 => Some(128 * 1024),
-        0x05 => Some(64 * 1024),
-        _ => None,
+    match header.cartridge_type {
+        0x00 | 0x08..=0x09 => Ok(Box::new(RomOnlyCartridge::new(rom, header, ram_dumper))),
+        0x01..=0x03 => Ok(Box::new(Mbc1::new(rom, header, ram_dumper))),
+        0x05..=0x06 => Ok(Box::new(Mbc2::new(rom, header, ram_dumper))),
+        0x22 => Ok(Box::new(Mbc7::new(rom, header, ram_dumper))),
+        0xFC => Ok(Box::new(PocketCamera::new(rom, header, ram_dumper))),
+        0xFE => Ok(Box::new(HuC3::new(rom, header, ram_dumper))),
+        0xFF => Ok(Box::new(HuC1::new(rom, header, ram_dumper))),
+        _ => Err(format!(
+            "Unknown cartridge type: 0x{:X}",
+            header.cartridge_type
+        )),
     }
 }
 
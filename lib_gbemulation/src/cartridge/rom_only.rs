@@ -1,18 +1,25 @@
 use crate::cartridge::cartridge_base::CartridgeBase;
-use crate::cartridge::{get_ram_size, Cartridge, RamDumper, CARTRIDGE_TYPE_ADDRESS};
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
 
 pub struct RomOnlyCartridge {
     cartridge_base: CartridgeBase,
 }
 
 impl RomOnlyCartridge {
-    pub fn new(rom: Vec<u8>, ram_dumper: Option<Box<dyn RamDumper + Send>>) -> Self {
-        let cartridge_type = rom[CARTRIDGE_TYPE_ADDRESS];
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let cartridge_type = header.cartridge_type;
         let has_ram = cartridge_type == 0x08 || cartridge_type == 0x09;
         let has_battery = cartridge_type == 0x09;
-        let ram_size = get_ram_size(&rom);
+        let ram_size = header.ram_size;
 
-        let cartridge_base = CartridgeBase::new(rom, has_ram, ram_size, has_battery, ram_dumper);
+        let cartridge_base =
+            CartridgeBase::new(rom, header, has_ram, ram_size, has_battery, ram_dumper);
 
         RomOnlyCartridge { cartridge_base }
     }
@@ -36,11 +43,31 @@ impl Cartridge for RomOnlyCartridge {
         self.cartridge_base.read_ram(address)
     }
 
-    fn dump_savegame(&self) {
-        self.cartridge_base.dump_savegame();
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        self.cartridge_base.dump_savegame()
     }
 
     fn load_savegame(&mut self) {
         self.cartridge_base.load_savegame()
     }
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)
+    }
 }
@@ -0,0 +1,129 @@
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header;
+use crate::cartridge::header::{CartridgeHeader, HEADER_END_ADDRESS};
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+
+/// Unlicensed Sachen MMC1 mapper. The base bank and mask select the game of a multicart.
+/// Both can only be changed while the upper bits of the rom bank are set.
+/// The scrambled logo is only visible to a boot ROM, so the mapper starts unlocked
+pub struct Sachen {
+    cartridge_base: CartridgeBase,
+    base_bank: u8,
+    bank_mask: u8,
+    rom_bank: u8,
+}
+
+impl Sachen {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let cartridge_base = CartridgeBase::new(rom, header, false, None, false, ram_dumper);
+
+        Sachen {
+            cartridge_base,
+            base_bank: 0,
+            bank_mask: 0,
+            rom_bank: 1,
+        }
+    }
+
+    fn is_outer_bank_writable(&self) -> bool {
+        self.rom_bank & 0x30 == 0x30
+    }
+
+    fn get_bank(&self, bank: u8) -> usize {
+        (self.base_bank & self.bank_mask | bank & !self.bank_mask) as usize
+    }
+}
+
+impl Cartridge for Sachen {
+    fn read(&self, address: u16) -> u8 {
+        match address {
+            0x0..=0x3FFF => self.cartridge_base.read_rom(self.get_bank(0), address),
+            _ => self
+                .cartridge_base
+                .read_rom(self.get_bank(self.rom_bank), address),
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0..=0x1FFF if self.is_outer_bank_writable() => {
+                self.base_bank = value;
+            }
+            0x2000..=0x3FFF => {
+                self.rom_bank = if value == 0 { 1 } else { value };
+            }
+            0x4000..=0x5FFF if self.is_outer_bank_writable() => {
+                self.bank_mask = value;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn read_ram(&self, _address: u16) -> u8 {
+        0xFF
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_savegame(&mut self) {}
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_u8(self.base_bank);
+        state.write_u8(self.bank_mask);
+        state.write_u8(self.rom_bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.base_bank = state.read_u8()?;
+        self.bank_mask = state.read_u8()?;
+        self.rom_bank = state.read_u8()?;
+        Ok(())
+    }
+}
+
+/// Sachen cartridges store the header with swapped address lines (A0 with A6, A1 with A4).
+/// Returns the unscrambled header if the logo can be found that way
+pub fn find_scrambled_header(rom: &[u8]) -> Option<CartridgeHeader> {
+    if rom.len() < HEADER_END_ADDRESS || header::contains_logo(rom, 0) {
+        return None;
+    }
+
+    //The swapped lines are all below the header end so it stays in place
+    let unscrambled: Vec<u8> = (0..HEADER_END_ADDRESS)
+        .map(|address| rom[scramble_address(address)])
+        .chain(rom[HEADER_END_ADDRESS..].iter().copied())
+        .collect();
+
+    CartridgeHeader::parse(&unscrambled)
+        .ok()
+        .filter(|header| header.logo_valid)
+}
+
+fn scramble_address(address: usize) -> usize {
+    let bit = |index: usize| (address >> index) & 1;
+
+    address & !0x53 | bit(6) | bit(4) << 1 | bit(1) << 4 | bit(0) << 6
+}
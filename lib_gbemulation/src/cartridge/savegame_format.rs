@@ -0,0 +1,301 @@
+//Data stored behind the ram: the clock of a HuC3 cartridge and the 44 and 48 byte
+//MBC3 clock formats used by other emulators
+pub const SAVEGAME_TRAILER_SIZES: [usize; 3] = [12, 44, 48];
+
+//Seconds, minutes, hours and days of the clock and the latched registers as 32 bit values,
+//followed by a 32 or 64 bit timestamp
+const RTC_REGISTERS_SIZE: usize = 40;
+const RTC_SHORT_TRAILER_SIZE: usize = 44;
+const RTC_TRAILER_SIZE: usize = 48;
+
+//Every cartridge ram is a multiple of this size while the trailers are shorter
+const RAM_SIZE_ALIGNMENT: usize = 256;
+
+const HEX_BYTES_PER_LINE: usize = 16;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SavegameFormat {
+    /// Ram followed by the clock, the layout used by this emulator, BGB, VBA-M, mGBA
+    /// and most flash carts. MBC3 clocks use the 48 byte layout
+    Sav,
+    /// Like Sav but with the 44 byte MBC3 clock of older VisualBoyAdvance versions
+    VbaSav,
+    /// Only the ram, as used by RetroArch which stores the clock in a separate file
+    Srm,
+    /// Text with the offset and 16 bytes per line
+    Hex,
+}
+
+impl SavegameFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "sav" => Some(SavegameFormat::Sav),
+            "vba" => Some(SavegameFormat::VbaSav),
+            "srm" => Some(SavegameFormat::Srm),
+            "hex" | "txt" => Some(SavegameFormat::Hex),
+            _ => None,
+        }
+    }
+
+    /// Formats which can be told apart by the extension. Both Sav formats are imported the same way
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match Self::from_name(extension) {
+            Some(SavegameFormat::VbaSav) => None,
+            format => format,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SavegameFormat::Sav | SavegameFormat::VbaSav => "sav",
+            SavegameFormat::Srm => "srm",
+            SavegameFormat::Hex => "txt",
+        }
+    }
+}
+
+/// Converts a savegame of this emulator to the given format
+pub fn export_savegame(savegame: &[u8], format: SavegameFormat) -> Vec<u8> {
+    let (ram, trailer) = split_trailer(savegame);
+
+    match format {
+        SavegameFormat::Sav => join_trailer(ram, &convert_rtc_trailer(trailer, RTC_TRAILER_SIZE)),
+        SavegameFormat::VbaSav => {
+            join_trailer(ram, &convert_rtc_trailer(trailer, RTC_SHORT_TRAILER_SIZE))
+        }
+        SavegameFormat::Srm => ram.to_vec(),
+        SavegameFormat::Hex => to_hex(savegame).into_bytes(),
+    }
+}
+
+/// Converts a savegame of the given format to the layout used by this emulator
+pub fn import_savegame(data: &[u8], format: SavegameFormat) -> Result<Vec<u8>, String> {
+    let savegame = match format {
+        SavegameFormat::Hex => match std::str::from_utf8(data) {
+            Ok(text) => from_hex(text)?,
+            Err(_) => return Err("Hex dump is not a text file".to_string()),
+        },
+        _ => data.to_vec(),
+    };
+
+    let (ram, trailer) = split_trailer(&savegame);
+
+    if ram.is_empty() {
+        return Err("Savegame does not contain any ram".to_string());
+    }
+
+    if format == SavegameFormat::Srm {
+        return Ok(ram.to_vec());
+    }
+
+    Ok(join_trailer(
+        ram,
+        &convert_rtc_trailer(trailer, RTC_TRAILER_SIZE),
+    ))
+}
+
+/// Splits a savegame into the ram and the trailer behind it
+pub fn split_trailer(savegame: &[u8]) -> (&[u8], &[u8]) {
+    let trailer_size = savegame.len() % RAM_SIZE_ALIGNMENT;
+
+    if SAVEGAME_TRAILER_SIZES.contains(&trailer_size) {
+        savegame.split_at(savegame.len() - trailer_size)
+    } else {
+        (savegame, &[])
+    }
+}
+
+fn join_trailer(ram: &[u8], trailer: &[u8]) -> Vec<u8> {
+    let mut savegame = ram.to_vec();
+    savegame.extend_from_slice(trailer);
+    savegame
+}
+
+//Changes the size of the timestamp of an MBC3 clock. Other trailers are kept as they are
+fn convert_rtc_trailer(trailer: &[u8], size: usize) -> Vec<u8> {
+    if trailer.len() != RTC_SHORT_TRAILER_SIZE && trailer.len() != RTC_TRAILER_SIZE {
+        return trailer.to_vec();
+    }
+
+    let mut timestamp = [0; 8];
+    let timestamp_size = trailer.len() - RTC_REGISTERS_SIZE;
+    timestamp[..timestamp_size].copy_from_slice(&trailer[RTC_REGISTERS_SIZE..]);
+
+    let mut converted = trailer[..RTC_REGISTERS_SIZE].to_vec();
+    converted.extend_from_slice(&timestamp[..size - RTC_REGISTERS_SIZE]);
+    converted
+}
+
+fn to_hex(data: &[u8]) -> String {
+    let mut hex = String::with_capacity(data.len() * 4);
+
+    for (line, bytes) in data.chunks(HEX_BYTES_PER_LINE).enumerate() {
+        hex.push_str(&format!("{:06X}:", line * HEX_BYTES_PER_LINE));
+
+        for byte in bytes {
+            hex.push_str(&format!(" {:02X}", byte));
+        }
+
+        hex.push('\n');
+    }
+
+    hex
+}
+
+//Offsets in front of a colon are ignored, so dumps with or without them can be imported
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+
+    for (line_number, line) in text.lines().enumerate() {
+        let bytes = match line.split_once(':') {
+            Some((_, bytes)) => bytes,
+            None => line,
+        };
+
+        let digits: Vec<char> = bytes.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if digits.len() % 2 == 1 {
+            return Err(format!(
+                "Odd number of hex digits in line {}",
+                line_number + 1
+            ));
+        }
+
+        for pair in digits.chunks(2) {
+            let byte: String = pair.iter().collect();
+
+            match u8::from_str_radix(&byte, 16) {
+                Ok(byte) => data.push(byte),
+                Err(_) => {
+                    return Err(format!(
+                        "Invalid hex value {} in line {}",
+                        byte,
+                        line_number + 1
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAM_SIZE: usize = 8192;
+    const FORMATS: [SavegameFormat; 4] = [
+        SavegameFormat::Sav,
+        SavegameFormat::VbaSav,
+        SavegameFormat::Srm,
+        SavegameFormat::Hex,
+    ];
+
+    //Ram followed by a trailer. The timestamp of a clock fits into 32 bits so it survives
+    //the 44 byte layout
+    fn savegame(trailer_size: usize) -> Vec<u8> {
+        let mut savegame: Vec<u8> = (0..RAM_SIZE).map(|index| index as u8).collect();
+        let mut trailer: Vec<u8> = (0..trailer_size).map(|index| 0x80 | index as u8).collect();
+
+        if trailer_size >= RTC_SHORT_TRAILER_SIZE {
+            for value in trailer[RTC_SHORT_TRAILER_SIZE..].iter_mut() {
+                *value = 0;
+            }
+        }
+
+        savegame.extend(trailer);
+        savegame
+    }
+
+    fn round_trip(savegame: &[u8], format: SavegameFormat) -> Vec<u8> {
+        export_savegame(&import_savegame(savegame, format).unwrap(), format)
+    }
+
+    #[test]
+    fn round_trips_sav() {
+        for trailer_size in [0, 12, 48] {
+            let savegame = savegame(trailer_size);
+            assert_eq!(round_trip(&savegame, SavegameFormat::Sav), savegame);
+        }
+    }
+
+    #[test]
+    fn round_trips_vba_sav() {
+        for trailer_size in [0, 12, 44] {
+            let savegame = savegame(trailer_size);
+            assert_eq!(round_trip(&savegame, SavegameFormat::VbaSav), savegame);
+        }
+    }
+
+    #[test]
+    fn round_trips_srm() {
+        let savegame = savegame(0);
+        assert_eq!(round_trip(&savegame, SavegameFormat::Srm), savegame);
+    }
+
+    #[test]
+    fn round_trips_hex() {
+        for trailer_size in [0, 12, 48] {
+            let hex = to_hex(&savegame(trailer_size)).into_bytes();
+            assert_eq!(round_trip(&hex, SavegameFormat::Hex), hex);
+        }
+    }
+
+    #[test]
+    fn exports_every_format_back_to_the_same_savegame() {
+        for trailer_size in [0, 12, 48] {
+            let savegame = savegame(trailer_size);
+
+            for format in FORMATS {
+                let exported = export_savegame(&savegame, format);
+                let imported = import_savegame(&exported, format).unwrap();
+
+                if format == SavegameFormat::Srm {
+                    assert_eq!(imported, &savegame[..RAM_SIZE]);
+                } else {
+                    assert_eq!(imported, savegame);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn converts_clock_sizes() {
+        let short = savegame(RTC_SHORT_TRAILER_SIZE);
+        let imported = import_savegame(&short, SavegameFormat::Sav).unwrap();
+
+        assert_eq!(imported.len(), RAM_SIZE + RTC_TRAILER_SIZE);
+        assert_eq!(&imported[..short.len()], &short[..]);
+        assert!(imported[short.len()..].iter().all(|value| *value == 0));
+
+        let exported = export_savegame(&imported, SavegameFormat::VbaSav);
+        assert_eq!(exported, short);
+    }
+
+    #[test]
+    fn splits_known_trailers_only() {
+        for trailer_size in SAVEGAME_TRAILER_SIZES {
+            let savegame = savegame(trailer_size);
+            let (ram, trailer) = split_trailer(&savegame);
+
+            assert_eq!(ram.len(), RAM_SIZE);
+            assert_eq!(trailer.len(), trailer_size);
+        }
+
+        let savegame = savegame(20);
+        assert_eq!(split_trailer(&savegame).1.len(), 0);
+    }
+
+    #[test]
+    fn imports_hex_without_offsets() {
+        let imported = import_savegame(b"01 02\n0A0B\n", SavegameFormat::Hex).unwrap();
+        assert_eq!(imported, vec![0x01, 0x02, 0x0A, 0x0B]);
+    }
+
+    #[test]
+    fn rejects_invalid_hex() {
+        assert!(import_savegame(b"000000: 01 0", SavegameFormat::Hex).is_err());
+        assert!(import_savegame(b"000000: 01 XY", SavegameFormat::Hex).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::cartridge::cartridge_base::CartridgeBase;
+use crate::cartridge::header::{CartridgeHeader, ROM_BANK_SIZE};
+use crate::cartridge::{Cartridge, RamDumper, SavegameStatus};
+use crate::state::{StateReader, StateWriter};
+
+const ROM_ONLY_SIZE: usize = 2 * ROM_BANK_SIZE;
+const SIGNATURE_SIZE: usize = 11;
+
+/// Unlicensed mapper used by Wisdom Tree. The lower address bits of a write to 0x0000-0x3FFF
+/// select a 32KB bank which is mapped to the whole ROM area
+pub struct WisdomTree {
+    cartridge_base: CartridgeBase,
+    bank: u8,
+}
+
+impl WisdomTree {
+    pub fn new(
+        rom: Vec<u8>,
+        header: CartridgeHeader,
+        ram_dumper: Option<Box<dyn RamDumper + Send>>,
+    ) -> Self {
+        let cartridge_base = CartridgeBase::new(rom, header, false, None, false, ram_dumper);
+
+        WisdomTree {
+            cartridge_base,
+            bank: 0,
+        }
+    }
+}
+
+impl Cartridge for WisdomTree {
+    fn read(&self, address: u16) -> u8 {
+        let rom = &self.cartridge_base.rom;
+        let offset = self.bank as usize * ROM_ONLY_SIZE;
+        rom[(offset + address as usize) % rom.len()]
+    }
+
+    fn write(&mut self, address: u16, _value: u8) {
+        if let 0x0..=0x3FFF = address {
+            self.bank = address as u8;
+        }
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn read_ram(&self, _address: u16) -> u8 {
+        0xFF
+    }
+
+    fn dump_savegame(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_savegame(&mut self) {}
+
+    fn header(&self) -> &CartridgeHeader {
+        &self.cartridge_base.header
+    }
+
+    fn savegame_status(&self) -> SavegameStatus {
+        self.cartridge_base.savegame_status()
+    }
+
+    fn flush_pending(&self) -> bool {
+        self.cartridge_base.flush_pending()
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        self.cartridge_base.save_state(state);
+        state.write_u8(self.bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cartridge_base.load_state(state)?;
+        self.bank = state.read_u8()?;
+        Ok(())
+    }
+}
+
+/// Wisdom Tree cartridges claim to be ROM only but are larger than 32KB. Overdumped ROM only
+/// images are larger as well, so the publisher name has to be found in the ROM too
+pub fn is_wisdom_tree(rom: &[u8], header: &CartridgeHeader) -> bool {
+    header.cartridge_type == 0x00 && rom.len() > ROM_ONLY_SIZE && contains_signature(rom)
+}
+
+//"WISDOM TREE" or "WISDOM\0TREE", the separator differs between the games
+fn contains_signature(rom: &[u8]) -> bool {
+    rom.windows(SIGNATURE_SIZE)
+        .any(|window| window.starts_with(b"WISDOM") && window.ends_with(b"TREE"))
+}
@@ -7,9 +7,8 @@ pub struct Clock {
 }
 
 impl Clock {
-    pub fn new(cpu_clock_hz: usize, fps: f32) -> Clock {
-        let clock_cycles_per_frame: usize = (cpu_clock_hz as f32 / fps) as usize;
-        let frame_time: f32 = 1.0 / fps;
+    pub fn new(cpu_clock_hz: usize, clock_cycles_per_frame: usize) -> Clock {
+        let frame_time: f32 = clock_cycles_per_frame as f32 / cpu_clock_hz as f32;
 
         Clock {
             cpu_clock_hz: cpu_clock_hz,
@@ -1,9 +1,9 @@
-use crate::cpu::instructions;
-use crate::cpu::instructions::{ExecutionType, Instruction};
-use crate::cpu::interrupt_handler::handle_interrupts;
+use crate::cpu::instructions::{ExecutionType, Instruction, CB_INSTRUCTIONS, INSTRUCTIONS};
+use crate::cpu::interrupt_handler::{handle_interrupts, INTERRUPT_CLOCK_CYCLES};
 use crate::cpu::registers::Registers;
 use crate::memory::interrupts::Interrupt;
-use crate::memory::mmu::{Mmu, Opcode};
+use crate::memory::mmu::{Mmu, Opcode, CLOCK_CYCLES_PER_MACHINE_CYCLE};
+use crate::state::{StateReader, StateWriter};
 
 pub enum InterruptAction {
     None,
@@ -30,44 +30,40 @@ impl Cpu {
         }
     }
 
+    /// Starts at address 0 with cleared registers, which are set up by the boot ROM
+    pub fn new_with_boot_rom() -> Cpu {
+        Cpu {
+            registers: Registers::new_cleared(),
+            ..Cpu::new()
+        }
+    }
+
+    /// Executes the next instruction. Every memory access ticks the rest of the system,
+    /// so the returned amount of clock cycles has already been emulated when this returns
     pub fn step(&mut self, mmu: &mut Mmu) -> u8 {
-        let op_code = mmu.read_opcode(self.registers.pc);
-
-        let instruction = match instructions::get_instruction_by_op_code(&op_code) {
-            Some(instruction) => instruction,
-            None => {
-                match op_code {
-                    Opcode::CB(value) => eprintln!(
-                        "Unimplemented CB Opcode! 0x{:X} PC: 0x{:X}",
-                        value, self.registers.pc
-                    ),
-                    Opcode::Regular(value) => eprintln!(
-                        "Unimplemented Opcode! 0x{:X} PC: 0x{:X}",
-                        value, self.registers.pc
-                    ),
-                };
-
-                std::process::exit(1);
-            }
-        };
+        let start_cycles = mmu.clock_cycles();
 
-        if self.is_halted && any_interrupt_fired(mmu) {
-            self.is_halted = false;
-            if !self.interrupt_master_enabled {
-                //HALT Bug
-                self.registers.pc -= 1;
-            }
+        //STOP halts the CPU until a button is pressed
+        if mmu.is_stopped() {
+            mmu.tick();
+            return CLOCK_CYCLES_PER_MACHINE_CYCLE;
         }
 
+        let mut halt_bug = false;
+
         if self.is_halted {
-            return 4;
+            if !any_interrupt_fired(mmu) {
+                mmu.tick();
+                return CLOCK_CYCLES_PER_MACHINE_CYCLE;
+            }
+
+            self.is_halted = false;
+            halt_bug = !self.interrupt_master_enabled;
         }
 
-        if self.interrupt_master_enabled {
-            match handle_interrupts(self, mmu) {
-                Some(cycles) => return cycles,
-                None => {}
-            }
+        if self.interrupt_master_enabled && handle_interrupts(self, mmu) {
+            finish_cycles(mmu, start_cycles, INTERRUPT_CLOCK_CYCLES);
+            return INTERRUPT_CLOCK_CYCLES;
         }
 
         match self.interrupt_action {
@@ -82,16 +78,46 @@ impl Cpu {
             _ => {}
         }
 
-        self.execute_instruction(instruction, mmu, &op_code)
+        let (instruction, op_code) = match mmu.read_opcode(self.registers.pc) {
+            Opcode::Regular(op_code) => (&INSTRUCTIONS[op_code as usize], op_code),
+            Opcode::CB(op_code) => (&CB_INSTRUCTIONS[op_code as usize], op_code),
+        };
+
+        if halt_bug {
+            //HALT Bug: The byte after HALT is read twice
+            self.registers.pc -= 1;
+        }
+
+        let clock_cycles = self.execute_instruction(instruction, mmu, op_code);
+        finish_cycles(mmu, start_cycles, clock_cycles);
+        clock_cycles
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        self.registers.save_state(state);
+        state.write_u8(match self.interrupt_action {
+            InterruptAction::None => 0,
+            InterruptAction::Enable => 1,
+            InterruptAction::Disable => 2,
+        });
+        state.write_bool(self.interrupt_master_enabled);
+        state.write_bool(self.is_halted);
     }
 
-    fn execute_instruction(
-        &mut self,
-        instruction: &Instruction,
-        mmu: &mut Mmu,
-        op_code: &Opcode,
-    ) -> u8 {
-        let result = (instruction.handler)(self, mmu, &op_code);
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.registers.load_state(state)?;
+        self.interrupt_action = match state.read_u8()? {
+            1 => InterruptAction::Enable,
+            2 => InterruptAction::Disable,
+            _ => InterruptAction::None,
+        };
+        self.interrupt_master_enabled = state.read_bool()?;
+        self.is_halted = state.read_bool()?;
+        Ok(())
+    }
+
+    fn execute_instruction(&mut self, instruction: &Instruction, mmu: &mut Mmu, op_code: u8) -> u8 {
+        let result = (instruction.handler)(self, mmu, op_code);
 
         //Use the correct value if action of conditional instruction is taken or not
         match result {
@@ -109,6 +135,13 @@ impl Cpu {
     }
 }
 
+/// Ticks the machine cycles of an instruction that did not access memory (internal cycles)
+fn finish_cycles(mmu: &mut Mmu, start_cycles: u64, clock_cycles: u8) {
+    while mmu.clock_cycles() - start_cycles < clock_cycles as u64 {
+        mmu.tick();
+    }
+}
+
 pub fn any_interrupt_fired(mmu: &Mmu) -> bool {
     mmu.interrupts.interrupt_fired(&Interrupt::Vblank)
         || mmu.interrupts.interrupt_fired(&Interrupt::LcdStat)
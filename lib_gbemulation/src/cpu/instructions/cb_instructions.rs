@@ -2,18 +2,18 @@ use crate::cpu::cpu::Cpu;
 use crate::cpu::instructions::{
     functions, read_hl_addr, write_hl_addr, ExecutionType, Instruction,
 };
-use crate::memory::mmu::{Mmu, Opcode};
+use crate::memory::mmu::Mmu;
 use crate::util::binary::{reset_bit_in_byte, set_bit_in_byte};
 
 //TODO: Fix descriptions
 macro_rules! bit {
     ($bit: expr) => {
-        Some(&Instruction {
+        Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "BIT $bit,(B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 functions::check_bit(cpu, read_by_opcode(op_code, cpu), $bit);
                 ExecutionType::None
             },
@@ -23,12 +23,12 @@ macro_rules! bit {
 
 macro_rules! bit_hl {
     ($bit: expr) => {
-        Some(&Instruction {
+        Some(Instruction {
             length: 2,
             clock_cycles: 12,
             clock_cycles_condition: None,
             description: "BIT $bit,(HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 functions::check_bit(cpu, read_hl_addr(cpu, mmu), $bit);
                 ExecutionType::None
             },
@@ -38,12 +38,12 @@ macro_rules! bit_hl {
 
 macro_rules! res {
     ($bit: expr) => {
-        Some(&Instruction {
+        Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "RES $bit,(B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let result = reset_bit_in_byte(read_by_opcode(op_code, cpu), $bit);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
@@ -54,12 +54,12 @@ macro_rules! res {
 
 macro_rules! res_hl {
     ($bit: expr) => {
-        Some(&Instruction {
+        Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "RES $bit,(HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = reset_bit_in_byte(read_hl_addr(cpu, mmu), $bit);
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
@@ -70,12 +70,12 @@ macro_rules! res_hl {
 
 macro_rules! set {
     ($bit: expr) => {
-        Some(&Instruction {
+        Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "SET $bit,(B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let result = set_bit_in_byte(read_by_opcode(op_code, cpu), $bit);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
@@ -86,12 +86,12 @@ macro_rules! set {
 
 macro_rules! set_hl {
     ($bit: expr) => {
-        Some(&Instruction {
+        Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "SET $bit,(HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = set_bit_in_byte(read_hl_addr(cpu, mmu), $bit);
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
@@ -100,189 +100,189 @@ macro_rules! set_hl {
     };
 }
 
-pub fn get_instruction(op_code: &u8) -> Option<&Instruction> {
+pub const fn get_instruction(op_code: u8) -> Option<Instruction> {
     match op_code {
-        0x00..=0x05 | 0x07 => Some(&Instruction {
+        0x00..=0x05 | 0x07 => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "RLC (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::rotate_left(cpu, value, true);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x06 => Some(&Instruction {
+        0x06 => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "RLC (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = functions::rotate_left(cpu, read_hl_addr(cpu, mmu), true);
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x08..=0x0D | 0x0F => Some(&Instruction {
+        0x08..=0x0D | 0x0F => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "RRC (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::rotate_right(cpu, value, true);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x0E => Some(&Instruction {
+        0x0E => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "RRC (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = functions::rotate_right(cpu, read_hl_addr(cpu, mmu), true);
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x10..=0x15 | 0x17 => Some(&Instruction {
+        0x10..=0x15 | 0x17 => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "RL (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::rotate_left_through_carry(cpu, value, true);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x16 => Some(&Instruction {
+        0x16 => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "RL (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result =
                     functions::rotate_left_through_carry(cpu, read_hl_addr(cpu, mmu), true);
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x18..=0x1D | 0x1F => Some(&Instruction {
+        0x18..=0x1D | 0x1F => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "RR (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::rotate_right_through_carry(cpu, value, true);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x1E => Some(&Instruction {
+        0x1E => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "RR (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result =
                     functions::rotate_right_through_carry(cpu, read_hl_addr(cpu, mmu), true);
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x20..=0x25 | 0x27 => Some(&Instruction {
+        0x20..=0x25 | 0x27 => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "SLA (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::sla(cpu, value);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x26 => Some(&Instruction {
+        0x26 => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "SLA (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = functions::sla(cpu, read_hl_addr(cpu, mmu));
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x28..=0x2D | 0x2F => Some(&Instruction {
+        0x28..=0x2D | 0x2F => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "SRA (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::sra(cpu, value);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x2E => Some(&Instruction {
+        0x2E => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "SRA (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = functions::sra(cpu, read_hl_addr(cpu, mmu));
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x30..=0x35 | 0x37 => Some(&Instruction {
+        0x30..=0x35 | 0x37 => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "SWAP (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::swap_nibbles(cpu, value);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x36 => Some(&Instruction {
+        0x36 => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "SWAP (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = functions::swap_nibbles(cpu, read_hl_addr(cpu, mmu));
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
             },
         }),
-        0x38..=0x3D | 0x3F => Some(&Instruction {
+        0x38..=0x3D | 0x3F => Some(Instruction {
             length: 2,
             clock_cycles: 8,
             clock_cycles_condition: None,
             description: "SRL (B..A)",
-            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: &Opcode| {
+            handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
                 let value = read_by_opcode(op_code, cpu);
                 let result = functions::srl(cpu, value);
                 write_by_opcode(op_code, result, cpu);
                 ExecutionType::None
             },
         }),
-        0x3E => Some(&Instruction {
+        0x3E => Some(Instruction {
             length: 2,
             clock_cycles: 16,
             clock_cycles_condition: None,
             description: "SRL (HL)",
-            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: &Opcode| {
+            handler: |cpu: &mut Cpu, mmu: &mut Mmu, _: u8| {
                 let result = functions::srl(cpu, read_hl_addr(cpu, mmu));
                 write_hl_addr(result, cpu, mmu);
                 ExecutionType::None
@@ -339,7 +339,7 @@ pub fn get_instruction(op_code: &u8) -> Option<&Instruction> {
     }
 }
 
-fn read_by_opcode(op_code: &Opcode, cpu: &Cpu) -> u8 {
+fn read_by_opcode(op_code: u8, cpu: &Cpu) -> u8 {
     match get_lower_nibble_of_opcode(op_code) {
         0x00 | 0x80 => cpu.registers.b,
         0x10 | 0x90 => cpu.registers.c,
@@ -352,7 +352,7 @@ fn read_by_opcode(op_code: &Opcode, cpu: &Cpu) -> u8 {
     }
 }
 
-fn write_by_opcode(op_code: &Opcode, value: u8, cpu: &mut Cpu) {
+fn write_by_opcode(op_code: u8, value: u8, cpu: &mut Cpu) {
     match get_lower_nibble_of_opcode(op_code) {
         0x00 | 0x80 => cpu.registers.b = value,
         0x10 | 0x90 => cpu.registers.c = value,
@@ -365,11 +365,6 @@ fn write_by_opcode(op_code: &Opcode, value: u8, cpu: &mut Cpu) {
     }
 }
 
-fn get_lower_nibble_of_opcode(op_code: &Opcode) -> u8 {
-    let op_val = match op_code {
-        Opcode::CB(value) => value,
-        _ => panic!("No CB Opcode"),
-    };
-
-    op_val << 4
+fn get_lower_nibble_of_opcode(op_code: u8) -> u8 {
+    op_code << 4
 }
@@ -162,7 +162,7 @@ pub fn srl(cpu: &mut Cpu, value: u8) -> u8 {
     result
 }
 
-pub fn jump_on_flag_reset(cpu: &mut Cpu, mmu: &Mmu, flag: Flag) -> bool {
+pub fn jump_on_flag_reset(cpu: &mut Cpu, mmu: &mut Mmu, flag: Flag) -> bool {
     if !cpu.registers.check_flag(flag) {
         jump_to_attribute_address(cpu, mmu);
         return true;
@@ -170,7 +170,7 @@ pub fn jump_on_flag_reset(cpu: &mut Cpu, mmu: &Mmu, flag: Flag) -> bool {
     false
 }
 
-pub fn jump_on_flag(cpu: &mut Cpu, mmu: &Mmu, flag: Flag) -> bool {
+pub fn jump_on_flag(cpu: &mut Cpu, mmu: &mut Mmu, flag: Flag) -> bool {
     if cpu.registers.check_flag(flag) {
         jump_to_attribute_address(cpu, mmu);
         return true;
@@ -178,7 +178,7 @@ pub fn jump_on_flag(cpu: &mut Cpu, mmu: &Mmu, flag: Flag) -> bool {
     false
 }
 
-pub fn jump_to_attribute_address(cpu: &mut Cpu, mmu: &Mmu) {
+pub fn jump_to_attribute_address(cpu: &mut Cpu, mmu: &mut Mmu) {
     let destination = get_argument(cpu, mmu, 0);
 
     cpu.registers.pc = cpu.registers.pc.wrapping_add((destination as i8) as u16);
@@ -416,18 +416,30 @@ pub fn and_bytes(cpu: &mut Cpu, byte1: u8, byte2: u8) -> u8 {
 }
 
 pub fn rst(cpu: &mut Cpu, mmu: &mut Mmu, param: u8) {
-    cpu.registers.sp = cpu.registers.sp.wrapping_sub(2);
-    mmu.write_word(cpu.registers.sp, cpu.registers.pc + 1);
+    push(cpu, mmu, cpu.registers.pc + 1);
     cpu.registers.pc = bytes_to_word(0x00, param);
 }
 
 pub fn call(cpu: &mut Cpu, mmu: &mut Mmu) {
     //Put address of next instruction onto stack and jump to aa
-    cpu.registers.sp = cpu.registers.sp.wrapping_sub(2);
-    mmu.write_word(cpu.registers.sp, cpu.registers.pc + 3);
-    cpu.registers.pc = bytes_to_word(get_argument(cpu, mmu, 1), get_argument(cpu, mmu, 0));
+    let address = get_word_argument(cpu, mmu);
+    push(cpu, mmu, cpu.registers.pc + 3);
+    cpu.registers.pc = address;
 }
 
-pub fn get_argument(cpu: &Cpu, mmu: &Mmu, index: u16) -> u8 {
-    mmu.read(cpu.registers.pc.wrapping_add(index + 1))
+/// Internal cycle followed by the writes of the high and the low byte
+pub fn push(cpu: &mut Cpu, mmu: &mut Mmu, value: u16) {
+    mmu.tick();
+    mmu.cycle_push_word(&mut cpu.registers.sp, value);
+}
+
+pub fn get_argument(cpu: &Cpu, mmu: &mut Mmu, index: u16) -> u8 {
+    mmu.cycle_read(cpu.registers.pc.wrapping_add(index + 1))
+}
+
+/// Reads the low byte of the argument first
+pub fn get_word_argument(cpu: &Cpu, mmu: &mut Mmu) -> u16 {
+    let low = get_argument(cpu, mmu, 0);
+    let high = get_argument(cpu, mmu, 1);
+    bytes_to_word(high, low)
 }
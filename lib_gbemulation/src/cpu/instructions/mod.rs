@@ -1,5 +1,5 @@
 use crate::cpu::cpu::Cpu;
-use crate::memory::mmu::{Mmu, Opcode};
+use crate::memory::mmu::Mmu;
 use crate::util::binary::bytes_to_word;
 
 pub mod cb_instructions;
@@ -18,20 +18,48 @@ pub struct Instruction {
     pub clock_cycles: u8,
     pub clock_cycles_condition: Option<u8>,
     pub description: &'static str,
-    pub handler: fn(cpu: &mut Cpu, mmu: &mut Mmu, op_code: &Opcode) -> ExecutionType,
+    pub handler: fn(cpu: &mut Cpu, mmu: &mut Mmu, op_code: u8) -> ExecutionType,
 }
 
-pub fn get_instruction_by_op_code(op_code: &Opcode) -> Option<&Instruction> {
-    match op_code {
-        Opcode::Regular(value) => instructions::get_instruction(&value),
-        Opcode::CB(value) => cb_instructions::get_instruction(&value),
-    }
+/// Placeholder for opcodes the CPU does not know
+const UNIMPLEMENTED_INSTRUCTION: Instruction = Instruction {
+    length: 1,
+    clock_cycles: 4,
+    clock_cycles_condition: None,
+    description: "Unimplemented",
+    handler: |cpu: &mut Cpu, _: &mut Mmu, op_code: u8| {
+        eprintln!(
+            "Unimplemented Opcode! 0x{:X} PC: 0x{:X}",
+            op_code, cpu.registers.pc
+        );
+        std::process::exit(1);
+    },
+};
+
+/// Builds a 256 entry dispatch table at compile time out of a get_instruction function
+macro_rules! dispatch_table {
+    ($get_instruction: path) => {{
+        let mut table = [UNIMPLEMENTED_INSTRUCTION; 256];
+        let mut op_code = 0;
+
+        while op_code < table.len() {
+            if let Some(instruction) = $get_instruction(op_code as u8) {
+                table[op_code] = instruction;
+            }
+            op_code += 1;
+        }
+
+        table
+    }};
 }
 
-fn read_hl_addr(cpu: &Cpu, mmu: &Mmu) -> u8 {
-    mmu.read(bytes_to_word(cpu.registers.h, cpu.registers.l))
+pub static INSTRUCTIONS: [Instruction; 256] = dispatch_table!(instructions::get_instruction);
+pub static CB_INSTRUCTIONS: [Instruction; 256] = dispatch_table!(cb_instructions::get_instruction);
+
+fn read_hl_addr(cpu: &Cpu, mmu: &mut Mmu) -> u8 {
+    mmu.cycle_read(bytes_to_word(cpu.registers.h, cpu.registers.l))
 }
 
 fn write_hl_addr(value: u8, cpu: &mut Cpu, mmu: &mut Mmu) {
-    mmu.write(bytes_to_word(cpu.registers.h, cpu.registers.l), value);
+    mmu.cycle_write(bytes_to_word(cpu.registers.h, cpu.registers.l), value);
 }
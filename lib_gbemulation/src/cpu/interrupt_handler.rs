@@ -2,38 +2,52 @@ use crate::cpu::cpu::Cpu;
 use crate::memory::interrupts::Interrupt;
 use crate::memory::mmu::Mmu;
 
-pub fn handle_interrupts(cpu: &mut Cpu, mmu: &mut Mmu) -> Option<u8> {
-    if handle_interrupt(cpu, mmu, &Interrupt::Vblank, 0x0040) {
-        return Some(12);
-    }
+/// Interrupt dispatch takes 5 machine cycles: 2 internal, 2 for pushing PC and 1 for setting PC
+pub const INTERRUPT_CLOCK_CYCLES: u8 = 20;
 
-    if handle_interrupt(cpu, mmu, &Interrupt::LcdStat, 0x0048) {
-        return Some(12);
-    }
+//Sorted by priority
+const INTERRUPT_VECTORS: [(Interrupt, u16); 5] = [
+    (Interrupt::Vblank, 0x0040),
+    (Interrupt::LcdStat, 0x0048),
+    (Interrupt::Timer, 0x0050),
+    (Interrupt::Serial, 0x0058),
+    (Interrupt::Joypad, 0x0060),
+];
 
-    if handle_interrupt(cpu, mmu, &Interrupt::Timer, 0x0050) {
-        return Some(12);
+pub fn handle_interrupts(cpu: &mut Cpu, mmu: &mut Mmu) -> bool {
+    if pending_interrupt(mmu).is_none() {
+        return false;
     }
 
-    if handle_interrupt(cpu, mmu, &Interrupt::Serial, 0x0058) {
-        return Some(12);
-    }
+    cpu.interrupt_master_enabled = false;
+    mmu.tick();
+    mmu.tick();
 
-    if handle_interrupt(cpu, mmu, &Interrupt::Joypad, 0x0060) {
-        return Some(12);
-    }
+    let pc = cpu.registers.pc;
+    cpu.registers.sp = cpu.registers.sp.wrapping_sub(1);
+    mmu.cycle_write(cpu.registers.sp, (pc >> 8) as u8);
 
-    return None;
-}
+    //The interrupt is chosen after the high byte is pushed. If that push overwrites IE another
+    //interrupt is dispatched or the dispatch is cancelled and jumps to 0x0000
+    let interrupt = pending_interrupt(mmu);
+
+    cpu.registers.sp = cpu.registers.sp.wrapping_sub(1);
+    mmu.cycle_write(cpu.registers.sp, pc as u8);
+
+    cpu.registers.pc = match interrupt {
+        Some((interrupt, isr_address)) => {
+            mmu.interrupts.reset_interrupt(&interrupt);
+            isr_address
+        }
+        None => 0x0000,
+    };
 
-fn handle_interrupt(cpu: &mut Cpu, mmu: &mut Mmu, interrupt: &Interrupt, isr_address: u16) -> bool {
-    if !mmu.interrupts.interrupt_fired(interrupt) {
-        return false;
-    }
-    cpu.interrupt_master_enabled = false;
-    cpu.registers.sp -= 2;
-    mmu.write_word(cpu.registers.sp, cpu.registers.pc);
-    cpu.registers.pc = isr_address;
-    mmu.interrupts.reset_interrupt(interrupt);
     true
 }
+
+fn pending_interrupt(mmu: &Mmu) -> Option<(Interrupt, u16)> {
+    INTERRUPT_VECTORS
+        .iter()
+        .find(|(interrupt, _)| mmu.interrupts.interrupt_fired(interrupt))
+        .copied()
+}
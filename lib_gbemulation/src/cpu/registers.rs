@@ -1,3 +1,5 @@
+use crate::state::{StateReader, StateWriter};
+
 pub enum Flag {
     Z = 0x80,
     N = 0x40,
@@ -35,6 +37,46 @@ impl Registers {
         }
     }
 
+    pub fn new_cleared() -> Registers {
+        Registers {
+            a: 0,
+            b: 0,
+            c: 0,
+            d: 0,
+            e: 0,
+            h: 0,
+            l: 0,
+            f: 0,
+            pc: 0,
+            sp: 0,
+        }
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        for value in [
+            self.a, self.b, self.c, self.d, self.e, self.h, self.l, self.f,
+        ] {
+            state.write_u8(value);
+        }
+
+        state.write_u16(self.pc);
+        state.write_u16(self.sp);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.a = state.read_u8()?;
+        self.b = state.read_u8()?;
+        self.c = state.read_u8()?;
+        self.d = state.read_u8()?;
+        self.e = state.read_u8()?;
+        self.h = state.read_u8()?;
+        self.l = state.read_u8()?;
+        self.f = state.read_u8()?;
+        self.pc = state.read_u16()?;
+        self.sp = state.read_u16()?;
+        Ok(())
+    }
+
     pub fn set_flag(&mut self, flag: Flag) {
         self.f |= flag as u8
     }
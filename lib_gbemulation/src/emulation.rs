@@ -4,16 +4,20 @@ use crate::io::joypad::Joypad;
 use crate::memory::mmu::Mmu;
 
 pub const CPU_CLOCK_HZ: usize = 4194304;
-pub const FPS: f32 = 60.0;
+pub const CLOCK_CYCLES_PER_FRAME: usize = 70224;
+pub const FPS: f32 = CPU_CLOCK_HZ as f32 / CLOCK_CYCLES_PER_FRAME as f32;
 
 pub struct Emulation {
     clock: Clock,
+    //Cycles the last call to run_cycles ran over its target
+    cycles_overrun: u64,
 }
 
 impl Emulation {
     pub fn new() -> Emulation {
         Emulation {
-            clock: Clock::new(CPU_CLOCK_HZ, FPS),
+            clock: Clock::new(CPU_CLOCK_HZ, CLOCK_CYCLES_PER_FRAME),
+            cycles_overrun: 0,
         }
     }
 
@@ -21,11 +25,57 @@ impl Emulation {
     /// Execute in a loop
     pub fn cycle(&mut self, cpu: &mut Cpu, mmu: &mut Mmu, joypad: &Joypad) {
         while self.clock.clock_cycles_passed_frame <= self.clock.clock_cycles_per_frame {
+            mmu.update_joypad(joypad);
             let last_cycle = cpu.step(mmu);
-            mmu.step(joypad, last_cycle);
             self.clock.cycle(last_cycle);
         }
 
         self.clock.reset();
     }
+
+    /// Runs until the gpu enters vblank and returns the amount of clock cycles consumed.
+    /// If the LCD is disabled it returns after the length of one frame
+    pub fn run_until_vblank(&mut self, cpu: &mut Cpu, mmu: &mut Mmu, joypad: &Joypad) -> u64 {
+        let start_cycles = mmu.clock_cycles();
+        mmu.gpu.take_frame_completed();
+
+        loop {
+            mmu.update_joypad(joypad);
+            cpu.step(mmu);
+
+            if mmu.gpu.take_frame_completed() {
+                break;
+            }
+
+            let cycles_passed = mmu.clock_cycles() - start_cycles;
+            if !mmu.gpu.is_lcd_enabled() && cycles_passed >= CLOCK_CYCLES_PER_FRAME as u64 {
+                break;
+            }
+        }
+
+        mmu.clock_cycles() - start_cycles
+    }
+
+    /// Runs for the given amount of clock cycles and returns the amount actually consumed.
+    /// Instructions can not be interrupted so the last one may run over. The overrun is
+    /// subtracted from the next call so consecutive calls do not drift
+    pub fn run_cycles(
+        &mut self,
+        cpu: &mut Cpu,
+        mmu: &mut Mmu,
+        joypad: &Joypad,
+        clock_cycles: u64,
+    ) -> u64 {
+        let start_cycles = mmu.clock_cycles();
+        let target_cycles = clock_cycles.saturating_sub(self.cycles_overrun);
+
+        while mmu.clock_cycles() - start_cycles < target_cycles {
+            mmu.update_joypad(joypad);
+            cpu.step(mmu);
+        }
+
+        let cycles_passed = mmu.clock_cycles() - start_cycles;
+        self.cycles_overrun = (self.cycles_overrun + cycles_passed).saturating_sub(clock_cycles);
+        cycles_passed
+    }
 }
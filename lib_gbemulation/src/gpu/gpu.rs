@@ -1,9 +1,11 @@
 use crate::gpu::lcdc::Lcdc;
 use crate::gpu::stat::{Mode, Stat};
-use crate::gpu::SCREEN_WIDTH;
 use crate::gpu::{Screen, BUFFER_SIZE};
+use crate::gpu::{SCREEN_HEIGHT, SCREEN_WIDTH};
 use crate::memory::interrupts::Interrupt;
 use crate::memory::mmu::{OAM_ADDRESS, VRAM_ADDRESS};
+use crate::sgb::sgb::Sgb;
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary::is_bit_set;
 use std::sync::Arc;
 
@@ -15,10 +17,10 @@ const TILESET_SECOND_BEGIN_ADDRESS: u16 = 0x9000;
 const BGMAP_FIRST_BEGIN_ADDRESS: u16 = 0x9800;
 const BGMAP_SECOND_BEGIN_ADDRESS: u16 = 0x9C00;
 
-const CYCLES_OAM: u16 = 80;
-const CYCLES_VRAM: u16 = 172;
-const CYCLES_HBLANK: u16 = 204;
-const CYCLES_VBLANK: u16 = 456;
+const CYCLES_OAM: u32 = 80;
+const CYCLES_VRAM: u32 = 172;
+const CYCLES_HBLANK: u32 = 204;
+const CYCLES_VBLANK: u32 = 456;
 
 const SCANLINES_DISPLAY: u8 = 143;
 const MAX_SCANLINES: u8 = 153;
@@ -39,8 +41,12 @@ pub struct Gpu {
     pub window_x: u8,
     pub window_y: u8,
     pub interrupts_fired: u8,
-    clock: u16,
+    pub sgb: Option<Sgb>,
+    frame_completed: bool,
+    clock: u32,
     screen_buffer: [u8; BUFFER_SIZE],
+    //Palette shades of the pixels, used to colorize the frame in Super Game Boy mode
+    shade_buffer: [u8; SCREEN_WIDTH * SCREEN_HEIGHT],
     bg_priority_map: [PriorityFlag; 65792],
     v_ram: [u8; V_RAM_SIZE],
     oam: [u8; OAM_SIZE],
@@ -67,8 +73,11 @@ impl Gpu {
             window_x: 7,
             lyc: 0,
             interrupts_fired: 0,
+            sgb: None,
+            frame_completed: false,
             clock: 0,
             screen_buffer: [0; BUFFER_SIZE],
+            shade_buffer: [0; SCREEN_WIDTH * SCREEN_HEIGHT],
             bg_priority_map: [PriorityFlag::None; 65792],
             v_ram: [0; V_RAM_SIZE],
             oam: [0; OAM_SIZE],
@@ -82,6 +91,11 @@ impl Gpu {
         }
     }
 
+    /// Colorizes the screen with Super Game Boy palettes and handles its VRAM transfers
+    pub fn enable_sgb(&mut self) {
+        self.sgb = Some(Sgb::new());
+    }
+
     pub fn read_vram(&self, address: u16) -> u8 {
         self.v_ram[(address - VRAM_ADDRESS) as usize]
     }
@@ -170,13 +184,110 @@ impl Gpu {
         self.stat.get_data()
     }
 
-    pub fn step(&mut self, clock_cycles: u8) {
+    pub fn step(&mut self, clock_cycles: u32) {
         if !self.lcd_enabled {
             return;
         }
 
-        self.clock += clock_cycles as u16;
-        self.step_set_mode();
+        self.clock += clock_cycles;
+
+        while self.clock >= self.get_mode_cycles() {
+            self.step_set_mode();
+        }
+    }
+
+    /// Amount of clock cycles until the next mode change. None if the LCD is disabled
+    pub fn cycles_until_mode_change(&self) -> Option<u32> {
+        if !self.lcd_enabled {
+            return None;
+        }
+
+        Some(self.get_mode_cycles() - self.clock)
+    }
+
+    /// Returns true once after the gpu entered vblank
+    pub fn take_frame_completed(&mut self) -> bool {
+        std::mem::replace(&mut self.frame_completed, false)
+    }
+
+    pub fn is_lcd_enabled(&self) -> bool {
+        self.lcd_enabled
+    }
+
+    /// The screen buffers are not part of the state because states are taken between frames
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.lcdc.get_data());
+        state.write_u8(self.stat.get_data());
+        state.write_u8(self.current_scanline);
+        state.write_u8(self.scroll_y);
+        state.write_u8(self.scroll_x);
+        state.write_u8(self.window_x);
+        state.write_u8(self.window_y);
+        state.write_u8(self.interrupts_fired);
+        state.write_u32(self.clock);
+        state.write_bytes(&self.v_ram);
+        state.write_bytes(&self.oam);
+        state.write_u8(self.lyc);
+        state.write_bytes(&self.raw_palette_data);
+        state.write_bool(self.lcd_enabled);
+        state.write_bool(self.first_frame_after_activation);
+
+        state.write_bool(self.sgb.is_some());
+        if let Some(ref sgb) = self.sgb {
+            sgb.save_state(state);
+        }
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.lcdc.set_data(state.read_u8()?);
+        let stat = state.read_u8()?;
+        self.stat.set_data(stat);
+        self.stat.mode = match stat & 0x03 {
+            0 => Mode::Hblank,
+            1 => Mode::Vblank,
+            2 => Mode::Oam,
+            _ => Mode::Vram,
+        };
+        self.stat.coincidence_flag = stat & 0x04 == 0x04;
+        self.current_scanline = state.read_u8()?;
+        self.scroll_y = state.read_u8()?;
+        self.scroll_x = state.read_u8()?;
+        self.window_x = state.read_u8()?;
+        self.window_y = state.read_u8()?;
+        self.interrupts_fired = state.read_u8()?;
+        self.clock = state.read_u32()?;
+        state.read_bytes_into(&mut self.v_ram)?;
+        state.read_bytes_into(&mut self.oam)?;
+        self.lyc = state.read_u8()?;
+
+        let mut raw_palette_data = [0; 3];
+        state.read_bytes_into(&mut raw_palette_data)?;
+        self.set_bg_pal(raw_palette_data[0]);
+        self.set_sprite_palette0(raw_palette_data[1]);
+        self.set_sprite_palette1(raw_palette_data[2]);
+
+        self.lcd_enabled = state.read_bool()?;
+        self.first_frame_after_activation = state.read_bool()?;
+        self.clear_screen();
+
+        //The state is skipped if Super Game Boy functions have been disabled since it was saved
+        if state.read_bool()? {
+            match self.sgb {
+                Some(ref mut sgb) => sgb.load_state(state)?,
+                None => Sgb::new().load_state(state)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_mode_cycles(&self) -> u32 {
+        match self.stat.mode {
+            Mode::Oam => CYCLES_OAM,
+            Mode::Vram => CYCLES_VRAM,
+            Mode::Hblank => CYCLES_HBLANK,
+            Mode::Vblank => CYCLES_VBLANK,
+        }
     }
 
     fn fire_interrupt(&mut self, interrupt: Interrupt) {
@@ -188,25 +299,26 @@ impl Gpu {
             Mode::Oam => {
                 if self.clock >= CYCLES_OAM {
                     self.set_mode(Mode::Vram);
-                    self.clock = self.clock % CYCLES_OAM;
+                    self.clock -= CYCLES_OAM;
                 }
             }
             Mode::Vram => {
                 if self.clock >= CYCLES_VRAM {
                     self.render_scanline_to_screen();
                     self.set_mode(Mode::Hblank);
-                    self.clock = self.clock % CYCLES_VRAM;
+                    self.clock -= CYCLES_VRAM;
                 }
             }
             Mode::Hblank => {
                 if self.clock >= CYCLES_HBLANK {
-                    self.clock = self.clock % CYCLES_HBLANK;
+                    self.clock -= CYCLES_HBLANK;
 
                     if self.current_scanline >= SCANLINES_DISPLAY {
                         self.set_mode(Mode::Vblank);
                         self.render_screen();
                         self.fire_interrupt(Interrupt::Vblank);
                         self.clear_screen();
+                        self.frame_completed = true;
                     } else {
                         self.set_current_scanline(self.current_scanline + 1);
                         self.set_mode(Mode::Oam);
@@ -216,7 +328,7 @@ impl Gpu {
             Mode::Vblank => {
                 if self.clock >= CYCLES_VBLANK {
                     self.set_current_scanline(self.current_scanline + 1);
-                    self.clock = self.clock % CYCLES_VBLANK;
+                    self.clock -= CYCLES_VBLANK;
                     if self.current_scanline > MAX_SCANLINES {
                         self.set_mode(Mode::Oam);
                         self.set_current_scanline(0);
@@ -276,6 +388,18 @@ impl Gpu {
             return;
         }
 
+        if let Some(ref mut sgb) = self.sgb {
+            sgb.update(&self.shade_buffer);
+
+            if let Some(border) = sgb.take_border() {
+                self.screen.draw_border(&border);
+            }
+
+            if !sgb.colorize(&self.shade_buffer, &mut self.screen_buffer) {
+                return;
+            }
+        }
+
         self.color_map = self.screen.get_palette();
         self.screen.draw(&self.screen_buffer);
     }
@@ -456,7 +580,7 @@ impl Gpu {
             return;
         }
 
-        self.draw_pixel_to_buffer(x as usize, y as usize, self.color_map[pixel as usize]);
+        self.draw_pixel_to_buffer(x as usize, y as usize, pixel);
     }
 
     fn background_has_priority_over_pixel(&self, sprite_options: &u8, offset: usize) -> bool {
@@ -488,10 +612,13 @@ impl Gpu {
             self.bg_priority_map[offset] = PriorityFlag::Color0
         }
 
-        self.draw_pixel_to_buffer(x as usize, y as usize, self.color_map[pixel as usize]);
+        self.draw_pixel_to_buffer(x as usize, y as usize, pixel);
     }
 
-    fn draw_pixel_to_buffer(&mut self, x: usize, y: usize, rgb: [u8; 3]) {
+    fn draw_pixel_to_buffer(&mut self, x: usize, y: usize, shade: u8) {
+        self.shade_buffer[x + y * SCREEN_WIDTH] = shade;
+
+        let rgb = self.color_map[shade as usize];
         let offset = (x * 3) + (y * SCREEN_WIDTH * 3);
 
         self.screen_buffer[offset] = rgb[0];
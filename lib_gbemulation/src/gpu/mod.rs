@@ -18,4 +18,8 @@ pub enum Pixel {
 pub trait Screen {
     fn draw(&self, screen_buffer: &[u8; BUFFER_SIZE]);
     fn get_palette(&self) -> [[u8; 3]; 4];
+
+    /// Called when a Super Game Boy game loads a new border. The buffer contains
+    /// BORDER_WIDTH * BORDER_HEIGHT RGBA pixels
+    fn draw_border(&self, _border_buffer: &[u8]) {}
 }
@@ -55,16 +55,19 @@ impl Joypad {
     }
 
     pub fn read_input(&self, value: u8) -> u8 {
+        let mut input = 0x0F;
+
         //Bit 4 = Direction keys selected
         if !is_bit_set(&value, 4) {
-            return self.direction_key_status;
+            input &= self.direction_key_status;
         }
 
-        //Bit 5 = Button keys
+        //Bit 5 = Button keys selected. If both groups are selected the lines are ANDed
         if !is_bit_set(&value, 5) {
-            return self.button_key_status;
+            input &= self.button_key_status;
         }
 
-        return 0;
+        //Upper two bits are unused and always read as 1
+        0xC0 | (value & 0x30) | input
     }
 }
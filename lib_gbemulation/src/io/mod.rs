@@ -1,2 +1,4 @@
 pub mod joypad;
+pub mod printer;
+pub mod serial;
 pub mod timer;
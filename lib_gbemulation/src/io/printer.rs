@@ -0,0 +1,473 @@
+use crate::io::serial::SerialPeer;
+
+pub const PRINT_WIDTH: usize = 160;
+
+const MAGIC_BYTE_1: u8 = 0x88;
+const MAGIC_BYTE_2: u8 = 0x33;
+const DEVICE_ID: u8 = 0x81;
+
+const COMMAND_INIT: u8 = 0x01;
+const COMMAND_PRINT: u8 = 0x02;
+const COMMAND_DATA: u8 = 0x04;
+const COMMAND_STATUS: u8 = 0x0F;
+
+const STATUS_CHECKSUM_ERROR: u8 = 0x01;
+const STATUS_PRINTING: u8 = 0x02;
+const STATUS_IMAGE_DATA_FULL: u8 = 0x04;
+const STATUS_UNPROCESSED_DATA: u8 = 0x08;
+
+//The printer memory holds 8KB of decompressed tile data
+const BUFFER_SIZE: usize = 0x2000;
+const TILE_SIZE: usize = 16;
+const TILES_PER_ROW: usize = PRINT_WIDTH / 8;
+//Amount of status requests which are answered as busy after printing
+const PRINTING_STATUS_COUNT: u8 = 4;
+//Exposure at which the palette shades are printed unchanged
+const EXPOSURE_REFERENCE: f32 = 0x40 as f32;
+
+/// Receives the images printed by the Game Boy Printer
+pub trait PrinterOutput {
+    /// Called for every printed strip with PRINT_WIDTH * height grayscale values,
+    /// 0 is black and 255 is white
+    fn print(&mut self, image: Vec<u8>, height: usize);
+}
+
+/// Output used if the printouts are not needed
+pub struct NullPrinterOutput;
+
+impl PrinterOutput for NullPrinterOutput {
+    fn print(&mut self, _image: Vec<u8>, _height: usize) {}
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum PacketState {
+    MagicByte1,
+    MagicByte2,
+    Command,
+    Compression,
+    LengthLow,
+    LengthHigh,
+    Data,
+    ChecksumLow,
+    ChecksumHigh,
+    DeviceId,
+    Status,
+}
+
+/// Game Boy Printer connected to the link port. Packets start with two magic bytes followed
+/// by command, compression flag, length, data and checksum. The printer answers the two
+/// bytes after the checksum with its device id and its status
+pub struct GameBoyPrinter {
+    output: Box<dyn PrinterOutput + Send>,
+    state: PacketState,
+    command: u8,
+    compressed: bool,
+    length: usize,
+    packet_data: Vec<u8>,
+    checksum: u16,
+    received_checksum: u16,
+    //Decompressed tile data waiting to be printed
+    image_data: Vec<u8>,
+    status: u8,
+    printing_status_count: u8,
+}
+
+impl GameBoyPrinter {
+    pub fn new(output: Box<dyn PrinterOutput + Send>) -> Self {
+        GameBoyPrinter {
+            output,
+            state: PacketState::MagicByte1,
+            command: 0,
+            compressed: false,
+            length: 0,
+            packet_data: Vec::new(),
+            checksum: 0,
+            received_checksum: 0,
+            image_data: Vec::with_capacity(BUFFER_SIZE),
+            status: 0,
+            printing_status_count: 0,
+        }
+    }
+
+    fn add_to_checksum(&mut self, value: u8) {
+        self.checksum = self.checksum.wrapping_add(value as u16);
+    }
+
+    fn process_packet(&mut self) {
+        if self.checksum != self.received_checksum {
+            self.status |= STATUS_CHECKSUM_ERROR;
+            return;
+        }
+
+        self.status &= !STATUS_CHECKSUM_ERROR;
+
+        match self.command {
+            COMMAND_INIT => {
+                self.image_data.clear();
+                self.status = 0;
+                self.printing_status_count = 0;
+            }
+            COMMAND_DATA => self.receive_data(),
+            COMMAND_PRINT => {
+                //Sheets, margins, palette and exposure
+                if let [sheets, _, palette, exposure, ..] = self.packet_data[..] {
+                    self.print(sheets, palette, exposure);
+                }
+            }
+            COMMAND_STATUS if self.printing_status_count > 0 => {
+                self.printing_status_count -= 1;
+
+                if self.printing_status_count == 0 {
+                    self.status &= !STATUS_PRINTING;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn receive_data(&mut self) {
+        //An empty data packet marks the end of the image
+        if self.packet_data.is_empty() {
+            self.status |= STATUS_IMAGE_DATA_FULL;
+            return;
+        }
+
+        let data = if self.compressed {
+            decompress(&self.packet_data)
+        } else {
+            self.packet_data.clone()
+        };
+
+        let free_space = BUFFER_SIZE - self.image_data.len();
+        self.image_data.extend(data.into_iter().take(free_space));
+        self.status |= STATUS_UNPROCESSED_DATA;
+    }
+
+    fn print(&mut self, sheets: u8, palette: u8, exposure: u8) {
+        //Zero sheets only feeds the paper
+        if sheets > 0 {
+            let image = decode_tiles(&self.image_data, palette, exposure);
+            let height = image.len() / PRINT_WIDTH;
+
+            if height > 0 {
+                self.output.print(image, height);
+            }
+        }
+
+        self.image_data.clear();
+        self.status = STATUS_PRINTING;
+        self.printing_status_count = PRINTING_STATUS_COUNT;
+    }
+}
+
+impl SerialPeer for GameBoyPrinter {
+    fn exchange(&mut self, value: u8) -> u8 {
+        let mut response = 0x00;
+
+        match self.state {
+            PacketState::MagicByte1 => {
+                if value == MAGIC_BYTE_1 {
+                    self.state = PacketState::MagicByte2;
+                }
+            }
+            PacketState::MagicByte2 => {
+                self.state = if value == MAGIC_BYTE_2 {
+                    PacketState::Command
+                } else {
+                    PacketState::MagicByte1
+                };
+            }
+            PacketState::Command => {
+                self.command = value;
+                self.checksum = value as u16;
+                self.state = PacketState::Compression;
+            }
+            PacketState::Compression => {
+                self.compressed = value & 0x01 == 0x01;
+                self.add_to_checksum(value);
+                self.state = PacketState::LengthLow;
+            }
+            PacketState::LengthLow => {
+                self.length = value as usize;
+                self.add_to_checksum(value);
+                self.state = PacketState::LengthHigh;
+            }
+            PacketState::LengthHigh => {
+                self.length |= (value as usize) << 8;
+                self.add_to_checksum(value);
+                self.packet_data.clear();
+
+                self.state = if self.length == 0 {
+                    PacketState::ChecksumLow
+                } else {
+                    PacketState::Data
+                };
+            }
+            PacketState::Data => {
+                self.packet_data.push(value);
+                self.add_to_checksum(value);
+
+                if self.packet_data.len() == self.length {
+                    self.state = PacketState::ChecksumLow;
+                }
+            }
+            PacketState::ChecksumLow => {
+                self.received_checksum = value as u16;
+                self.state = PacketState::ChecksumHigh;
+            }
+            PacketState::ChecksumHigh => {
+                self.received_checksum |= (value as u16) << 8;
+                self.process_packet();
+                self.state = PacketState::DeviceId;
+            }
+            PacketState::DeviceId => {
+                response = DEVICE_ID;
+                self.state = PacketState::Status;
+            }
+            PacketState::Status => {
+                response = self.status;
+                self.state = PacketState::MagicByte1;
+            }
+        }
+
+        response
+    }
+}
+
+/// Run length encoding used by data packets. A control byte with bit 7 set repeats the
+/// following byte (control & 0x7F) + 2 times, otherwise control + 1 bytes are copied
+fn decompress(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(BUFFER_SIZE);
+    let mut index = 0;
+
+    while index < data.len() {
+        let control = data[index];
+        index += 1;
+
+        if control & 0x80 == 0x80 {
+            let length = (control & 0x7F) as usize + 2;
+
+            if let Some(value) = data.get(index) {
+                result.resize(result.len() + length, *value);
+            }
+
+            index += 1;
+        } else {
+            let end = (index + control as usize + 1).min(data.len());
+            result.extend_from_slice(&data[index..end]);
+            index = end;
+        }
+    }
+
+    result
+}
+
+/// Converts rows of 20 tiles to grayscale. The palette maps the color numbers of the tiles
+/// to shades and the exposure darkens or lightens them by up to 25%
+fn decode_tiles(tile_data: &[u8], palette: u8, exposure: u8) -> Vec<u8> {
+    let height = tile_data.len() / (TILE_SIZE * TILES_PER_ROW) * 8;
+    let darkness = 1.0 + ((exposure & 0x7F) as f32 - EXPOSURE_REFERENCE) / 0x7F as f32 * 0.5;
+
+    let mut image = Vec::with_capacity(PRINT_WIDTH * height);
+
+    for y in 0..height {
+        for x in 0..PRINT_WIDTH {
+            let tile = (y / 8) * TILES_PER_ROW + x / 8;
+            let address = tile * TILE_SIZE + (y % 8) * 2;
+            let bit = 7 - (x % 8);
+
+            let low = (tile_data[address] >> bit) & 0x01;
+            let high = (tile_data[address + 1] >> bit) & 0x01;
+            let color_number = high << 1 | low;
+            let shade = (palette >> (color_number * 2)) & 0x03;
+
+            let intensity = (shade as f32 / 3.0 * darkness).clamp(0.0, 1.0);
+            image.push(255 - (intensity * 255.0) as u8);
+        }
+    }
+
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type Printouts = Arc<Mutex<Vec<(Vec<u8>, usize)>>>;
+
+    struct RecordingOutput {
+        printouts: Printouts,
+    }
+
+    impl PrinterOutput for RecordingOutput {
+        fn print(&mut self, image: Vec<u8>, height: usize) {
+            self.printouts.lock().unwrap().push((image, height));
+        }
+    }
+
+    fn new_printer() -> (GameBoyPrinter, Printouts) {
+        let printouts = Arc::new(Mutex::new(Vec::new()));
+        let output = RecordingOutput {
+            printouts: printouts.clone(),
+        };
+        (GameBoyPrinter::new(Box::new(output)), printouts)
+    }
+
+    fn packet(command: u8, compressed: bool, data: &[u8]) -> Vec<u8> {
+        let mut body = vec![
+            command,
+            compressed as u8,
+            data.len() as u8,
+            (data.len() >> 8) as u8,
+        ];
+        body.extend_from_slice(data);
+
+        let checksum = body
+            .iter()
+            .fold(0u16, |checksum, value| checksum.wrapping_add(*value as u16));
+
+        let mut bytes = vec![MAGIC_BYTE_1, MAGIC_BYTE_2];
+        bytes.extend_from_slice(&body);
+        bytes.extend_from_slice(&[checksum as u8, (checksum >> 8) as u8, 0x00, 0x00]);
+        bytes
+    }
+
+    //Returns the device id and the status the printer answers with
+    fn send(printer: &mut GameBoyPrinter, bytes: &[u8]) -> (u8, u8) {
+        let responses: Vec<u8> = bytes.iter().map(|value| printer.exchange(*value)).collect();
+        (
+            responses[responses.len() - 2],
+            responses[responses.len() - 1],
+        )
+    }
+
+    #[test]
+    fn answers_status_request() {
+        let (mut printer, _) = new_printer();
+
+        assert_eq!(
+            send(&mut printer, &packet(COMMAND_STATUS, false, &[])),
+            (DEVICE_ID, 0)
+        );
+    }
+
+    #[test]
+    fn ignores_bytes_before_magic_bytes() {
+        let (mut printer, _) = new_printer();
+        let mut bytes = vec![0x00, MAGIC_BYTE_1, 0x00];
+        bytes.extend(packet(COMMAND_STATUS, false, &[]));
+
+        assert_eq!(send(&mut printer, &bytes), (DEVICE_ID, 0));
+    }
+
+    #[test]
+    fn reports_checksum_error() {
+        let (mut printer, _) = new_printer();
+        let mut bytes = packet(COMMAND_DATA, false, &[0x12, 0x34]);
+        let checksum_index = bytes.len() - 4;
+        bytes[checksum_index] ^= 0xFF;
+
+        let (_, status) = send(&mut printer, &bytes);
+        assert_eq!(status, STATUS_CHECKSUM_ERROR);
+
+        //The next valid packet clears the error
+        let (_, status) = send(&mut printer, &packet(COMMAND_STATUS, false, &[]));
+        assert_eq!(status, 0);
+    }
+
+    #[test]
+    fn reports_received_data() {
+        let (mut printer, _) = new_printer();
+
+        let (_, status) = send(&mut printer, &packet(COMMAND_DATA, false, &[0xFF; 16]));
+        assert_eq!(status, STATUS_UNPROCESSED_DATA);
+
+        let (_, status) = send(&mut printer, &packet(COMMAND_DATA, false, &[]));
+        assert_eq!(status, STATUS_UNPROCESSED_DATA | STATUS_IMAGE_DATA_FULL);
+
+        let (_, status) = send(&mut printer, &packet(COMMAND_INIT, false, &[]));
+        assert_eq!(status, 0);
+    }
+
+    #[test]
+    fn decompresses_runs_and_literals() {
+        let data = [0x81, 0xAA, 0x01, 0x11, 0x22, 0x80, 0x33];
+
+        assert_eq!(
+            decompress(&data),
+            vec![0xAA, 0xAA, 0xAA, 0x11, 0x22, 0x33, 0x33]
+        );
+    }
+
+    #[test]
+    fn decompresses_truncated_data() {
+        assert_eq!(decompress(&[0x85]), Vec::<u8>::new());
+        assert_eq!(decompress(&[0x03, 0x11, 0x22]), vec![0x11, 0x22]);
+    }
+
+    #[test]
+    fn prints_tile_row() {
+        let (mut printer, printouts) = new_printer();
+        //One row of tiles, the upper half uses color 3 and the lower half color 0
+        let tile_row: Vec<u8> = (0..TILE_SIZE * TILES_PER_ROW)
+            .map(|index| if index % TILE_SIZE < 8 { 0xFF } else { 0x00 })
+            .collect();
+
+        send(&mut printer, &packet(COMMAND_INIT, false, &[]));
+        send(&mut printer, &packet(COMMAND_DATA, false, &tile_row));
+        send(&mut printer, &packet(COMMAND_DATA, false, &[]));
+
+        let (_, status) = send(
+            &mut printer,
+            &packet(COMMAND_PRINT, false, &[0x01, 0x13, 0xE4, 0x40]),
+        );
+        assert_eq!(status, STATUS_PRINTING);
+
+        let printouts = printouts.lock().unwrap();
+        assert_eq!(printouts.len(), 1);
+
+        let (image, height) = &printouts[0];
+        assert_eq!(*height, 8);
+        assert_eq!(image.len(), PRINT_WIDTH * 8);
+        assert!(image[..PRINT_WIDTH * 4].iter().all(|value| *value == 0));
+        assert!(image[PRINT_WIDTH * 4..].iter().all(|value| *value == 255));
+    }
+
+    #[test]
+    fn prints_compressed_data() {
+        let (mut printer, printouts) = new_printer();
+        //Two rows of tiles, 640 bytes of 0xFF as four runs of 129 and one run of 124 bytes
+        let mut compressed = vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        compressed.extend_from_slice(&[0x80 | (124 - 2), 0xFF]);
+
+        send(&mut printer, &packet(COMMAND_DATA, true, &compressed));
+        send(
+            &mut printer,
+            &packet(COMMAND_PRINT, false, &[0x01, 0x13, 0xE4, 0x40]),
+        );
+
+        let printouts = printouts.lock().unwrap();
+        let (image, height) = &printouts[0];
+        assert_eq!(*height, 16);
+        assert_eq!(image.len(), PRINT_WIDTH * 16);
+        assert!(image.iter().all(|value| *value == 0));
+    }
+
+    #[test]
+    fn stays_busy_for_some_status_requests() {
+        let (mut printer, _) = new_printer();
+        send(
+            &mut printer,
+            &packet(COMMAND_PRINT, false, &[0x00, 0x13, 0xE4, 0x40]),
+        );
+
+        for _ in 0..PRINTING_STATUS_COUNT - 1 {
+            let (_, status) = send(&mut printer, &packet(COMMAND_STATUS, false, &[]));
+            assert_eq!(status, STATUS_PRINTING);
+        }
+
+        let (_, status) = send(&mut printer, &packet(COMMAND_STATUS, false, &[]));
+        assert_eq!(status, 0);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::memory::interrupts::Interrupt;
+use crate::state::{StateReader, StateWriter};
+use crate::util::binary::is_bit_set;
+
+/// Clock cycles needed to shift out all 8 bits with the internal clock (8192 Hz)
+pub const TRANSFER_CLOCK_CYCLES: u64 = 4096;
+
+/// Device connected to the link port
+pub trait SerialPeer {
+    /// Receives the byte shifted out by the Game Boy and returns the byte shifted in
+    fn exchange(&mut self, value: u8) -> u8;
+}
+
+pub struct Serial {
+    pub data: u8,
+    pub interrupts_fired: u8,
+    control: u8,
+    peer: Option<Box<dyn SerialPeer + Send>>,
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serial {
+    pub fn new() -> Self {
+        Serial {
+            data: 0,
+            interrupts_fired: 0,
+            control: 0,
+            peer: None,
+        }
+    }
+
+    pub fn set_peer(&mut self, peer: Box<dyn SerialPeer + Send>) {
+        self.peer = Some(peer);
+    }
+
+    pub fn read_control(&self) -> u8 {
+        //Unused bits always read as 1
+        self.control | 0x7E
+    }
+
+    /// Returns true if a transfer using the internal clock has been started.
+    /// Transfers using an external clock never complete because peers never provide the clock
+    pub fn write_control(&mut self, value: u8) -> bool {
+        self.control = value & 0x81;
+        is_bit_set(&self.control, 7) && is_bit_set(&self.control, 0)
+    }
+
+    /// The connected peer is not part of the state
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.data);
+        state.write_u8(self.interrupts_fired);
+        state.write_u8(self.control);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.data = state.read_u8()?;
+        self.interrupts_fired = state.read_u8()?;
+        self.control = state.read_u8()?;
+        Ok(())
+    }
+
+    pub fn complete_transfer(&mut self) {
+        //Without a peer all bits shifted in are 1
+        self.data = match self.peer {
+            Some(ref mut peer) => peer.exchange(self.data),
+            None => 0xFF,
+        };
+        self.control &= 0x7F;
+        self.interrupts_fired |= Interrupt::Serial as u8;
+    }
+}
@@ -1,80 +1,135 @@
 use crate::memory::interrupts::Interrupt;
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary::is_bit_set;
 
-const DIVIDER_CYCLES: u32 = 256;
-const SPEED_0_CYCLES: u32 = 1024;
-const SPEED_1_CYCLES: u32 = 16;
-const SPEED_2_CYCLES: u32 = 64;
-const SPEED_3_CYCLES: u32 = 256;
+const SPEED_0_CYCLES: u64 = 1024;
+const SPEED_1_CYCLES: u64 = 16;
+const SPEED_2_CYCLES: u64 = 64;
+const SPEED_3_CYCLES: u64 = 256;
 
 pub struct Timer {
-    pub divider: u8,
     pub counter: u8,
     pub modulo: u8,
-    pub timer_control: u8,
     pub interrupts_fired: u8,
-    clock_cycles_divider: u32,
-    clock_cycles_timer: u32,
-    has_overflowed: bool,
+    timer_control: u8,
+    //Internal counter incremented every clock cycle. The divider is its upper byte
+    system_counter: u16,
 }
 
 impl Timer {
     pub fn new() -> Timer {
         Timer {
-            divider: 0,
             counter: 0,
             modulo: 0,
-            timer_control: 0,
             interrupts_fired: 0,
-            clock_cycles_divider: 0,
-            clock_cycles_timer: 0,
-            has_overflowed: false,
+            timer_control: 0,
+            system_counter: 0,
         }
     }
 
-    pub fn step(&mut self, clock_cycles: u8) {
-        let cycles = clock_cycles as u32;
-        self.clock_cycles_divider += cycles;
+    pub fn step(&mut self, clock_cycles: u64) {
+        let old_counter = self.system_counter as u64;
+        let new_counter = old_counter + clock_cycles;
+        self.system_counter = new_counter as u16;
 
-        while self.clock_cycles_divider >= DIVIDER_CYCLES {
-            self.divider = self.divider.wrapping_add(1);
-            self.clock_cycles_divider -= DIVIDER_CYCLES;
+        if !self.is_running() {
+            return;
         }
 
-        let speed = self.timer_control << 6;
-        let running = is_bit_set(&self.timer_control, 2);
+        //The counter is incremented on every falling edge of the selected system counter bit
+        let period = self.get_period();
+        self.increment_counter(new_counter / period - old_counter / period);
+    }
 
-        if !running {
-            return;
+    pub fn get_divider(&self) -> u8 {
+        (self.system_counter >> 8) as u8
+    }
+
+    pub fn reset_divider(&mut self) {
+        //Resetting can cause a falling edge on the selected bit
+        let increment = self.timer_signal();
+        self.system_counter = 0;
+
+        if increment {
+            self.increment_counter(1);
         }
+    }
 
-        self.clock_cycles_timer += cycles;
+    pub fn get_timer_control(&self) -> u8 {
+        self.timer_control | 0xF8
+    }
 
-        let timer_cycles = match speed {
-            0 => SPEED_0_CYCLES,
-            0x40 => SPEED_1_CYCLES,
-            0x80 => SPEED_2_CYCLES,
-            0xC0 => SPEED_3_CYCLES,
-            _ => panic!("Unknown timer speed: 0x{:X}", speed),
-        };
+    pub fn set_timer_control(&mut self, value: u8) {
+        //Disabling the timer or switching the speed can also cause a falling edge
+        let old_signal = self.timer_signal();
+        self.timer_control = value & 0x07;
 
-        if self.has_overflowed {
-            self.fire_interrupt(Interrupt::Timer);
-            self.counter = self.modulo;
-            self.has_overflowed = false;
+        if old_signal && !self.timer_signal() {
+            self.increment_counter(1);
         }
+    }
 
-        //It could happen that the timer needs to be incremented multiple times within a given cycle
-        while self.clock_cycles_timer >= timer_cycles {
-            if self.counter == 255 {
-                //Overflow does not happen immediately
-                self.has_overflowed = true;
-                self.counter = 0;
+    /// Amount of clock cycles until the counter overflows and the interrupt is fired
+    pub fn cycles_until_overflow(&self) -> Option<u64> {
+        if !self.is_running() {
+            return None;
+        }
+
+        let period = self.get_period();
+        let cycles_until_increment = period - self.system_counter as u64 % period;
+        let increments_until_overflow = 256 - self.counter as u64;
+
+        Some(cycles_until_increment + (increments_until_overflow - 1) * period)
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.counter);
+        state.write_u8(self.modulo);
+        state.write_u8(self.interrupts_fired);
+        state.write_u8(self.timer_control);
+        state.write_u16(self.system_counter);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.counter = state.read_u8()?;
+        self.modulo = state.read_u8()?;
+        self.interrupts_fired = state.read_u8()?;
+        self.timer_control = state.read_u8()?;
+        self.system_counter = state.read_u16()?;
+        Ok(())
+    }
+
+    fn increment_counter(&mut self, increments: u64) {
+        let mut remaining = increments;
+
+        while remaining > 0 {
+            let increments_until_overflow = 256 - self.counter as u64;
+
+            if remaining < increments_until_overflow {
+                self.counter += remaining as u8;
                 return;
             }
 
-            self.counter += 1;
-            self.clock_cycles_timer -= timer_cycles;
+            remaining -= increments_until_overflow;
+            self.counter = self.modulo;
+            self.fire_interrupt(Interrupt::Timer);
+        }
+    }
+
+    fn timer_signal(&self) -> bool {
+        self.is_running() && self.system_counter as u64 & (self.get_period() / 2) != 0
+    }
+
+    fn is_running(&self) -> bool {
+        is_bit_set(&self.timer_control, 2)
+    }
+
+    fn get_period(&self) -> u64 {
+        match self.timer_control & 0x03 {
+            0 => SPEED_0_CYCLES,
+            1 => SPEED_1_CYCLES,
+            2 => SPEED_2_CYCLES,
+            _ => SPEED_3_CYCLES,
         }
     }
 
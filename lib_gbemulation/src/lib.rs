@@ -6,4 +6,7 @@ pub mod emulation;
 pub mod gpu;
 pub mod io;
 pub mod memory;
+pub mod scheduler;
+pub mod sgb;
+pub mod state;
 pub mod util;
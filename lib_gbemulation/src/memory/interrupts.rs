@@ -1,3 +1,5 @@
+use crate::state::{StateReader, StateWriter};
+
 pub const INTERRUPT_ENABLE_ADDRESS: u16 = 0xFFFF;
 pub const INTERRUPT_FLAGS_ADDRESS: u16 = 0xFF0F;
 
@@ -23,6 +25,17 @@ impl InterruptState {
         }
     }
 
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.interrupt_flags);
+        state.write_u8(self.interrupts_enabled);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.interrupt_flags = state.read_u8()?;
+        self.interrupts_enabled = state.read_u8()?;
+        Ok(())
+    }
+
     pub fn fire_interrupt(&mut self, interrupt: &Interrupt) {
         self.interrupt_flags |= *interrupt as u8;
     }
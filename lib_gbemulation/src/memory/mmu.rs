@@ -1,11 +1,16 @@
 use crate::apu::apu::Apu;
 
+use crate::cartridge::header::CartridgeHeader;
 use crate::cartridge::Cartridge;
 use crate::gpu::gpu::Gpu;
 use crate::io::joypad::Joypad;
+use crate::io::serial;
+use crate::io::serial::Serial;
 use crate::io::timer::Timer;
 use crate::memory::interrupts;
-use crate::memory::interrupts::InterruptState;
+use crate::memory::interrupts::{Interrupt, InterruptState};
+use crate::scheduler::{EventType, Scheduler};
+use crate::state::{StateReader, StateWriter};
 use crate::util::binary;
 
 const EXT_RAM_START_ADDRESS: u16 = 0xA000;
@@ -17,6 +22,15 @@ pub const OAM_ADDRESS: u16 = 0xFE00;
 
 const W_RAM_SIZE: usize = 8192;
 const H_RAM_SIZE: usize = 127;
+const OAM_SIZE: u16 = 160;
+
+pub const CLOCK_CYCLES_PER_MACHINE_CYCLE: u8 = 4;
+//About 2 milliseconds of audio
+const APU_SYNC_CLOCK_CYCLES: u64 = 8192;
+
+//The DMG boot ROM is mapped over the start of the cartridge until it is disabled
+pub const BOOT_ROM_SIZE: usize = 256;
+const BOOT_ROM_DISABLE_ADDRESS: u16 = 0xFF50;
 
 pub enum Opcode {
     Regular(u8),
@@ -26,6 +40,7 @@ pub enum Opcode {
 pub struct Mmu<'a> {
     pub gpu: &'a mut Gpu,
     pub timer: Timer,
+    pub serial: Serial,
     pub interrupts: InterruptState,
     pub apu: &'a mut Apu<'a>,
     w_ram: [u8; W_RAM_SIZE],
@@ -33,6 +48,16 @@ pub struct Mmu<'a> {
     joypad_select: u8,
     joypad: u8,
     cartridge: &'a mut dyn Cartridge,
+    stopped: bool,
+    clock_cycles: u64,
+    dma_source_address: u16,
+    dma_offset: u16,
+    scheduler: Scheduler,
+    gpu_last_sync: u64,
+    timer_last_sync: u64,
+    apu_last_sync: u64,
+    boot_rom: Vec<u8>,
+    boot_rom_mapped: bool,
 }
 
 impl<'a> Mmu<'a> {
@@ -41,9 +66,10 @@ impl<'a> Mmu<'a> {
         gpu: &'a mut Gpu,
         apu: &'a mut Apu<'a>,
     ) -> Mmu<'a> {
-        Mmu {
+        let mut mmu = Mmu {
             gpu,
             timer: Timer::new(),
+            serial: Serial::new(),
             interrupts: InterruptState::new(),
             apu,
             w_ram: [0; W_RAM_SIZE],
@@ -51,56 +77,350 @@ impl<'a> Mmu<'a> {
             joypad_select: 0xFF,
             joypad: 0xFF,
             cartridge,
+            stopped: false,
+            clock_cycles: 0,
+            dma_source_address: 0,
+            dma_offset: OAM_SIZE,
+            scheduler: Scheduler::new(),
+            gpu_last_sync: 0,
+            timer_last_sync: 0,
+            apu_last_sync: 0,
+            boot_rom: Vec::new(),
+            boot_rom_mapped: false,
+        };
+
+        mmu.schedule_gpu();
+        mmu.schedule_timer();
+        mmu.schedule_apu();
+        mmu
+    }
+
+    /// Advances the rest of the system by one machine cycle (4 clock cycles).
+    /// The CPU calls this for every memory access and every internal cycle of an instruction.
+    /// Components are only stepped when one of their events is due or their registers are accessed
+    pub fn tick(&mut self) {
+        self.clock_cycles += CLOCK_CYCLES_PER_MACHINE_CYCLE as u64;
+
+        if !self.stopped {
+            self.step_dma();
+        }
+
+        if self.clock_cycles >= self.scheduler.next_timestamp() {
+            self.handle_events();
         }
     }
 
-    pub fn step(&mut self, joypad: &Joypad, clock_cycles: u8) {
-        self.read_joypad(joypad);
-        self.gpu.step(clock_cycles);
-        self.timer.step(clock_cycles);
-        self.apu.step(clock_cycles);
-        self.interrupts.interrupt_flags |= self.timer.interrupts_fired;
+    fn handle_events(&mut self) {
+        while let Some(event_type) = self.scheduler.pop_due(self.clock_cycles) {
+            match event_type {
+                EventType::Gpu => self.sync_gpu(),
+                EventType::Timer => self.sync_timer(),
+                EventType::Apu => self.sync_apu(),
+                EventType::Serial => self.serial.complete_transfer(),
+            }
+        }
+
+        self.collect_interrupts();
+    }
+
+    fn collect_interrupts(&mut self) {
         self.interrupts.interrupt_flags |= self.gpu.interrupts_fired;
+        self.interrupts.interrupt_flags |= self.timer.interrupts_fired;
+        self.interrupts.interrupt_flags |= self.serial.interrupts_fired;
         self.gpu.interrupts_fired = 0;
         self.timer.interrupts_fired = 0;
+        self.serial.interrupts_fired = 0;
+    }
+
+    /// Catches the gpu up to the current clock cycle and schedules its next mode change
+    fn sync_gpu(&mut self) {
+        let clock_cycles = self.clock_cycles - self.gpu_last_sync;
+        self.gpu_last_sync = self.clock_cycles;
+
+        //While the LCD is off the gpu is not synced, so long HALTs can exceed u32::MAX cycles.
+        //The gpu ignores the cycles in that case
+        if clock_cycles > 0 {
+            self.gpu.step(u32::try_from(clock_cycles).unwrap_or(u32::MAX));
+        }
+
+        self.schedule_gpu();
+    }
+
+    fn schedule_gpu(&mut self) {
+        match self.gpu.cycles_until_mode_change() {
+            Some(cycles) => self
+                .scheduler
+                .schedule(EventType::Gpu, self.gpu_last_sync + cycles as u64),
+            None => self.scheduler.cancel(EventType::Gpu),
+        }
+    }
+
+    /// Catches the timer up to the current clock cycle and schedules its next overflow
+    fn sync_timer(&mut self) {
+        let clock_cycles = self.clock_cycles - self.timer_last_sync;
+        self.timer_last_sync = self.clock_cycles;
+        self.timer.step(clock_cycles);
+
+        self.schedule_timer();
+    }
+
+    fn schedule_timer(&mut self) {
+        match self.timer.cycles_until_overflow() {
+            Some(cycles) => self
+                .scheduler
+                .schedule(EventType::Timer, self.timer_last_sync + cycles),
+            None => self.scheduler.cancel(EventType::Timer),
+        }
+    }
+
+    /// Catches the apu up to the current clock cycle. It is stepped in chunks so no sample or
+    /// frame sequencer step gets skipped
+    fn sync_apu(&mut self) {
+        let mut clock_cycles = self.clock_cycles - self.apu_last_sync;
+        self.apu_last_sync = self.clock_cycles;
+
+        while clock_cycles > 0 {
+            let chunk = clock_cycles.min(self.apu.cycles_until_next_event() as u64);
+            self.apu.step(chunk as u16);
+            clock_cycles -= chunk;
+        }
+
+        self.schedule_apu();
+    }
+
+    //The samples of the whole interval are generated at once, the apu does not need an
+    //event for every sample
+    fn schedule_apu(&mut self) {
+        self.scheduler
+            .schedule(EventType::Apu, self.apu_last_sync + APU_SYNC_CLOCK_CYCLES);
+    }
+
+    /// Syncs the component owning the given io register. Called before and after a write
+    /// so the component runs up to the write and gets rescheduled with its new state
+    fn sync_io_register(&mut self, address: u16) {
+        match address {
+            0xFF04..=0xFF07 => self.sync_timer(),
+            0xFF10..=0xFF3F => self.sync_apu(),
+            0xFF40..=0xFF4B => self.sync_gpu(),
+            _ => return,
+        }
+
+        self.collect_interrupts();
+    }
+
+    /// Total amount of clock cycles the system has been running
+    pub fn clock_cycles(&self) -> u64 {
+        self.clock_cycles
+    }
+
+    pub fn update_joypad(&mut self, joypad: &Joypad) {
+        self.read_joypad(joypad);
+    }
+
+    /// Dumps the cartridge ram if it has been changed since the last save
+    pub fn save(&mut self) -> Result<(), String> {
+        self.cartridge.dump_savegame()
+    }
+
+    /// The game disabled the cartridge ram after changing it, see Mmu::save
+    pub fn flush_pending(&self) -> bool {
+        self.cartridge.flush_pending()
     }
 
-    pub fn save(&self) {
-        self.cartridge.dump_savegame();
+    /// Runs the boot ROM before the game. The CPU has to start at address 0,
+    /// see Cpu::new_with_boot_rom
+    pub fn set_boot_rom(&mut self, boot_rom: Vec<u8>) {
+        self.boot_rom = boot_rom;
+        self.boot_rom_mapped = true;
+    }
+
+    pub fn cartridge_header(&self) -> &CartridgeHeader {
+        self.cartridge.header()
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        self.timer.save_state(state);
+        self.serial.save_state(state);
+        self.interrupts.save_state(state);
+        state.write_bytes(&self.w_ram);
+        state.write_bytes(&self.h_ram);
+        state.write_u8(self.joypad_select);
+        state.write_u8(self.joypad);
+        state.write_bool(self.stopped);
+        state.write_u64(self.clock_cycles);
+        state.write_u16(self.dma_source_address);
+        state.write_u16(self.dma_offset);
+        self.scheduler.save_state(state);
+        state.write_u64(self.gpu_last_sync);
+        state.write_u64(self.timer_last_sync);
+        state.write_u64(self.apu_last_sync);
+        state.write_bool(self.boot_rom_mapped);
+
+        self.gpu.save_state(state);
+        self.apu.save_state(state);
+        self.cartridge.save_state(state);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.timer.load_state(state)?;
+        self.serial.load_state(state)?;
+        self.interrupts.load_state(state)?;
+        state.read_bytes_into(&mut self.w_ram)?;
+        state.read_bytes_into(&mut self.h_ram)?;
+        self.joypad_select = state.read_u8()?;
+        self.joypad = state.read_u8()?;
+        self.stopped = state.read_bool()?;
+        self.clock_cycles = state.read_u64()?;
+        self.dma_source_address = state.read_u16()?;
+        self.dma_offset = state.read_u16()?;
+        self.scheduler.load_state(state)?;
+        self.gpu_last_sync = state.read_u64()?;
+        self.timer_last_sync = state.read_u64()?;
+        self.apu_last_sync = state.read_u64()?;
+        let boot_rom_mapped = state.read_bool()?;
+        self.boot_rom_mapped = boot_rom_mapped && !self.boot_rom.is_empty();
+
+        self.gpu.load_state(state)?;
+        self.apu.load_state(state)?;
+        self.cartridge.load_state(state)
+    }
+
+    /// Enters the low power mode of the STOP instruction. CPU, LCD and timer are halted
+    /// until one of the selected joypad lines goes low
+    pub fn stop(&mut self) {
+        self.sync_gpu();
+        self.sync_timer();
+        self.collect_interrupts();
+
+        self.stopped = true;
+        self.timer.reset_divider();
+        self.scheduler.cancel(EventType::Gpu);
+        self.scheduler.cancel(EventType::Timer);
+    }
+
+    fn wake_up(&mut self) {
+        self.stopped = false;
+
+        //LCD and timer continue from where they were stopped
+        self.gpu_last_sync = self.clock_cycles;
+        self.timer_last_sync = self.clock_cycles;
+        self.schedule_gpu();
+        self.schedule_timer();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
     }
 
     fn read_joypad(&mut self, joypad: &Joypad) {
-        self.joypad = joypad.read_input(self.joypad_select);
+        let previous_state = self.joypad;
+        self.joypad = match self.gpu.sgb {
+            Some(ref sgb) => sgb.read_joypad(joypad, self.joypad_select),
+            None => joypad.read_input(self.joypad_select),
+        };
+
+        //Interrupt is requested if one of the selected lines goes from high to low
+        if previous_state & !self.joypad & 0x0F != 0 {
+            self.interrupts.fire_interrupt(&Interrupt::Joypad);
+        }
+
+        if self.stopped && self.joypad & 0x0F != 0x0F {
+            self.wake_up();
+        }
     }
 
-    fn dma_transfer(&mut self, source_address: u8) {
+    fn start_dma_transfer(&mut self, source_address: u8) {
         //DMA Transfer starts to OAM
         //Start address = value * 0x100 (value << 8)
         //Destination = OAM
-        //Write everything from start for OAM length
+        //One byte is copied each machine cycle for OAM length
         //OAM Length = 0xA0 (160)
-        let start_address: u16 = (source_address as u16) << 8;
+        self.dma_source_address = (source_address as u16) << 8;
+        self.dma_offset = 0;
+    }
+
+    fn step_dma(&mut self) {
+        if !self.dma_active() {
+            return;
+        }
+
+        let value = self.read(self.dma_source_address + self.dma_offset);
+        self.gpu.write_oam(OAM_ADDRESS + self.dma_offset, value);
+        self.dma_offset += 1;
+    }
+
+    fn dma_active(&self) -> bool {
+        self.dma_offset < OAM_SIZE
+    }
+
+    /// Reads a byte like the CPU does. The system is ticked for one machine cycle before
+    pub fn cycle_read(&mut self, address: u16) -> u8 {
+        self.tick();
 
-        for offset in 0..160 {
-            self.gpu
-                .write_oam(OAM_ADDRESS + offset, self.read(start_address + offset))
+        //The timer and apu registers change without an event being scheduled
+        match address {
+            0xFF04..=0xFF07 => self.sync_timer(),
+            0xFF10..=0xFF3F => self.sync_apu(),
+            _ => {}
         }
+
+        //OAM is not accessible for the CPU during DMA
+        if self.dma_active() && (OAM_ADDRESS..=0xFE9F).contains(&address) {
+            return 0xFF;
+        }
+
+        self.read(address)
+    }
+
+    /// Writes a byte like the CPU does. The system is ticked for one machine cycle before
+    pub fn cycle_write(&mut self, address: u16, value: u8) {
+        self.tick();
+
+        if self.dma_active() && (OAM_ADDRESS..=0xFE9F).contains(&address) {
+            return;
+        }
+
+        self.write(address, value);
+    }
+
+    pub fn cycle_read_word(&mut self, address: u16) -> u16 {
+        let low = self.cycle_read(address);
+        let high = self.cycle_read(address.wrapping_add(1));
+        binary::bytes_to_word(high, low)
+    }
+
+    pub fn cycle_write_word(&mut self, address: u16, value: u16) {
+        self.cycle_write(address, value as u8);
+        self.cycle_write(address.wrapping_add(1), (value >> 8) as u8);
+    }
+
+    /// Pushes a word like the CPU does, the high byte is written first
+    pub fn cycle_push_word(&mut self, sp: &mut u16, value: u16) {
+        *sp = sp.wrapping_sub(1);
+        self.cycle_write(*sp, (value >> 8) as u8);
+        *sp = sp.wrapping_sub(1);
+        self.cycle_write(*sp, value as u8);
     }
 
     pub fn read(&self, address: u16) -> u8 {
         match address {
             W_RAM_ADDRESS..=0xDFFF => self.w_ram[(address - W_RAM_ADDRESS) as usize],
             ECHO_RAM_ADDRESS..=0xFDFF => self.w_ram[(address - ECHO_RAM_ADDRESS) as usize],
+            0..=0xFF if self.boot_rom_mapped && (address as usize) < self.boot_rom.len() => {
+                self.boot_rom[address as usize]
+            }
             0..=0x7FFF => self.cartridge.read(address),
             interrupts::INTERRUPT_FLAGS_ADDRESS => self.interrupts.interrupt_flags,
             VRAM_ADDRESS..=0x9FFF => self.gpu.read_vram(address),
             OAM_ADDRESS..=0xFE9F => self.gpu.read_oam(address),
             EXT_RAM_START_ADDRESS..=0xBFFF => self.cartridge.read_ram(address),
             0xFF00 => self.joypad,
-            0xFF04 => self.timer.divider,
+            0xFF01 => self.serial.data,
+            0xFF02 => self.serial.read_control(),
+            0xFF04 => self.timer.get_divider(),
             0xFF05 => self.timer.counter,
             0xFF06 => self.timer.modulo,
-            0xFF07 => self.timer.timer_control,
+            0xFF07 => self.timer.get_timer_control(),
             0xFF10..=0xFF3F => self.apu.read(address),
             0xFF40 => self.gpu.get_lcdc(),
             0xFF41 => self.gpu.get_stat(),
@@ -120,25 +440,49 @@ impl<'a> Mmu<'a> {
     }
 
     pub fn write(&mut self, address: u16, value: u8) {
+        self.sync_io_register(address);
+        self.write_register(address, value);
+        self.sync_io_register(address);
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
         match address {
             W_RAM_ADDRESS..=0xDFFF => self.w_ram[(address - W_RAM_ADDRESS) as usize] = value,
             EXT_RAM_START_ADDRESS..=0xBFFF => self.cartridge.write_ram(address, value),
             0..=0x7FFF => self.cartridge.write(address, value),
             interrupts::INTERRUPT_FLAGS_ADDRESS => self.interrupts.interrupt_flags = value,
             interrupts::INTERRUPT_ENABLE_ADDRESS => self.interrupts.interrupts_enabled = value,
-            0xFF00 => self.joypad_select = value,
-            0xFF04 => self.timer.divider = 0,
+            0xFF00 => {
+                self.joypad_select = value & 0x30;
+
+                if let Some(ref mut sgb) = self.gpu.sgb {
+                    sgb.write_joypad(value);
+                }
+            }
+            0xFF01 => self.serial.data = value,
+            0xFF02 => {
+                if self.serial.write_control(value) {
+                    self.scheduler.schedule(
+                        EventType::Serial,
+                        self.clock_cycles + serial::TRANSFER_CLOCK_CYCLES,
+                    );
+                } else {
+                    self.scheduler.cancel(EventType::Serial);
+                }
+            }
+            0xFF04 => self.timer.reset_divider(),
+            //The boot ROM disables itself right before it jumps to the game
+            BOOT_ROM_DISABLE_ADDRESS if value != 0 => self.boot_rom_mapped = false,
             0xFF05 => self.timer.counter = value,
             0xFF06 => self.timer.modulo = value,
-            0xFF07 => self.timer.timer_control = value,
+            0xFF07 => self.timer.set_timer_control(value),
             0xFF10..=0xFF3F => self.apu.write(address, value),
             0xFF40 => self.gpu.set_lcdc(value),
             0xFF41 => self.gpu.set_stat(value),
             0xFF42 => self.gpu.scroll_y = value,
             0xFF43 => self.gpu.scroll_x = value,
             0xFF45 => self.gpu.set_lyc(value),
-            //TODO: Implement accurate dma
-            0xFF46 => self.dma_transfer(value),
+            0xFF46 => self.start_dma_transfer(value),
             0xFF47 => self.gpu.set_bg_pal(value),
             0xFF48 => self.gpu.set_sprite_palette0(value),
             0xFF49 => self.gpu.set_sprite_palette1(value),
@@ -165,11 +509,11 @@ impl<'a> Mmu<'a> {
         binary::bytes_to_word(self.read(address + 1), self.read(address))
     }
 
-    pub fn read_opcode(&self, pc: u16) -> Opcode {
-        let op_code = self.read(pc);
+    pub fn read_opcode(&mut self, pc: u16) -> Opcode {
+        let op_code = self.cycle_read(pc);
 
         match op_code {
-            0xCB => Opcode::CB(self.read(pc + 1)),
+            0xCB => Opcode::CB(self.cycle_read(pc.wrapping_add(1))),
             _ => Opcode::Regular(op_code),
         }
     }
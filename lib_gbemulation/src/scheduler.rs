@@ -0,0 +1,143 @@
+use crate::state::{StateReader, StateWriter};
+
+const EVENT_TYPE_COUNT: usize = 4;
+
+#[derive(Copy, Clone)]
+pub enum EventType {
+    Gpu = 0,
+    Timer = 1,
+    Apu = 2,
+    Serial = 3,
+}
+
+const EVENT_TYPES: [EventType; EVENT_TYPE_COUNT] = [
+    EventType::Gpu,
+    EventType::Timer,
+    EventType::Apu,
+    EventType::Serial,
+];
+
+/// Keeps track of the clock cycle at which each component needs to be stepped next.
+/// Every event type can only be scheduled once, rescheduling replaces the previous timestamp
+pub struct Scheduler {
+    events: [Option<u64>; EVENT_TYPE_COUNT],
+    next_timestamp: u64,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler {
+            events: [None; EVENT_TYPE_COUNT],
+            next_timestamp: u64::MAX,
+        }
+    }
+
+    pub fn schedule(&mut self, event_type: EventType, timestamp: u64) {
+        self.events[event_type as usize] = Some(timestamp);
+        //The event can also have been moved to a later timestamp
+        self.update_next_timestamp();
+    }
+
+    pub fn cancel(&mut self, event_type: EventType) {
+        self.events[event_type as usize] = None;
+        self.update_next_timestamp();
+    }
+
+    /// Timestamp of the earliest scheduled event. u64::MAX if nothing is scheduled
+    pub fn next_timestamp(&self) -> u64 {
+        self.next_timestamp
+    }
+
+    /// Removes and returns the earliest event that is due at the given timestamp
+    pub fn pop_due(&mut self, timestamp: u64) -> Option<EventType> {
+        if self.next_timestamp > timestamp {
+            return None;
+        }
+
+        let event_type = EVENT_TYPES
+            .iter()
+            .filter(|event_type| self.events[**event_type as usize].is_some())
+            .min_by_key(|event_type| self.events[**event_type as usize])
+            .copied()?;
+
+        if self.events[event_type as usize]? > timestamp {
+            return None;
+        }
+
+        self.cancel(event_type);
+        Some(event_type)
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        for timestamp in self.events.iter() {
+            state.write_bool(timestamp.is_some());
+            state.write_u64(timestamp.unwrap_or(0));
+        }
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        for timestamp in self.events.iter_mut() {
+            let scheduled = state.read_bool()?;
+            let value = state.read_u64()?;
+            *timestamp = if scheduled { Some(value) } else { None };
+        }
+
+        self.update_next_timestamp();
+        Ok(())
+    }
+
+    fn update_next_timestamp(&mut self) {
+        self.next_timestamp = self
+            .events
+            .iter()
+            .filter_map(|timestamp| *timestamp)
+            .min()
+            .unwrap_or(u64::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_events_in_order() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(EventType::Timer, 200);
+        scheduler.schedule(EventType::Gpu, 100);
+
+        assert_eq!(scheduler.next_timestamp(), 100);
+        assert!(scheduler.pop_due(99).is_none());
+        assert!(matches!(scheduler.pop_due(250), Some(EventType::Gpu)));
+        assert!(matches!(scheduler.pop_due(250), Some(EventType::Timer)));
+        assert!(scheduler.pop_due(250).is_none());
+        assert_eq!(scheduler.next_timestamp(), u64::MAX);
+    }
+
+    #[test]
+    fn rescheduled_event_does_not_fire_early() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(EventType::Serial, 100);
+        scheduler.schedule(EventType::Serial, 4096);
+
+        assert_eq!(scheduler.next_timestamp(), 4096);
+        assert!(scheduler.pop_due(100).is_none());
+        assert!(scheduler.pop_due(4095).is_none());
+        assert!(matches!(scheduler.pop_due(4096), Some(EventType::Serial)));
+    }
+
+    #[test]
+    fn cancelled_event_does_not_fire() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(EventType::Apu, 100);
+        scheduler.cancel(EventType::Apu);
+
+        assert!(scheduler.pop_due(1000).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod packet;
+pub mod sgb;
+
+pub const BORDER_WIDTH: usize = 256;
+pub const BORDER_HEIGHT: usize = 224;
+//Border pixels are RGBA so the screen can be seen through its transparent center
+pub const BORDER_BUFFER_SIZE: usize = BORDER_WIDTH * BORDER_HEIGHT * 4;
+//Position of the game screen inside the border
+pub const BORDER_SCREEN_X: usize = 48;
+pub const BORDER_SCREEN_Y: usize = 40;
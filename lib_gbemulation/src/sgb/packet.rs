@@ -0,0 +1,87 @@
+const PACKET_SIZE: usize = 16;
+const PACKET_BITS: usize = PACKET_SIZE * 8;
+
+/// Decodes the command packets a game sends bit by bit by writing to P1.
+/// A reset pulse (P14 and P15 low) starts a packet. Each bit is sent by pulling P14 low
+/// for a 0 or P15 low for a 1 and releasing both lines afterwards. A 0 stop bit ends the packet
+pub struct PacketReceiver {
+    receiving: bool,
+    released: bool,
+    bit_index: usize,
+    packet: [u8; PACKET_SIZE],
+    command: Vec<u8>,
+}
+
+impl Default for PacketReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketReceiver {
+    pub fn new() -> Self {
+        PacketReceiver {
+            receiving: false,
+            released: false,
+            bit_index: 0,
+            packet: [0; PACKET_SIZE],
+            command: Vec::new(),
+        }
+    }
+
+    /// Returns the data of all packets of a command once its last packet has been received
+    pub fn write(&mut self, value: u8) -> Option<Vec<u8>> {
+        match value & 0x30 {
+            0x00 => {
+                self.receiving = true;
+                self.released = false;
+                self.bit_index = 0;
+                self.packet = [0; PACKET_SIZE];
+                None
+            }
+            0x30 => {
+                self.released = true;
+                None
+            }
+            lines => {
+                if !self.receiving || !self.released {
+                    return None;
+                }
+
+                self.released = false;
+                let bit = lines == 0x10;
+
+                if self.bit_index < PACKET_BITS {
+                    if bit {
+                        self.packet[self.bit_index / 8] |= 1 << (self.bit_index % 8);
+                    }
+
+                    self.bit_index += 1;
+                    return None;
+                }
+
+                self.receiving = false;
+
+                //Packets with an invalid stop bit are dropped
+                if bit {
+                    return None;
+                }
+
+                self.receive_packet()
+            }
+        }
+    }
+
+    fn receive_packet(&mut self) -> Option<Vec<u8>> {
+        self.command.extend_from_slice(&self.packet);
+
+        //The lower three bits of the first byte contain the amount of packets
+        let packet_count = (self.command[0] & 0x07).max(1) as usize;
+
+        if self.command.len() < packet_count * PACKET_SIZE {
+            return None;
+        }
+
+        Some(std::mem::take(&mut self.command))
+    }
+}
@@ -0,0 +1,564 @@
+use crate::gpu::{BUFFER_SIZE, SCREEN_WIDTH};
+use crate::io::joypad::Joypad;
+use crate::sgb::packet::PacketReceiver;
+use crate::sgb::{BORDER_BUFFER_SIZE, BORDER_WIDTH};
+use crate::state::{StateReader, StateWriter};
+
+const COMMAND_PAL01: u8 = 0x00;
+const COMMAND_PAL23: u8 = 0x01;
+const COMMAND_PAL03: u8 = 0x02;
+const COMMAND_PAL12: u8 = 0x03;
+const COMMAND_ATTR_BLK: u8 = 0x04;
+const COMMAND_ATTR_LIN: u8 = 0x05;
+const COMMAND_ATTR_DIV: u8 = 0x06;
+const COMMAND_ATTR_CHR: u8 = 0x07;
+const COMMAND_PAL_SET: u8 = 0x0A;
+const COMMAND_PAL_TRN: u8 = 0x0B;
+const COMMAND_MLT_REQ: u8 = 0x11;
+const COMMAND_CHR_TRN: u8 = 0x13;
+const COMMAND_PCT_TRN: u8 = 0x14;
+const COMMAND_ATTR_TRN: u8 = 0x15;
+const COMMAND_ATTR_SET: u8 = 0x16;
+const COMMAND_MASK_EN: u8 = 0x17;
+
+//The attributes select one of the four palettes for each 8x8 tile of the screen
+const ATTRIBUTE_WIDTH: usize = 20;
+const ATTRIBUTE_HEIGHT: usize = 18;
+const ATTRIBUTE_MAP_SIZE: usize = ATTRIBUTE_WIDTH * ATTRIBUTE_HEIGHT;
+const ATTRIBUTE_FILE_SIZE: usize = ATTRIBUTE_MAP_SIZE / 4;
+const ATTRIBUTE_FILE_COUNT: usize = 45;
+
+const SYSTEM_PALETTE_COUNT: usize = 512;
+//VRAM transfers copy 4KB from the tiles displayed on screen
+const TRANSFER_SIZE: usize = 4096;
+const BORDER_TILE_SIZE: usize = 32;
+const BORDER_MAP_WIDTH: usize = 32;
+const BORDER_MAP_HEIGHT: usize = 28;
+const BORDER_MAP_SIZE: usize = 0x800;
+
+#[derive(Copy, Clone, PartialEq)]
+enum MaskMode {
+    Cancel,
+    Freeze,
+    Black,
+    Color0,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Transfer {
+    BorderTiles(usize),
+    BorderMap,
+    Palettes,
+    Attributes,
+}
+
+/// Super Game Boy functions. Commands are received through the joypad register and change
+/// the palettes used to colorize the screen or load the border around it
+pub struct Sgb {
+    packet_receiver: PacketReceiver,
+    palettes: [[u16; 4]; 4],
+    system_palettes: Vec<u16>,
+    attribute_map: [u8; ATTRIBUTE_MAP_SIZE],
+    attribute_files: Vec<u8>,
+    mask_mode: MaskMode,
+    player_count: u8,
+    current_player: u8,
+    joypad_select: u8,
+    pending_transfer: Option<Transfer>,
+    border_tiles: Vec<u8>,
+    border_map: Vec<u8>,
+    border_palettes: [[u16; 16]; 4],
+    border_changed: bool,
+}
+
+impl Default for Sgb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sgb {
+    pub fn new() -> Self {
+        Sgb {
+            packet_receiver: PacketReceiver::new(),
+            //Grayscale until the game sets its own palettes
+            palettes: [[0x7FFF, 0x56B5, 0x294A, 0x0000]; 4],
+            system_palettes: vec![0; SYSTEM_PALETTE_COUNT * 4],
+            attribute_map: [0; ATTRIBUTE_MAP_SIZE],
+            attribute_files: vec![0; ATTRIBUTE_FILE_COUNT * ATTRIBUTE_FILE_SIZE],
+            mask_mode: MaskMode::Cancel,
+            player_count: 1,
+            current_player: 0,
+            joypad_select: 0x30,
+            pending_transfer: None,
+            border_tiles: vec![0; 2 * TRANSFER_SIZE],
+            border_map: vec![0; BORDER_MAP_SIZE],
+            border_palettes: [[0; 16]; 4],
+            border_changed: false,
+        }
+    }
+
+    /// Packets and VRAM transfers in progress are not part of the state
+    pub fn save_state(&self, state: &mut StateWriter) {
+        for palette in self.palettes.iter() {
+            palette.iter().for_each(|color| state.write_u16(*color));
+        }
+
+        self.system_palettes
+            .iter()
+            .for_each(|color| state.write_u16(*color));
+        state.write_bytes(&self.attribute_map);
+        state.write_bytes(&self.attribute_files);
+        state.write_u8(match self.mask_mode {
+            MaskMode::Cancel => 0,
+            MaskMode::Freeze => 1,
+            MaskMode::Black => 2,
+            MaskMode::Color0 => 3,
+        });
+        state.write_u8(self.player_count);
+        state.write_u8(self.current_player);
+        state.write_u8(self.joypad_select);
+        state.write_bytes(&self.border_tiles);
+        state.write_bytes(&self.border_map);
+
+        for palette in self.border_palettes.iter() {
+            palette.iter().for_each(|color| state.write_u16(*color));
+        }
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        for palette in self.palettes.iter_mut() {
+            for color in palette.iter_mut() {
+                *color = state.read_u16()?;
+            }
+        }
+
+        for color in self.system_palettes.iter_mut() {
+            *color = state.read_u16()?;
+        }
+
+        state.read_bytes_into(&mut self.attribute_map)?;
+        state.read_bytes_into(&mut self.attribute_files)?;
+        self.mask_mode = match state.read_u8()? {
+            1 => MaskMode::Freeze,
+            2 => MaskMode::Black,
+            3 => MaskMode::Color0,
+            _ => MaskMode::Cancel,
+        };
+        self.player_count = state.read_u8()?.max(1);
+        self.current_player = state.read_u8()? % self.player_count;
+        self.joypad_select = state.read_u8()?;
+        state.read_bytes_into(&mut self.border_tiles)?;
+        state.read_bytes_into(&mut self.border_map)?;
+
+        for palette in self.border_palettes.iter_mut() {
+            for color in palette.iter_mut() {
+                *color = state.read_u16()?;
+            }
+        }
+
+        self.packet_receiver = PacketReceiver::new();
+        self.pending_transfer = None;
+        self.border_changed = true;
+        Ok(())
+    }
+
+    pub fn write_joypad(&mut self, value: u8) {
+        //With multiple controllers the next one is selected when P15 goes high
+        if self.player_count > 1 && value & 0x20 != 0 && self.joypad_select & 0x20 == 0 {
+            self.current_player = (self.current_player + 1) % self.player_count;
+        }
+
+        self.joypad_select = value & 0x30;
+
+        if let Some(command) = self.packet_receiver.write(value) {
+            self.execute_command(&command);
+        }
+    }
+
+    pub fn read_joypad(&self, joypad: &Joypad, select: u8) -> u8 {
+        //Deselecting both groups returns the id of the current controller
+        if self.player_count > 1 && select & 0x30 == 0x30 {
+            return 0xF0 | (0x0F - self.current_player);
+        }
+
+        //Only the first controller is connected
+        if self.current_player != 0 {
+            return 0xC0 | (select & 0x30) | 0x0F;
+        }
+
+        joypad.read_input(select)
+    }
+
+    /// Performs a pending VRAM transfer using the color numbers of the displayed frame
+    pub fn update(&mut self, shade_buffer: &[u8]) {
+        let transfer = match self.pending_transfer.take() {
+            Some(transfer) => transfer,
+            None => return,
+        };
+
+        let data = encode_tiles(shade_buffer);
+
+        match transfer {
+            Transfer::BorderTiles(offset) => {
+                self.border_tiles[offset..offset + TRANSFER_SIZE].copy_from_slice(&data);
+                self.border_changed = true;
+            }
+            Transfer::BorderMap => {
+                self.border_map.copy_from_slice(&data[..BORDER_MAP_SIZE]);
+
+                //The palettes follow the map
+                let colors = self.border_palettes.iter_mut().flatten();
+                for (index, color) in colors.enumerate() {
+                    *color = read_word(&data, BORDER_MAP_SIZE + index * 2);
+                }
+
+                self.border_changed = true;
+            }
+            Transfer::Palettes => {
+                for (index, color) in self.system_palettes.iter_mut().enumerate() {
+                    *color = read_word(&data, index * 2);
+                }
+            }
+            Transfer::Attributes => {
+                let size = self.attribute_files.len();
+                self.attribute_files.copy_from_slice(&data[..size]);
+            }
+        }
+    }
+
+    /// Returns the RGBA pixels of the border if it changed since the last call
+    pub fn take_border(&mut self) -> Option<Vec<u8>> {
+        if !self.border_changed {
+            return None;
+        }
+
+        self.border_changed = false;
+        Some(self.render_border())
+    }
+
+    /// Colorizes the shades of the frame using the palettes of the attribute map.
+    /// Returns false if the screen is frozen and the frame should not be displayed
+    pub fn colorize(&self, shade_buffer: &[u8], screen_buffer: &mut [u8; BUFFER_SIZE]) -> bool {
+        match self.mask_mode {
+            MaskMode::Freeze => return false,
+            MaskMode::Black => {
+                screen_buffer.fill(0);
+                return true;
+            }
+            _ => {}
+        }
+
+        for (index, shade) in shade_buffer.iter().enumerate() {
+            let x = index % SCREEN_WIDTH;
+            let y = index / SCREEN_WIDTH;
+
+            let color = if self.mask_mode == MaskMode::Color0 {
+                self.palettes[0][0]
+            } else {
+                let palette = self.attribute_map[(y / 8) * ATTRIBUTE_WIDTH + x / 8];
+                self.palettes[palette as usize][*shade as usize]
+            };
+
+            screen_buffer[index * 3..index * 3 + 3].copy_from_slice(&to_rgb(color));
+        }
+
+        true
+    }
+
+    fn execute_command(&mut self, data: &[u8]) {
+        match data[0] >> 3 {
+            COMMAND_PAL01 => self.set_palette_pair(0, 1, data),
+            COMMAND_PAL23 => self.set_palette_pair(2, 3, data),
+            COMMAND_PAL03 => self.set_palette_pair(0, 3, data),
+            COMMAND_PAL12 => self.set_palette_pair(1, 2, data),
+            COMMAND_ATTR_BLK => self.set_attribute_blocks(data),
+            COMMAND_ATTR_LIN => self.set_attribute_lines(data),
+            COMMAND_ATTR_DIV => self.set_attribute_division(data),
+            COMMAND_ATTR_CHR => self.set_attribute_characters(data),
+            COMMAND_PAL_SET => self.set_system_palettes(data),
+            COMMAND_PAL_TRN => self.pending_transfer = Some(Transfer::Palettes),
+            COMMAND_MLT_REQ => {
+                self.player_count = match data[1] & 0x03 {
+                    0x01 => 2,
+                    0x03 => 4,
+                    _ => 1,
+                };
+                self.current_player = 0;
+            }
+            COMMAND_CHR_TRN => {
+                //Bit 0 selects if tiles 0x00-0x7F or 0x80-0xFF are transferred
+                let offset = (data[1] & 0x01) as usize * TRANSFER_SIZE;
+                self.pending_transfer = Some(Transfer::BorderTiles(offset));
+            }
+            COMMAND_PCT_TRN => self.pending_transfer = Some(Transfer::BorderMap),
+            COMMAND_ATTR_TRN => self.pending_transfer = Some(Transfer::Attributes),
+            COMMAND_ATTR_SET => {
+                self.apply_attribute_file((data[1] & 0x3F) as usize);
+
+                if data[1] & 0x40 == 0x40 {
+                    self.mask_mode = MaskMode::Cancel;
+                }
+            }
+            COMMAND_MASK_EN => {
+                self.mask_mode = match data[1] & 0x03 {
+                    0x01 => MaskMode::Freeze,
+                    0x02 => MaskMode::Black,
+                    0x03 => MaskMode::Color0,
+                    _ => MaskMode::Cancel,
+                };
+            }
+            //Sound, SNES program and other commands are not supported
+            _ => {}
+        }
+    }
+
+    fn set_palette_pair(&mut self, first: usize, second: usize, data: &[u8]) {
+        for color in 1..4 {
+            self.palettes[first][color] = read_word(data, 1 + color * 2);
+            self.palettes[second][color] = read_word(data, 7 + color * 2);
+        }
+
+        self.set_shared_color(read_word(data, 1));
+    }
+
+    //Color 0 is shared between all palettes
+    fn set_shared_color(&mut self, color: u16) {
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color;
+        }
+    }
+
+    fn set_system_palettes(&mut self, data: &[u8]) {
+        for index in 0..4 {
+            let number = (read_word(data, 1 + index * 2) & 0x1FF) as usize;
+            self.palettes[index].copy_from_slice(&self.system_palettes[number * 4..number * 4 + 4]);
+        }
+
+        self.set_shared_color(self.palettes[0][0]);
+
+        let attributes = data[9];
+
+        if attributes & 0x80 == 0x80 {
+            self.apply_attribute_file((attributes & 0x3F) as usize);
+        }
+
+        if attributes & 0x40 == 0x40 {
+            self.mask_mode = MaskMode::Cancel;
+        }
+    }
+
+    fn set_attribute_blocks(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+
+        for block in data[2..].chunks_exact(6).take(count) {
+            let control = block[0] & 0x07;
+            let inside_palette = block[1] & 0x03;
+            let outside_palette = (block[1] >> 4) & 0x03;
+
+            //If only the inside or the outside is changed the border takes the same palette
+            let (change_border, border_palette) = match control {
+                0x01 => (true, inside_palette),
+                0x04 => (true, outside_palette),
+                _ => (control & 0x02 == 0x02, (block[1] >> 2) & 0x03),
+            };
+
+            let (x1, y1, x2, y2) = (
+                block[2] as usize,
+                block[3] as usize,
+                block[4] as usize,
+                block[5] as usize,
+            );
+
+            for y in 0..ATTRIBUTE_HEIGHT {
+                for x in 0..ATTRIBUTE_WIDTH {
+                    let inside_area = x >= x1 && x <= x2 && y >= y1 && y <= y2;
+                    let on_border = inside_area && (x == x1 || x == x2 || y == y1 || y == y2);
+
+                    let palette = if on_border {
+                        if !change_border {
+                            continue;
+                        }
+                        border_palette
+                    } else if inside_area {
+                        if control & 0x01 == 0 {
+                            continue;
+                        }
+                        inside_palette
+                    } else {
+                        if control & 0x04 == 0 {
+                            continue;
+                        }
+                        outside_palette
+                    };
+
+                    self.attribute_map[y * ATTRIBUTE_WIDTH + x] = palette;
+                }
+            }
+        }
+    }
+
+    fn set_attribute_lines(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+
+        for value in data[2..].iter().take(count) {
+            let line = (value & 0x1F) as usize;
+            let palette = (value >> 5) & 0x03;
+
+            //Bit 7 selects a horizontal line, otherwise it is a vertical one
+            if value & 0x80 == 0x80 {
+                if line < ATTRIBUTE_HEIGHT {
+                    let start = line * ATTRIBUTE_WIDTH;
+                    self.attribute_map[start..start + ATTRIBUTE_WIDTH].fill(palette);
+                }
+            } else if line < ATTRIBUTE_WIDTH {
+                for y in 0..ATTRIBUTE_HEIGHT {
+                    self.attribute_map[y * ATTRIBUTE_WIDTH + line] = palette;
+                }
+            }
+        }
+    }
+
+    fn set_attribute_division(&mut self, data: &[u8]) {
+        let palettes = data[1];
+        let horizontal = palettes & 0x40 == 0x40;
+        let coordinate = data[2] as usize;
+
+        for y in 0..ATTRIBUTE_HEIGHT {
+            for x in 0..ATTRIBUTE_WIDTH {
+                let position = if horizontal { y } else { x };
+
+                //Left or above, on the line and right or below the division
+                let palette = match position.cmp(&coordinate) {
+                    std::cmp::Ordering::Less => (palettes >> 2) & 0x03,
+                    std::cmp::Ordering::Equal => (palettes >> 4) & 0x03,
+                    std::cmp::Ordering::Greater => palettes & 0x03,
+                };
+
+                self.attribute_map[y * ATTRIBUTE_WIDTH + x] = palette;
+            }
+        }
+    }
+
+    fn set_attribute_characters(&mut self, data: &[u8]) {
+        let mut x = data[1] as usize;
+        let mut y = data[2] as usize;
+        let count = (read_word(data, 3) as usize).min(ATTRIBUTE_MAP_SIZE);
+        let vertical = data[5] & 0x01 == 0x01;
+
+        for index in 0..count {
+            let value = match data.get(6 + index / 4) {
+                Some(value) => *value,
+                None => break,
+            };
+
+            //Four palettes per byte starting with the upper bits
+            let palette = (value >> (6 - (index % 4) * 2)) & 0x03;
+
+            if x < ATTRIBUTE_WIDTH && y < ATTRIBUTE_HEIGHT {
+                self.attribute_map[y * ATTRIBUTE_WIDTH + x] = palette;
+            }
+
+            if vertical {
+                y += 1;
+                if y >= ATTRIBUTE_HEIGHT {
+                    y = 0;
+                    x += 1;
+                }
+            } else {
+                x += 1;
+                if x >= ATTRIBUTE_WIDTH {
+                    x = 0;
+                    y += 1;
+                }
+            }
+        }
+    }
+
+    fn apply_attribute_file(&mut self, file: usize) {
+        if file >= ATTRIBUTE_FILE_COUNT {
+            return;
+        }
+
+        let start = file * ATTRIBUTE_FILE_SIZE;
+        let attribute_file = &self.attribute_files[start..start + ATTRIBUTE_FILE_SIZE];
+
+        for (index, attribute) in self.attribute_map.iter_mut().enumerate() {
+            *attribute = (attribute_file[index / 4] >> (6 - (index % 4) * 2)) & 0x03;
+        }
+    }
+
+    fn render_border(&self) -> Vec<u8> {
+        let mut buffer = vec![0; BORDER_BUFFER_SIZE];
+
+        for map_y in 0..BORDER_MAP_HEIGHT {
+            for map_x in 0..BORDER_MAP_WIDTH {
+                let entry = read_word(&self.border_map, (map_y * BORDER_MAP_WIDTH + map_x) * 2);
+                let tile = &self.border_tiles[(entry & 0xFF) as usize * BORDER_TILE_SIZE..]
+                    [..BORDER_TILE_SIZE];
+                let palette = &self.border_palettes[((entry >> 10) & 0x03) as usize];
+                let flip_x = entry & 0x4000 == 0x4000;
+                let flip_y = entry & 0x8000 == 0x8000;
+
+                for y in 0..8 {
+                    for x in 0..8 {
+                        let tile_y = if flip_y { 7 - y } else { y };
+                        let bit = if flip_x { x } else { 7 - x };
+
+                        //SNES tiles have four bitplanes, the upper two follow the lower two
+                        let color = (0..4).fold(0, |color, plane| {
+                            let address = (plane / 2) * 16 + tile_y * 2 + plane % 2;
+                            color | ((tile[address] >> bit) & 0x01) << plane
+                        });
+
+                        //Color 0 is transparent
+                        if color == 0 {
+                            continue;
+                        }
+
+                        let offset = ((map_y * 8 + y) * BORDER_WIDTH + map_x * 8 + x) * 4;
+                        buffer[offset..offset + 3]
+                            .copy_from_slice(&to_rgb(palette[color as usize]));
+                        buffer[offset + 3] = 0xFF;
+                    }
+                }
+            }
+        }
+
+        buffer
+    }
+}
+
+/// Converts the color numbers of the screen back into tiles. The game displays the data
+/// to transfer as 20 tiles per row starting in the upper left corner
+fn encode_tiles(shade_buffer: &[u8]) -> Vec<u8> {
+    let mut data = vec![0; TRANSFER_SIZE];
+
+    for (tile, tile_data) in data.chunks_exact_mut(16).enumerate() {
+        let tile_x = (tile % ATTRIBUTE_WIDTH) * 8;
+        let tile_y = (tile / ATTRIBUTE_WIDTH) * 8;
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let shade = shade_buffer[(tile_y + y) * SCREEN_WIDTH + tile_x + x];
+                tile_data[y * 2] |= (shade & 0x01) << (7 - x);
+                tile_data[y * 2 + 1] |= ((shade >> 1) & 0x01) << (7 - x);
+            }
+        }
+    }
+
+    data
+}
+
+fn read_word(data: &[u8], index: usize) -> u16 {
+    let low = data.get(index).copied().unwrap_or(0);
+    let high = data.get(index + 1).copied().unwrap_or(0);
+    (high as u16) << 8 | low as u16
+}
+
+/// Converts a 15 bit SNES color to RGB
+fn to_rgb(color: u16) -> [u8; 3] {
+    let expand = |value: u16| ((value & 0x1F) << 3 | (value & 0x1F) >> 2) as u8;
+
+    [expand(color), expand(color >> 5), expand(color >> 10)]
+}
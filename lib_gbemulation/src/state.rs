@@ -0,0 +1,164 @@
+use crate::cpu::cpu::Cpu;
+use crate::memory::mmu::Mmu;
+
+const STATE_MAGIC: &[u8; 4] = b"GBST";
+//States of other versions can not be loaded
+const STATE_VERSION: u8 = 1;
+
+/// Serializes the state of the components into a little endian byte stream
+pub struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl Default for StateWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateWriter {
+    pub fn new() -> Self {
+        StateWriter { data: Vec::new() }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes the length followed by the bytes
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u32(bytes.len() as u32);
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Reads values in the same order they have been written by the StateWriter
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        StateReader { data, position: 0 }
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, String> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, String> {
+        let mut bytes = [0; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, String> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, String> {
+        let length = self.read_u32()? as usize;
+        Ok(self.take(length)?.to_vec())
+    }
+
+    /// Reads bytes into a buffer which has to be as long as the stored bytes
+    pub fn read_bytes_into(&mut self, buffer: &mut [u8]) -> Result<(), String> {
+        let length = self.read_u32()? as usize;
+
+        if length != buffer.len() {
+            return Err(format!(
+                "Save state contains {} bytes where {} bytes were expected",
+                length,
+                buffer.len()
+            ));
+        }
+
+        buffer.copy_from_slice(self.take(length)?);
+        Ok(())
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.data.len() - self.position < length {
+            return Err("Save state is truncated".to_string());
+        }
+
+        let bytes = &self.data[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+}
+
+/// Saves the state of the whole machine. The state can only be loaded for the same game
+pub fn save_state(cpu: &Cpu, mmu: &Mmu) -> Vec<u8> {
+    let mut state = StateWriter::new();
+    let header = mmu.cartridge_header();
+
+    state.data.extend_from_slice(STATE_MAGIC);
+    state.write_u8(STATE_VERSION);
+    state.write_bytes(header.title.as_bytes());
+    state.write_u16(header.global_checksum);
+
+    cpu.save_state(&mut state);
+    mmu.save_state(&mut state);
+    state.into_data()
+}
+
+pub fn load_state(cpu: &mut Cpu, mmu: &mut Mmu, data: &[u8]) -> Result<(), String> {
+    let mut state = StateReader::new(data);
+
+    if state.take(STATE_MAGIC.len())? != STATE_MAGIC {
+        return Err("File is not a save state".to_string());
+    }
+
+    let version = state.read_u8()?;
+    if version != STATE_VERSION {
+        return Err(format!("Unsupported save state version: {}", version));
+    }
+
+    let header = mmu.cartridge_header();
+    let title = state.read_bytes()?;
+    let global_checksum = state.read_u16()?;
+
+    if title != header.title.as_bytes() || global_checksum != header.global_checksum {
+        return Err(format!(
+            "Save state belongs to a different game: {}",
+            String::from_utf8_lossy(&title)
+        ));
+    }
+
+    cpu.load_state(&mut state)?;
+    mmu.load_state(&mut state)
+}